@@ -0,0 +1,56 @@
+/** An installed browser. */
+export interface Browser {
+  /** The browser key, e.g. "chrome" or "firefox-nightly". */
+  browserType: string
+  /** The executable or app bundle. */
+  path: string
+  /** The Exec line of the Linux desktop entry the browser is launched with, null if there is none. */
+  exec?: string | null
+  displayName: string
+  /** The version as reported by the browser, empty if unknown. */
+  version: string
+  /** The version as read from the executable or bundle, null if it is the same as `version`. */
+  rawVersion?: string | null
+  /** Where the browser was found, e.g. "system" or "ms-store". */
+  installSource: string
+  /** The bundle identifier, registry subkey or desktop entry ID, null if unknown. */
+  sourceId?: string | null
+  /** The app bundle, package directory or desktop entry file, null if unknown. */
+  sourcePath?: string | null
+  /** The package manager's identifier for the install, e.g. the Homebrew cask "firefox", null if unknown. */
+  package?: string | null
+}
+/**
+ * Lists installed browsers, optionally filtered by type and version.
+ *
+ * `type` and `exclude` take a glob or an array of globs. `version` is a glob such as "120*" or a
+ * requirement such as ">=120". An invalid glob or requirement throws with the code "InvalidArg".
+ */
+export declare function browsers(
+  type?: string | Array<string> | undefined | null,
+  version?: string | undefined | null,
+  exclude?: string | Array<string> | undefined | null,
+): Array<Browser>
+/** Returns the information for the provided browser key, null if it is not installed. */
+export declare function get(browser: string, version?: string | undefined | null): Browser | null
+/** Launches a web browser and returns a handle to its process. */
+export declare function launch(
+  browser: string,
+  version?: string | undefined | null,
+  url?: string | undefined | null,
+  args?: Array<string> | undefined | null,
+): LaunchedBrowser
+/** A browser started by `launch()`. */
+export declare class LaunchedBrowser {
+  /** The process ID of the launched browser. */
+  get pid(): number
+  /** The information of the launched browser. */
+  get browser(): Browser
+  /** Kills the browser without letting it save its profile, see `close()`. */
+  kill(): void
+  /**
+   * Asks the browser to quit, killing it if it is still running after `timeout` seconds, and returns
+   * its exit code, -1 if it was ended by a signal.
+   */
+  close(timeout?: number | undefined | null): number
+}
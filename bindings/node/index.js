@@ -0,0 +1,25 @@
+// Loads the addon built for this platform, either next to this file after `npm run build` or from the
+// package with the prebuilt binary, e.g. `rsbrowsers-linux-x64-gnu`.
+const { existsSync } = require('fs')
+const { join } = require('path')
+
+function platformSuffix() {
+  const { platform, arch } = process
+  switch (platform) {
+    case 'darwin':
+      return `darwin-${arch}`
+    case 'win32':
+      return `win32-${arch}-msvc`
+    case 'linux': {
+      const musl = !process.report.getReport().header.glibcVersionRuntime
+      return `linux-${arch}-${musl ? 'musl' : 'gnu'}`
+    }
+    default:
+      throw new Error(`rsbrowsers does not support ${platform} on ${arch}`)
+  }
+}
+
+const suffix = platformSuffix()
+const local = join(__dirname, `rsbrowsers.${suffix}.node`)
+
+module.exports = require(existsSync(local) ? local : `rsbrowsers-${suffix}`)
@@ -0,0 +1,165 @@
+//! Node.js bindings with the functions of the Python module, `browsers()`, `get()` and `launch()`.
+
+use napi::{Either, Error, Result, Status};
+use napi_derive::napi;
+use std::time::Duration;
+
+/// The seconds `close()` waits for by default.
+const CLOSE_TIMEOUT: f64 = 10.0;
+
+/// An installed browser.
+#[napi(object)]
+pub struct Browser {
+    /// The browser key, e.g. "chrome" or "firefox-nightly".
+    pub browser_type: String,
+    /// The executable or app bundle.
+    pub path: String,
+    /// The Exec line of the Linux desktop entry the browser is launched with, null if there is none.
+    pub exec: Option<String>,
+    pub display_name: String,
+    /// The version as reported by the browser, empty if unknown.
+    pub version: String,
+    /// The version as read from the executable or bundle, null if it is the same as `version`.
+    pub raw_version: Option<String>,
+    /// Where the browser was found, e.g. "system" or "ms-store".
+    pub install_source: String,
+    /// The bundle identifier, registry subkey or desktop entry ID, null if unknown.
+    pub source_id: Option<String>,
+    /// The app bundle, package directory or desktop entry file, null if unknown.
+    pub source_path: Option<String>,
+    /// The package manager's identifier for the install, e.g. the Homebrew cask "firefox", null if unknown.
+    pub package: Option<String>,
+}
+
+impl From<&rsbrowsers::Browser> for Browser {
+    fn from(browser: &rsbrowsers::Browser) -> Self {
+        Browser {
+            browser_type: browser.browser_type.clone(),
+            path: browser.path.to_string_lossy().to_string(),
+            exec: browser.exec.clone(),
+            display_name: browser.display_name.clone(),
+            version: browser.version.clone(),
+            raw_version: browser.raw_version.clone(),
+            install_source: browser.install_source.to_string(),
+            source_id: browser.source_id.clone(),
+            source_path: browser.source_path.as_ref().map(|path| path.to_string_lossy().to_string()),
+            package: browser.package.clone(),
+        }
+    }
+}
+
+fn to_js_err(error: rsbrowsers::Error) -> Error {
+    match error {
+        rsbrowsers::Error::Pattern(_) | rsbrowsers::Error::VersionReq(_) => {
+            Error::new(Status::InvalidArg, error.to_string())
+        }
+        _ => Error::from_reason(error.to_string()),
+    }
+}
+
+fn launch_to_js_err(error: rsbrowsers::LaunchError) -> Error {
+    match error {
+        rsbrowsers::LaunchError::Discovery(error) => to_js_err(error),
+        _ => Error::from_reason(error.to_string()),
+    }
+}
+
+fn into_vec(patterns: Option<Either<String, Vec<String>>>) -> Vec<String> {
+    match patterns {
+        Some(Either::A(pattern)) => vec![pattern],
+        Some(Either::B(patterns)) => patterns,
+        None => vec![],
+    }
+}
+
+/// Lists installed browsers, optionally filtered by type and version.
+///
+/// `type` and `exclude` take a glob or an array of globs. `version` is a glob such as "120*" or a
+/// requirement such as ">=120". An invalid glob or requirement throws with the code "InvalidArg".
+#[napi]
+pub fn browsers(
+    types: Option<Either<String, Vec<String>>>,
+    version: Option<String>,
+    exclude: Option<Either<String, Vec<String>>>,
+) -> Result<Vec<Browser>> {
+    let mut finder = rsbrowsers::BrowserFinder::new().with_types(into_vec(types)).exclude_types(into_vec(exclude));
+    if let Some(version) = version {
+        // requirements start with an operator, anything else is treated as a glob
+        finder = if version.starts_with(['<', '>', '=', '^', '~']) {
+            finder.with_version_req(&version)
+        } else {
+            finder.with_version(version)
+        };
+    }
+    Ok(finder.try_all().map_err(to_js_err)?.map(|browser| Browser::from(&browser)).collect())
+}
+
+/// Returns the information for the provided browser key, null if it is not installed.
+#[napi]
+pub fn get(browser: String, version: Option<String>) -> Result<Option<Browser>> {
+    let mut browsers = rsbrowsers::BrowserFinder::new()
+        .with_type(browser)
+        .with_version(version.unwrap_or_else(|| "*".to_string()))
+        .try_all()
+        .map_err(to_js_err)?;
+    Ok(browsers.next().map(|browser| Browser::from(&browser)))
+}
+
+/// A browser started by `launch()`.
+#[napi]
+pub struct LaunchedBrowser {
+    launched: rsbrowsers::LaunchedBrowser,
+    browser: rsbrowsers::Browser,
+}
+
+#[napi]
+impl LaunchedBrowser {
+    /// The process ID of the launched browser.
+    #[napi(getter)]
+    pub fn pid(&self) -> u32 {
+        self.launched.id()
+    }
+
+    /// The information of the launched browser.
+    #[napi(getter)]
+    pub fn browser(&self) -> Browser {
+        Browser::from(&self.browser)
+    }
+
+    /// Kills the browser without letting it save its profile, see `close()`.
+    #[napi]
+    pub fn kill(&mut self) -> Result<()> {
+        self.launched.kill().map_err(|error| Error::from_reason(error.to_string()))
+    }
+
+    /// Asks the browser to quit, killing it if it is still running after `timeout` seconds, and returns
+    /// its exit code, -1 if it was ended by a signal.
+    #[napi]
+    pub fn close(&mut self, timeout: Option<f64>) -> Result<i32> {
+        let timeout = timeout.unwrap_or(CLOSE_TIMEOUT);
+        let timeout = Duration::try_from_secs_f64(timeout)
+            .map_err(|error| Error::new(Status::InvalidArg, format!("invalid timeout {timeout}: {error}")))?;
+        let status = self.launched.close(timeout).map_err(|error| Error::from_reason(error.to_string()))?;
+        Ok(status.code().unwrap_or(-1))
+    }
+}
+
+/// Launches a web browser and returns a handle to its process.
+#[napi]
+pub fn launch(
+    browser: String,
+    version: Option<String>,
+    url: Option<String>,
+    args: Option<Vec<String>>,
+) -> Result<LaunchedBrowser> {
+    let mut finder = rsbrowsers::BrowserFinder::new().with_type(browser);
+    if let Some(version) = version {
+        finder = finder.with_version(version);
+    }
+    let mut options = rsbrowsers::LaunchOptions::new().args(args.unwrap_or_default());
+    if let Some(url) = url {
+        options = options.url(url);
+    }
+    let (launched, browser) = finder.launch(&options).map_err(launch_to_js_err)?;
+    Ok(LaunchedBrowser { launched, browser })
+}
@@ -0,0 +1,233 @@
+//! Swift and Kotlin bindings, generated with UniFFI from the library. `Browser` is a record, the finder
+//! and launched browsers are objects.
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+uniffi::setup_scaffolding!();
+
+/// An installed browser.
+#[derive(uniffi::Record, Clone, PartialEq, Eq, Debug)]
+pub struct Browser {
+    /// The browser key, e.g. "chrome" or "firefox-nightly".
+    pub browser_type: String,
+    /// The executable or app bundle.
+    pub path: String,
+    /// The Exec line of the Linux desktop entry the browser is launched with, null if there is none.
+    pub exec: Option<String>,
+    pub display_name: String,
+    /// The version as reported by the browser, empty if unknown.
+    pub version: String,
+    /// The version as read from the executable or bundle, null if it is the same as `version`.
+    pub raw_version: Option<String>,
+    /// Where the browser was found, e.g. "system" or "ms-store".
+    pub install_source: String,
+    /// The bundle identifier, registry subkey or desktop entry ID, null if unknown.
+    pub source_id: Option<String>,
+    /// The app bundle, package directory or desktop entry file, null if unknown.
+    pub source_path: Option<String>,
+    /// The package manager's identifier for the install, e.g. the Homebrew cask "firefox", null if unknown.
+    pub package: Option<String>,
+}
+
+impl From<&rsbrowsers::Browser> for Browser {
+    fn from(browser: &rsbrowsers::Browser) -> Self {
+        Browser {
+            browser_type: browser.browser_type.clone(),
+            path: browser.path.to_string_lossy().to_string(),
+            exec: browser.exec.clone(),
+            display_name: browser.display_name.clone(),
+            version: browser.version.clone(),
+            raw_version: browser.raw_version.clone(),
+            install_source: browser.install_source.to_string(),
+            source_id: browser.source_id.clone(),
+            source_path: browser.source_path.as_ref().map(|path| path.to_string_lossy().to_string()),
+            package: browser.package.clone(),
+        }
+    }
+}
+
+/// Why a scan or launch failed.
+#[derive(uniffi::Error, Clone, PartialEq, Eq, Debug)]
+pub enum BrowsersError {
+    /// No installed browser matches the query.
+    NotFound,
+    /// A type, version or exclude pattern could not be parsed.
+    InvalidPattern { message: String },
+    /// A version requirement such as `>=abc` could not be parsed.
+    InvalidVersionReq { message: String },
+    /// The platform's browser sources could not be read.
+    Discovery { message: String },
+    /// The browser was found but could not be started or stopped.
+    Launch { message: String },
+    /// An argument is out of range, e.g. a negative timeout.
+    InvalidArgument { message: String },
+}
+
+impl fmt::Display for BrowsersError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BrowsersError::NotFound => write!(f, "browser not found"),
+            BrowsersError::InvalidPattern { message }
+            | BrowsersError::InvalidVersionReq { message }
+            | BrowsersError::Discovery { message }
+            | BrowsersError::Launch { message }
+            | BrowsersError::InvalidArgument { message } => write!(f, "{message}"),
+        }
+    }
+}
+
+impl std::error::Error for BrowsersError {}
+
+impl From<rsbrowsers::Error> for BrowsersError {
+    fn from(error: rsbrowsers::Error) -> Self {
+        match error {
+            rsbrowsers::Error::Pattern(_) => BrowsersError::InvalidPattern { message: error.to_string() },
+            rsbrowsers::Error::VersionReq(_) => BrowsersError::InvalidVersionReq { message: error.to_string() },
+            rsbrowsers::Error::Discovery(_) => BrowsersError::Discovery { message: error.to_string() },
+        }
+    }
+}
+
+impl From<rsbrowsers::LaunchError> for BrowsersError {
+    fn from(error: rsbrowsers::LaunchError) -> Self {
+        match error {
+            rsbrowsers::LaunchError::NotFound => BrowsersError::NotFound,
+            rsbrowsers::LaunchError::Discovery(error) => error.into(),
+            _ => BrowsersError::Launch { message: error.to_string() },
+        }
+    }
+}
+
+/// Finds installed browsers. Every filter returns a new finder, so one can be shared and refined.
+#[derive(uniffi::Object)]
+pub struct BrowserFinder(rsbrowsers::BrowserFinder);
+
+impl BrowserFinder {
+    fn with(&self, filter: impl FnOnce(rsbrowsers::BrowserFinder) -> rsbrowsers::BrowserFinder) -> Arc<Self> {
+        Arc::new(BrowserFinder(filter(self.0.clone())))
+    }
+}
+
+#[uniffi::export]
+impl BrowserFinder {
+    /// Creates a finder for all installed browsers.
+    #[uniffi::constructor]
+    pub fn new() -> Arc<Self> {
+        Arc::new(BrowserFinder(rsbrowsers::BrowserFinder::new()))
+    }
+
+    /// Only lists browsers whose type or name matches the glob, e.g. "chrome*". Can be called multiple
+    /// times to accept several patterns.
+    pub fn with_type(&self, browser_type: String) -> Arc<Self> {
+        self.with(|finder| finder.with_type(browser_type))
+    }
+
+    /// Leaves out browsers whose type or name matches the glob.
+    pub fn exclude_type(&self, browser_type: String) -> Arc<Self> {
+        self.with(|finder| finder.exclude_types([browser_type]))
+    }
+
+    /// Only lists browsers whose version matches the glob, e.g. "120*".
+    pub fn with_version(&self, version: String) -> Arc<Self> {
+        self.with(|finder| finder.with_version(version))
+    }
+
+    /// Only lists browsers whose version meets the requirement, e.g. ">=120" or ">=115, <120".
+    pub fn with_version_req(&self, requirement: String) -> Arc<Self> {
+        self.with(|finder| finder.with_version_req(&requirement))
+    }
+
+    /// Scans for the browsers matching the filters.
+    pub fn all(&self) -> Result<Vec<Browser>, BrowsersError> {
+        Ok(self.0.try_all()?.map(|browser| Browser::from(&browser)).collect())
+    }
+
+    /// Returns the first browser matching the filters, null if none does.
+    pub fn first(&self) -> Result<Option<Browser>, BrowsersError> {
+        Ok(self.0.try_all()?.next().map(|browser| Browser::from(&browser)))
+    }
+
+    /// Launches the first browser matching the filters with `args`, opening `url` unless it is null.
+    pub fn launch(&self, url: Option<String>, args: Vec<String>) -> Result<Arc<LaunchedBrowser>, BrowsersError> {
+        let mut options = rsbrowsers::LaunchOptions::new().args(args);
+        if let Some(url) = url {
+            options = options.url(url);
+        }
+        let (launched, browser) = self.0.launch(&options)?;
+        Ok(Arc::new(LaunchedBrowser { launched: Mutex::new(launched), browser: Browser::from(&browser) }))
+    }
+}
+
+/// A browser started by `BrowserFinder.launch`. It keeps running when the object is released.
+#[derive(uniffi::Object)]
+pub struct LaunchedBrowser {
+    launched: Mutex<rsbrowsers::LaunchedBrowser>,
+    browser: Browser,
+}
+
+#[uniffi::export]
+impl LaunchedBrowser {
+    /// The process ID of the launched browser.
+    pub fn pid(&self) -> u32 {
+        self.launched.lock().unwrap().id()
+    }
+
+    /// The information of the launched browser.
+    pub fn browser(&self) -> Browser {
+        self.browser.clone()
+    }
+
+    /// Kills the browser without letting it save its profile, see `close`.
+    pub fn kill(&self) -> Result<(), BrowsersError> {
+        self.launched.lock().unwrap().kill().map_err(|error| BrowsersError::Launch { message: error.to_string() })
+    }
+
+    /// Asks the browser to quit, killing it if it is still running after `timeout` seconds, and returns
+    /// its exit code, -1 if it was ended by a signal.
+    pub fn close(&self, timeout: f64) -> Result<i32, BrowsersError> {
+        // checked before locking, so a bad value doesn't poison the lock
+        let timeout = seconds(timeout)?;
+        let status = self.launched.lock().unwrap().close(timeout);
+        let status = status.map_err(|error| BrowsersError::Launch { message: error.to_string() })?;
+        Ok(status.code().unwrap_or(-1))
+    }
+}
+
+/// Converts a timeout in seconds, rejecting negative, infinite and NaN values.
+fn seconds(timeout: f64) -> Result<Duration, BrowsersError> {
+    Duration::try_from_secs_f64(timeout)
+        .map_err(|error| BrowsersError::InvalidArgument { message: format!("invalid timeout {timeout}: {error}") })
+}
+
+/// Lists all installed browsers, the same as `BrowserFinder().all()`.
+#[uniffi::export]
+pub fn browsers() -> Result<Vec<Browser>, BrowsersError> {
+    BrowserFinder::new().all()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BrowserFinder, BrowsersError, seconds};
+    use std::time::Duration;
+
+    #[test]
+    fn test_browser_finder() {
+        let finder = BrowserFinder::new().with_type("nonexistent-browser".to_string());
+        assert_eq!(finder.all(), Ok(vec![]));
+        assert_eq!(finder.first(), Ok(None));
+        assert_eq!(finder.launch(None, vec![]).err(), Some(BrowsersError::NotFound));
+
+        let error = BrowserFinder::new().with_type("chrome[".to_string()).all().unwrap_err();
+        assert!(matches!(error, BrowsersError::InvalidPattern { .. }), "{error:?}");
+    }
+
+    #[test]
+    fn test_seconds() {
+        assert_eq!(seconds(1.5), Ok(Duration::from_millis(1500)));
+        for timeout in [-1.0, f64::NAN, f64::INFINITY] {
+            assert!(matches!(seconds(timeout), Err(BrowsersError::InvalidArgument { .. })));
+        }
+    }
+}
@@ -0,0 +1,103 @@
+from types import TracebackType
+from typing import Dict, Iterator, List, Literal, Optional, Type, Union
+
+class BrowserNotFoundError(Exception): ...
+class DiscoveryError(Exception): ...
+class LaunchError(Exception): ...
+
+class Browser:
+    """An installed browser."""
+
+    @property
+    def browser_type(self) -> str: ...
+    @property
+    def path(self) -> str: ...
+    @property
+    def exec(self) -> Optional[str]: ...
+    @property
+    def display_name(self) -> str: ...
+    @property
+    def version(self) -> str: ...
+    @property
+    def raw_version(self) -> Optional[str]: ...
+    @property
+    def chromium_version(self) -> Optional[str]: ...
+    @property
+    def install_source(self) -> str: ...
+    @property
+    def source_id(self) -> Optional[str]: ...
+    @property
+    def source_path(self) -> Optional[str]: ...
+    @property
+    def package(self) -> Optional[str]: ...
+    def launch(self, url: Optional[str] = None, args: Optional[List[str]] = None) -> "LaunchedBrowser": ...
+    def to_dict(self) -> Dict[str, Optional[str]]: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __hash__(self) -> int: ...
+
+class BrowserIterator(Iterator[Browser]):
+    def __iter__(self) -> "BrowserIterator": ...
+    def __next__(self) -> Browser: ...
+
+class LaunchedBrowser:
+    """A browser started by `launch()` or `Browser.launch()`, closed when leaving a `with` block."""
+
+    @property
+    def pid(self) -> int: ...
+    @property
+    def browser(self) -> Browser: ...
+    def wait(self, timeout: Optional[float] = None) -> int: ...
+    def kill(self) -> None: ...
+    def close(self, timeout: float = 10.0) -> int: ...
+    def __enter__(self) -> "LaunchedBrowser": ...
+    def __exit__(
+        self,
+        exc_type: Optional[Type[BaseException]],
+        exc_value: Optional[BaseException],
+        traceback: Optional[TracebackType],
+    ) -> bool: ...
+
+class Application:
+    """An application other than a known browser that handles a URL scheme."""
+
+    @property
+    def id(self) -> str: ...
+    @property
+    def path(self) -> Optional[str]: ...
+
+def browsers(
+    type: Optional[Union[str, List[str]]] = None,
+    version: Optional[str] = None,
+    exclude: Optional[Union[str, List[str]]] = None,
+) -> BrowserIterator: ...
+def get(browser: str, version: str = "*") -> Optional[Browser]: ...
+def launch(
+    browser: str,
+    version: Optional[str] = None,
+    url: Optional[str] = None,
+    args: Optional[List[str]] = None,
+) -> LaunchedBrowser: ...
+def default() -> Optional[Browser]: ...
+def handler_for(scheme: str) -> Optional[Union[Browser, Application]]: ...
+def set_default(browser: Browser) -> Literal["changed", "prompted", "settings-opened"]: ...
+
+class _Aio:
+    """`browsers.aio`: the functions above as coroutines that don't block the event loop."""
+
+    @staticmethod
+    async def list(
+        type: Optional[Union[str, List[str]]] = None,
+        version: Optional[str] = None,
+        exclude: Optional[Union[str, List[str]]] = None,
+    ) -> List[Browser]: ...
+    @staticmethod
+    async def get(browser: str, version: str = "*") -> Optional[Browser]: ...
+    @staticmethod
+    async def launch(
+        browser: str,
+        version: Optional[str] = None,
+        url: Optional[str] = None,
+        args: Optional[List[str]] = None,
+    ) -> LaunchedBrowser: ...
+
+aio: _Aio
@@ -0,0 +1,11 @@
+import asyncio
+from pprint import pprint
+
+import browsers.aio
+
+async def main():
+    pprint(await browsers.aio.list())
+
+
+if __name__ == "__main__":
+    asyncio.run(main())
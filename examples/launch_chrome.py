@@ -3,7 +3,10 @@ from pprint import pprint
 import browsers
 
 def main():
-    browsers.launch("chrome")
+    # the browser is closed when the block exits
+    with browsers.launch("chrome", url="https://example.com") as chrome:
+        pprint(chrome.browser.to_dict())
+        chrome.wait()
 
 
 if __name__ == "__main__":
@@ -0,0 +1,121 @@
+#ifndef RSBROWSERS_H
+#define RSBROWSERS_H
+
+/* Generated by cbindgen from src/lib.rs, do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * A browser started by `rsbrowsers_launch`.
+ */
+typedef struct RsLaunchedBrowser RsLaunchedBrowser;
+
+/**
+ * An installed browser. The strings are UTF-8, `version` is empty if unknown.
+ */
+typedef struct RsBrowser {
+  /**
+   * The browser key, e.g. `chrome` or `firefox-nightly`.
+   */
+  char *browser_type;
+  /**
+   * The executable or app bundle.
+   */
+  char *path;
+  char *display_name;
+  char *version;
+} RsBrowser;
+
+/**
+ * The browsers returned by `rsbrowsers_list`, `browsers` is `NULL` if `len` is 0.
+ */
+typedef struct RsBrowserList {
+  struct RsBrowser *browsers;
+  size_t len;
+} RsBrowserList;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Returns the message of the last error on this thread, `NULL` if the last call succeeded. The string
+ * belongs to the library and is valid until the next call on this thread.
+ */
+const char *rsbrowsers_last_error(void);
+
+/**
+ * Lists the installed browsers. Free the list with `rsbrowsers_list_free`.
+ */
+struct RsBrowserList rsbrowsers_list(void);
+
+/**
+ * Frees a list returned by `rsbrowsers_list`.
+ *
+ * # Safety
+ * `list` must come from `rsbrowsers_list` and not be freed before.
+ */
+void rsbrowsers_list_free(struct RsBrowserList list);
+
+/**
+ * Lists the installed browsers as a JSON array of records, as written by `rsbrowsers list --format json`.
+ * Free the string with `rsbrowsers_string_free`. `NULL` if the browsers could not be scanned or serialized.
+ */
+char *rsbrowsers_list_json(void);
+
+/**
+ * Frees a string returned by the library. Does nothing for `NULL`.
+ *
+ * # Safety
+ * `string` must be `NULL` or come from `rsbrowsers_list_json` and not be freed before.
+ */
+void rsbrowsers_string_free(char *string);
+
+/**
+ * Launches the first browser of `browser_type`, e.g. `firefox` or a glob such as `chrome*`, opening `url`
+ * unless it is `NULL`. A `NULL` type launches the default browser set by `BROWSER` or else the first one
+ * found. Returns `NULL` if no browser matches or it could not be started.
+ *
+ * Free the handle with `rsbrowsers_launched_free`, which leaves the browser running.
+ *
+ * # Safety
+ * `browser_type` and `url` must be `NULL` or point to NUL-terminated strings.
+ */
+struct RsLaunchedBrowser *rsbrowsers_launch(const char *browser_type,
+                                            const char *url);
+
+/**
+ * Returns the process ID of a launched browser.
+ *
+ * # Safety
+ * `launched` must come from `rsbrowsers_launch` and not be freed.
+ */
+uint32_t rsbrowsers_launched_pid(const struct RsLaunchedBrowser *launched);
+
+/**
+ * Asks a launched browser to quit, killing it if it is still running after `timeout_ms` milliseconds, and
+ * returns its exit code. -1 if it could not be stopped, or exited from a signal.
+ *
+ * # Safety
+ * `launched` must come from `rsbrowsers_launch` and not be freed.
+ */
+int32_t rsbrowsers_launched_close(struct RsLaunchedBrowser *launched,
+                                  uint64_t timeout_ms);
+
+/**
+ * Frees a handle returned by `rsbrowsers_launch`. Does nothing for `NULL`.
+ *
+ * # Safety
+ * `launched` must be `NULL` or come from `rsbrowsers_launch` and not be freed before.
+ */
+void rsbrowsers_launched_free(struct RsLaunchedBrowser *launched);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* RSBROWSERS_H */
@@ -0,0 +1,249 @@
+//! A C interface to find and launch browsers, for applications that can't use the Rust or Python API.
+//! `include/rsbrowsers.h` is generated from this file with cbindgen, see `cbindgen.toml`.
+//!
+//! Strings and lists returned by the library are owned by the caller and freed with the matching
+//! `*_free` function. Functions that fail return `NULL` or an empty list and leave a message for
+//! `rsbrowsers_last_error`.
+
+use rsbrowsers::{Browser, BrowserFinder, Browsers, LaunchOptions, LaunchedBrowser};
+use std::cell::RefCell;
+use std::ffi::{CStr, CString, c_char};
+use std::ptr;
+use std::time::Duration;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// An installed browser. The strings are UTF-8, `version` is empty if unknown.
+#[repr(C)]
+pub struct RsBrowser {
+    /// The browser key, e.g. `chrome` or `firefox-nightly`.
+    pub browser_type: *mut c_char,
+    /// The executable or app bundle.
+    pub path: *mut c_char,
+    pub display_name: *mut c_char,
+    pub version: *mut c_char,
+}
+
+/// The browsers returned by `rsbrowsers_list`, `browsers` is `NULL` if `len` is 0.
+#[repr(C)]
+pub struct RsBrowserList {
+    pub browsers: *mut RsBrowser,
+    pub len: usize,
+}
+
+/// A browser started by `rsbrowsers_launch`.
+pub struct RsLaunchedBrowser(LaunchedBrowser);
+
+fn set_last_error(message: impl ToString) {
+    let message = CString::new(message.to_string().replace('\0', "")).unwrap_or_default();
+    LAST_ERROR.with(|error| *error.borrow_mut() = Some(message));
+}
+
+fn clear_last_error() {
+    LAST_ERROR.with(|error| *error.borrow_mut() = None);
+}
+
+/// Copies `value` into a string for C, dropping NUL characters which can't be represented.
+fn c_string(value: &str) -> *mut c_char {
+    CString::new(value.replace('\0', "")).unwrap_or_default().into_raw()
+}
+
+/// Reads an optional UTF-8 argument, `Err` if it is not valid UTF-8.
+///
+/// # Safety
+/// `value` must be `NULL` or point to a NUL-terminated string.
+unsafe fn optional_str<'a>(name: &str, value: *const c_char) -> Result<Option<&'a str>, String> {
+    if value.is_null() {
+        return Ok(None);
+    }
+    match unsafe { CStr::from_ptr(value) }.to_str() {
+        Ok(value) => Ok(Some(value)),
+        Err(_) => Err(format!("{name} is not valid UTF-8")),
+    }
+}
+
+fn scan() -> Option<Vec<Browser>> {
+    clear_last_error();
+    match BrowserFinder::new().try_all() {
+        Ok(browsers) => Some(browsers.collect()),
+        Err(error) => {
+            set_last_error(error);
+            None
+        }
+    }
+}
+
+/// Returns the message of the last error on this thread, `NULL` if the last call succeeded. The string
+/// belongs to the library and is valid until the next call on this thread.
+#[unsafe(no_mangle)]
+pub extern "C" fn rsbrowsers_last_error() -> *const c_char {
+    LAST_ERROR.with(|error| error.borrow().as_ref().map_or(ptr::null(), |message| message.as_ptr()))
+}
+
+/// Lists the installed browsers. Free the list with `rsbrowsers_list_free`.
+#[unsafe(no_mangle)]
+pub extern "C" fn rsbrowsers_list() -> RsBrowserList {
+    let browsers: Box<[RsBrowser]> = scan()
+        .unwrap_or_default()
+        .iter()
+        .map(|browser| RsBrowser {
+            browser_type: c_string(&browser.browser_type),
+            path: c_string(&browser.path.to_string_lossy()),
+            display_name: c_string(&browser.display_name),
+            version: c_string(&browser.version),
+        })
+        .collect();
+    match browsers.len() {
+        0 => RsBrowserList { browsers: ptr::null_mut(), len: 0 },
+        len => RsBrowserList { browsers: Box::into_raw(browsers).cast(), len },
+    }
+}
+
+/// Frees a list returned by `rsbrowsers_list`.
+///
+/// # Safety
+/// `list` must come from `rsbrowsers_list` and not be freed before.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rsbrowsers_list_free(list: RsBrowserList) {
+    if list.browsers.is_null() {
+        return;
+    }
+    let browsers = unsafe { Box::from_raw(ptr::slice_from_raw_parts_mut(list.browsers, list.len)) };
+    for browser in browsers.iter() {
+        for string in [browser.browser_type, browser.path, browser.display_name, browser.version] {
+            drop(unsafe { CString::from_raw(string) });
+        }
+    }
+}
+
+/// Lists the installed browsers as a JSON array of records, as written by `rsbrowsers list --format json`.
+/// Free the string with `rsbrowsers_string_free`. `NULL` if the browsers could not be scanned or serialized.
+#[unsafe(no_mangle)]
+pub extern "C" fn rsbrowsers_list_json() -> *mut c_char {
+    match scan().and_then(|browsers| Browsers::from(browsers).to_json().ok()) {
+        Some(json) => c_string(&json),
+        None => ptr::null_mut(),
+    }
+}
+
+/// Frees a string returned by the library. Does nothing for `NULL`.
+///
+/// # Safety
+/// `string` must be `NULL` or come from `rsbrowsers_list_json` and not be freed before.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rsbrowsers_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        drop(unsafe { CString::from_raw(string) });
+    }
+}
+
+/// Launches the first browser of `browser_type`, e.g. `firefox` or a glob such as `chrome*`, opening `url`
+/// unless it is `NULL`. A `NULL` type launches the default browser set by `BROWSER` or else the first one
+/// found. Returns `NULL` if no browser matches or it could not be started.
+///
+/// Free the handle with `rsbrowsers_launched_free`, which leaves the browser running.
+///
+/// # Safety
+/// `browser_type` and `url` must be `NULL` or point to NUL-terminated strings.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rsbrowsers_launch(browser_type: *const c_char, url: *const c_char) -> *mut RsLaunchedBrowser {
+    clear_last_error();
+    let (browser_type, url) = match unsafe { (optional_str("browser_type", browser_type), optional_str("url", url)) } {
+        (Ok(browser_type), Ok(url)) => (browser_type, url),
+        (Err(error), _) | (_, Err(error)) => {
+            set_last_error(error);
+            return ptr::null_mut();
+        }
+    };
+
+    let mut finder = BrowserFinder::new();
+    if let Some(browser_type) = browser_type {
+        finder = finder.with_type(browser_type.to_string());
+    }
+    let mut options = LaunchOptions::new();
+    if let Some(url) = url {
+        options = options.url(url.to_string());
+    }
+    match finder.launch(&options) {
+        Ok((launched, _)) => Box::into_raw(Box::new(RsLaunchedBrowser(launched))),
+        Err(error) => {
+            set_last_error(error);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Returns the process ID of a launched browser.
+///
+/// # Safety
+/// `launched` must come from `rsbrowsers_launch` and not be freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rsbrowsers_launched_pid(launched: *const RsLaunchedBrowser) -> u32 {
+    unsafe { &*launched }.0.id()
+}
+
+/// Asks a launched browser to quit, killing it if it is still running after `timeout_ms` milliseconds, and
+/// returns its exit code. -1 if it could not be stopped, or exited from a signal.
+///
+/// # Safety
+/// `launched` must come from `rsbrowsers_launch` and not be freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rsbrowsers_launched_close(launched: *mut RsLaunchedBrowser, timeout_ms: u64) -> i32 {
+    clear_last_error();
+    match unsafe { &mut *launched }.0.close(Duration::from_millis(timeout_ms)) {
+        Ok(status) => status.code().unwrap_or(-1),
+        Err(error) => {
+            set_last_error(error);
+            -1
+        }
+    }
+}
+
+/// Frees a handle returned by `rsbrowsers_launch`. Does nothing for `NULL`.
+///
+/// # Safety
+/// `launched` must be `NULL` or come from `rsbrowsers_launch` and not be freed before.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rsbrowsers_launched_free(launched: *mut RsLaunchedBrowser) {
+    if !launched.is_null() {
+        drop(unsafe { Box::from_raw(launched) });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        rsbrowsers_last_error, rsbrowsers_launch, rsbrowsers_list, rsbrowsers_list_free, rsbrowsers_list_json,
+        rsbrowsers_string_free,
+    };
+    use std::ffi::CStr;
+    use std::ptr;
+
+    #[test]
+    fn test_list() {
+        let list = rsbrowsers_list();
+        assert_eq!(list.browsers.is_null(), list.len == 0);
+        assert!(rsbrowsers_last_error().is_null());
+        unsafe { rsbrowsers_list_free(list) };
+
+        let json = rsbrowsers_list_json();
+        assert!(unsafe { CStr::from_ptr(json) }.to_str().unwrap().starts_with('['));
+        unsafe { rsbrowsers_string_free(json) };
+        unsafe { rsbrowsers_string_free(ptr::null_mut()) };
+    }
+
+    #[test]
+    fn test_launch_errors() {
+        let error = || unsafe { CStr::from_ptr(rsbrowsers_last_error()) }.to_str().unwrap().to_string();
+
+        let launched = unsafe { rsbrowsers_launch(c"nonexistent-browser".as_ptr(), ptr::null()) };
+        assert!(launched.is_null());
+        assert_eq!(error(), "browser not found");
+
+        let launched = unsafe { rsbrowsers_launch(c"firefox".as_ptr(), c"https://example.com/\xff".as_ptr()) };
+        assert!(launched.is_null());
+        assert_eq!(error(), "url is not valid UTF-8");
+    }
+}
@@ -0,0 +1,59 @@
+//! Discovery on the machine running `cargo bench -p rsbrowsers`, with the platform's backends measured one at a
+//! time. The cache is never used, so every iteration scans.
+
+use criterion::measurement::WallTime;
+use criterion::{BenchmarkGroup, Criterion, criterion_group, criterion_main};
+#[cfg(target_os = "macos")]
+use rsbrowsers::SpotlightBackend;
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+use rsbrowsers::TorBackend;
+#[cfg(target_os = "windows")]
+use rsbrowsers::{AppPathsBackend, MsStoreBackend, RegistryBackend};
+use rsbrowsers::{BrowserFinder, DetectionBackend};
+#[cfg(target_os = "linux")]
+use rsbrowsers::{DesktopEntryBackend, WslBackend};
+use std::time::Duration;
+
+fn discovery(c: &mut Criterion) {
+    let mut group = c.benchmark_group("discovery");
+    // scans with probes take seconds
+    group.sample_size(10);
+    group.bench_function("all", |b| b.iter(|| BrowserFinder::new().all().count()));
+    group.bench_function("skip_version_probe", |b| b.iter(|| BrowserFinder::new().skip_version_probe().all().count()));
+    group
+        .bench_function("skip_process_probes", |b| b.iter(|| BrowserFinder::new().skip_process_probes().all().count()));
+    group.bench_function("with_deadline_100ms", |b| {
+        b.iter(|| BrowserFinder::new().with_deadline(Duration::from_millis(100)).all().count())
+    });
+    group.finish();
+}
+
+fn backends(c: &mut Criterion) {
+    let mut group = c.benchmark_group("backends");
+    group.sample_size(10);
+    #[cfg(target_os = "macos")]
+    bench_backend(&mut group, SpotlightBackend);
+    #[cfg(target_os = "windows")]
+    {
+        bench_backend(&mut group, RegistryBackend);
+        bench_backend(&mut group, AppPathsBackend);
+        bench_backend(&mut group, MsStoreBackend);
+    }
+    #[cfg(target_os = "linux")]
+    {
+        bench_backend(&mut group, DesktopEntryBackend);
+        bench_backend(&mut group, WslBackend);
+    }
+    #[cfg(any(target_os = "windows", target_os = "linux"))]
+    bench_backend(&mut group, TorBackend);
+    group.finish();
+}
+
+fn bench_backend<B: DetectionBackend + 'static>(group: &mut BenchmarkGroup<WallTime>, backend: B) {
+    let name = backend.name().to_string();
+    let finder = BrowserFinder::new().without_builtin_backends().with_backend(backend);
+    group.bench_function(name, |b| b.iter(|| finder.all().count()));
+}
+
+criterion_group!(benches, discovery, backends);
+criterion_main!(benches);
@@ -0,0 +1,237 @@
+use crate::{Browser, InstallSource, ScanContext, parallel};
+use glob::{Pattern, glob};
+use std::env;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+#[cfg(not(target_os = "windows"))]
+use {
+    crate::version::{find_version, probe_output},
+    std::process::Command,
+};
+
+#[cfg(target_os = "linux")]
+const PLAYWRIGHT_BROWSER_LIST: &[(&str, &str, &str)] = &[
+    // browser name, display name, executable pattern relative to the cache
+    ("chromium", "Chromium", "chromium-*/chrome-linux*/chrome"),
+    ("firefox", "Firefox", "firefox-*/firefox/firefox"),
+    ("webkit", "WebKit", "webkit-*/pw_run.sh"),
+];
+#[cfg(target_os = "macos")]
+const PLAYWRIGHT_BROWSER_LIST: &[(&str, &str, &str)] = &[
+    ("chromium", "Chromium", "chromium-*/chrome-mac*/Chromium.app/Contents/MacOS/Chromium"),
+    ("firefox", "Firefox", "firefox-*/firefox/Nightly.app/Contents/MacOS/firefox"),
+    ("webkit", "WebKit", "webkit-*/pw_run.sh"),
+];
+#[cfg(target_os = "windows")]
+const PLAYWRIGHT_BROWSER_LIST: &[(&str, &str, &str)] = &[
+    ("chromium", "Chromium", r"chromium-*\chrome-win*\chrome.exe"),
+    ("firefox", "Firefox", r"firefox-*\firefox\firefox.exe"),
+    ("webkit", "WebKit", r"webkit-*\Playwright.exe"),
+];
+
+#[cfg(target_os = "linux")]
+const PUPPETEER_BROWSER_LIST: &[(&str, &str, &str)] = &[
+    // browser name, display name, executable pattern relative to the cache (the version is in the
+    // second directory, e.g. "chrome/linux-120.0.6099.109/...")
+    ("chrome-test", "Google Chrome for Testing", "chrome/*/chrome-*/chrome"),
+    (
+        "chrome-headless-shell",
+        "Chrome Headless Shell",
+        "chrome-headless-shell/*/chrome-headless-shell-*/chrome-headless-shell",
+    ),
+    ("firefox", "Firefox", "firefox/*/firefox/firefox"),
+];
+#[cfg(target_os = "macos")]
+const PUPPETEER_BROWSER_LIST: &[(&str, &str, &str)] = &[
+    (
+        "chrome-test",
+        "Google Chrome for Testing",
+        "chrome/*/chrome-*/Google Chrome for Testing.app/Contents/MacOS/Google Chrome for Testing",
+    ),
+    (
+        "chrome-headless-shell",
+        "Chrome Headless Shell",
+        "chrome-headless-shell/*/chrome-headless-shell-*/chrome-headless-shell",
+    ),
+    ("firefox", "Firefox", "firefox/*/Firefox*.app/Contents/MacOS/firefox"),
+];
+#[cfg(target_os = "windows")]
+const PUPPETEER_BROWSER_LIST: &[(&str, &str, &str)] = &[
+    ("chrome-test", "Google Chrome for Testing", r"chrome\*\chrome-*\chrome.exe"),
+    (
+        "chrome-headless-shell",
+        "Chrome Headless Shell",
+        r"chrome-headless-shell\*\chrome-headless-shell-*\chrome-headless-shell.exe",
+    ),
+    ("firefox", "Firefox", r"firefox\*\firefox\firefox.exe"),
+];
+
+#[cfg(target_os = "linux")]
+const CHROME_FOR_TESTING_LIST: &[(&str, &str, &str)] = &[
+    // browser name, display name, executable pattern relative to the chrome-for-testing directory (the
+    // version is the first directory, e.g. "120.0.6099.109/chrome-linux64/chrome")
+    ("chrome-test", "Google Chrome for Testing", "*/chrome-linux64/chrome"),
+    ("chrome-headless-shell", "Chrome Headless Shell", "*/chrome-headless-shell-linux64/chrome-headless-shell"),
+];
+#[cfg(target_os = "macos")]
+const CHROME_FOR_TESTING_LIST: &[(&str, &str, &str)] = &[
+    (
+        "chrome-test",
+        "Google Chrome for Testing",
+        "*/chrome-mac-*/Google Chrome for Testing.app/Contents/MacOS/Google Chrome for Testing",
+    ),
+    ("chrome-headless-shell", "Chrome Headless Shell", "*/chrome-headless-shell-mac-*/chrome-headless-shell"),
+];
+#[cfg(target_os = "windows")]
+const CHROME_FOR_TESTING_LIST: &[(&str, &str, &str)] = &[
+    ("chrome-test", "Google Chrome for Testing", r"*\chrome-win*\chrome.exe"),
+    ("chrome-headless-shell", "Chrome Headless Shell", r"*\chrome-headless-shell-win*\chrome-headless-shell.exe"),
+];
+
+/// Finds browsers downloaded by Playwright and Puppeteer into their caches, and Chrome for Testing
+/// downloads extracted into `chrome-for-testing` under the user cache directory. Playwright versions are left
+/// empty unless process probes are allowed, and for the browsers not probed before the deadline.
+pub(crate) fn detect(context: &ScanContext) -> Vec<Browser> {
+    let mut playwright = vec![];
+    if let Some(cache) = playwright_cache() {
+        for (browser_type, display_name, pattern) in PLAYWRIGHT_BROWSER_LIST {
+            for path in find(&cache, pattern) {
+                playwright.push(browser(browser_type, display_name, path, "".to_string(), InstallSource::Playwright));
+            }
+        }
+    }
+    // the directory suffix is a Playwright revision, so ask the browser itself
+    let mut browsers = parallel::map(playwright, context.concurrency(), |mut browser| {
+        // reading the PE resources doesn't run anything
+        let probe = context.process_probes() || cfg!(target_os = "windows");
+        if browser.browser_type != "webkit" && probe && !context.expired() {
+            browser.version = probe_version(&browser.path, context.probe_timeout());
+        }
+        browser
+    });
+
+    if let Some(cache) = puppeteer_cache() {
+        for (browser_type, display_name, pattern) in PUPPETEER_BROWSER_LIST {
+            for path in find(&cache, pattern) {
+                let version = path
+                    .strip_prefix(&cache)
+                    .ok()
+                    .and_then(|relative| relative.components().nth(1))
+                    .and_then(|dir| dir.as_os_str().to_str().map(puppeteer_version))
+                    .unwrap_or_default();
+                browsers.push(browser(browser_type, display_name, path, version, InstallSource::Puppeteer));
+            }
+        }
+    }
+
+    if let Some(dir) = dirs::cache_dir().map(|path| path.join("chrome-for-testing")) {
+        for (browser_type, display_name, pattern) in CHROME_FOR_TESTING_LIST {
+            for path in find(&dir, pattern) {
+                let version = path
+                    .strip_prefix(&dir)
+                    .ok()
+                    .and_then(|relative| relative.components().next())
+                    .map_or("".to_string(), |version| version.as_os_str().to_string_lossy().to_string());
+                browsers.push(browser(browser_type, display_name, path, version, InstallSource::ChromeForTesting));
+            }
+        }
+    }
+
+    browsers
+}
+
+fn playwright_cache() -> Option<PathBuf> {
+    // "0" means the browsers live next to the playwright package in node_modules
+    match env::var_os("PLAYWRIGHT_BROWSERS_PATH") {
+        Some(path) if path != "0" => Some(PathBuf::from(path)),
+        _ => dirs::cache_dir().map(|path| path.join("ms-playwright")),
+    }
+}
+
+fn puppeteer_cache() -> Option<PathBuf> {
+    match env::var_os("PUPPETEER_CACHE_DIR") {
+        Some(path) => Some(PathBuf::from(path)),
+        None => dirs::home_dir().map(|path| path.join(".cache").join("puppeteer")),
+    }
+}
+
+fn find(base: &Path, pattern: &str) -> Vec<PathBuf> {
+    let pattern = Path::new(&Pattern::escape(&base.to_string_lossy())).join(pattern);
+    match glob(&pattern.to_string_lossy()) {
+        Ok(paths) => paths.filter_map(Result::ok).collect(),
+        Err(_) => vec![],
+    }
+}
+
+/// Extracts the version from a Puppeteer directory name such as `linux-120.0.6099.109` or
+/// `mac_arm-stable_121.0`.
+fn puppeteer_version(dir: &str) -> String {
+    let version = dir.split_once('-').map_or(dir, |(_, version)| version);
+    version.rsplit('_').next().unwrap_or(version).to_string()
+}
+
+#[cfg(target_os = "windows")]
+fn probe_version(path: &Path, _timeout: Duration) -> String {
+    crate::get_version_info(path)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn probe_version(path: &Path, timeout: Duration) -> String {
+    probe_output(Command::new(path).arg("--version"), timeout)
+        .and_then(|output| find_version(&output))
+        .map_or("".to_string(), |version| version.to_string())
+}
+
+fn browser(
+    browser_type: &str,
+    display_name: &str,
+    path: PathBuf,
+    version: String,
+    install_source: InstallSource,
+) -> Browser {
+    Browser {
+        browser_type: browser_type.to_string(),
+        path,
+        exec: None,
+        args: vec![],
+        display_name: display_name.to_string(),
+        version,
+        raw_version: None,
+        install_source,
+        source_id: None,
+        source_path: None,
+        package: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::automation::puppeteer_version;
+    #[cfg(target_os = "linux")]
+    use {crate::automation::CHROME_FOR_TESTING_LIST, crate::automation::find, std::env, std::fs};
+
+    #[test]
+    fn test_puppeteer_version() {
+        assert_eq!(puppeteer_version("linux-120.0.6099.109"), "120.0.6099.109");
+        assert_eq!(puppeteer_version("mac_arm-stable_121.0"), "121.0");
+        assert_eq!(puppeteer_version("win64-nightly_123.0a1"), "123.0a1");
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_find_chrome_for_testing() {
+        let dir = env::temp_dir().join(format!("rsbrowsers-cft-{}", std::process::id()));
+        let chrome = dir.join("120.0.6099.109/chrome-linux64/chrome");
+        let shell = dir.join("121.0.6167.85/chrome-headless-shell-linux64/chrome-headless-shell");
+        for path in [&chrome, &shell] {
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+
+        let found: Vec<_> = CHROME_FOR_TESTING_LIST.iter().map(|(_, _, pattern)| find(&dir, pattern)).collect();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(found, [vec![chrome], vec![shell]]);
+    }
+}
@@ -0,0 +1,730 @@
+use crate::SkipReason;
+use crate::custom::Bundle;
+use crate::diagnose::Recorder;
+use crate::inventory::Inventory;
+#[cfg(all(target_os = "macos", feature = "launch-services"))]
+use crate::launch_services;
+use crate::{Browser, Error, SourceOutcome};
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+use crate::{InstallSource, parallel, tor};
+#[cfg(target_os = "macos")]
+use crate::{OSX_BROWSER_BUNDLE_LIST, application_bundles, extract_info_from_plist, order_copies, parallel};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+#[cfg(target_os = "linux")]
+use {
+    crate::desktop_files::{DesktopFile, application_dirs, desktop_files},
+    crate::info::is_executable,
+    crate::{
+        LINUX_DESKTOP_ENTRY_IGNORE_LIST, LINUX_DESKTOP_ENTRY_NAME_LIST, LINUX_VERSION_PROBE_IGNORE_LIST, exec,
+        generic_browser_type, get_version_output, is_web_browser_entry, wsl,
+    },
+    freedesktop_desktop_entry::DesktopEntry,
+};
+#[cfg(target_os = "windows")]
+use {
+    crate::{
+        WINDOWS_EXECUTABLE_LIST, WINDOWS_INSTALL_PATH_LIST, cmdline, get_version_info, msstore, windows_browser_type,
+    },
+    std::env,
+    std::ffi::{OsStr, OsString},
+    winreg::RegKey,
+    winreg::enums::{HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE},
+};
+#[cfg(all(target_os = "macos", not(feature = "launch-services")))]
+use {std::ffi::OsStr, std::os::unix::ffi::OsStrExt, std::process::Command};
+
+/// A source of installed browsers, such as the Windows registry or desktop entries on Linux.
+///
+/// The platform's built-in backends run by default. Implement this to add a source of your own, see
+/// `BrowserFinder::with_backend`.
+pub trait DetectionBackend: Send + Sync {
+    /// Identifies the backend for `BrowserFinder::disable_backend`, e.g. `registry`.
+    fn name(&self) -> &str;
+
+    /// Adds the browsers found by this source to `sink`, leaving versions empty if reading them needs a
+    /// probe that `context` doesn't allow.
+    fn detect(&self, context: &ScanContext, sink: &mut Vec<Browser>) -> Result<(), Error>;
+}
+
+/// Runs `backend`, reporting how many browsers it found and how long it took with the `tracing` feature and
+/// to `BrowserFinder::diagnose`, which also goes on after an error. Skips it once the deadline has passed.
+pub(crate) fn run(backend: &dyn DetectionBackend, context: &ScanContext, sink: &mut Vec<Browser>) -> Result<(), Error> {
+    if context.expired() {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(backend = backend.name(), "backend skipped after the deadline");
+        if let Some(recorder) = context.recorder {
+            recorder.backend(backend.name(), 0, Duration::ZERO, Some("skipped after the deadline".to_string()));
+        }
+        return Ok(());
+    }
+
+    let (started, before) = (Instant::now(), sink.len());
+    let result = backend.detect(context, sink);
+    let (elapsed, found) = (started.elapsed(), sink.len() - before);
+    #[cfg(feature = "tracing")]
+    match &result {
+        Ok(()) => tracing::debug!(backend = backend.name(), found, ?elapsed, "backend ran"),
+        Err(error) => tracing::warn!(backend = backend.name(), %error, ?elapsed, "backend failed"),
+    }
+    match context.recorder {
+        Some(recorder) => {
+            recorder.backend(backend.name(), found, elapsed, result.err().map(|error| error.to_string()));
+            Ok(())
+        }
+        None => result,
+    }
+}
+
+/// The finder settings a backend should honor while scanning.
+pub struct ScanContext<'a> {
+    pub(crate) concurrency: usize,
+    pub(crate) version_probe: bool,
+    pub(crate) process_probes: bool,
+    pub(crate) probe_timeout: Duration,
+    pub(crate) deadline: Option<Instant>,
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    pub(crate) bundles: &'a [Bundle],
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    pub(crate) include_no_display: bool,
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    pub(crate) inventory: Option<&'a Inventory>,
+    pub(crate) recorder: Option<&'a Recorder>,
+}
+
+impl ScanContext<'_> {
+    /// How many threads a backend may use, see `BrowserFinder::concurrency`.
+    pub fn concurrency(&self) -> usize {
+        self.concurrency
+    }
+
+    /// Whether processes may be spawned, see `BrowserFinder::skip_process_probes`.
+    pub fn process_probes(&self) -> bool {
+        self.process_probes
+    }
+
+    /// Whether browsers may be run to read their versions, see `BrowserFinder::skip_version_probe`. Not after
+    /// the deadline.
+    pub fn version_probe(&self) -> bool {
+        self.version_probe && self.process_probes && !self.expired()
+    }
+
+    /// How long to wait for a probed browser, see `BrowserFinder::probe_timeout`, at most until the deadline.
+    pub fn probe_timeout(&self) -> Duration {
+        match self.deadline {
+            Some(deadline) => self.probe_timeout.min(deadline.saturating_duration_since(Instant::now())),
+            None => self.probe_timeout,
+        }
+    }
+
+    /// Returns the version the last scan kept with `BrowserFinder::with_disk_cache` found for the executable
+    /// at `path` if it didn't change since.
+    #[cfg(target_os = "linux")]
+    pub(crate) fn known_version(&self, browser: &Browser) -> Option<String> {
+        self.inventory?.version(browser).map(str::to_string)
+    }
+
+    /// Whether the time budget set with `BrowserFinder::with_deadline` is spent, so a backend should return
+    /// what it found so far.
+    pub fn expired(&self) -> bool {
+        self.deadline.is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    /// Records that `source`, e.g. a registry key, desktop file or directory, was examined and what came of
+    /// it, for `BrowserFinder::diagnose`. Does nothing during other scans.
+    pub fn report(&self, source: impl Into<String>, outcome: SourceOutcome) {
+        if let Some(recorder) = self.recorder {
+            recorder.source(source.into(), outcome);
+        }
+    }
+}
+
+/// Finds app bundles by bundle ID with Launch Services, or with Spotlight without the `launch-services`
+/// feature. Without either, when processes may not be spawned, looks in the Applications folders.
+#[cfg(target_os = "macos")]
+pub struct SpotlightBackend;
+
+#[cfg(target_os = "macos")]
+impl DetectionBackend for SpotlightBackend {
+    fn name(&self) -> &str {
+        "spotlight"
+    }
+
+    fn detect(&self, context: &ScanContext, sink: &mut Vec<Browser>) -> Result<(), Error> {
+        // Launch Services is asked within the process
+        let installed = match context.process_probes() || cfg!(feature = "launch-services") {
+            true => None,
+            false => Some(application_bundles()),
+        };
+
+        for result in parallel::map(
+            OSX_BROWSER_BUNDLE_LIST
+                .iter()
+                .copied()
+                .chain(context.bundles.iter().map(|bundle| {
+                    (bundle.browser_type.as_str(), bundle.bundle_id.as_str(), bundle.version_key.as_str())
+                }))
+                .collect(),
+            context.concurrency(),
+            |(browser_type, bundle_id, version_string)| {
+                let applications = match &installed {
+                    Some(installed) => installed
+                        .iter()
+                        .filter(|(_, id)| id.eq_ignore_ascii_case(bundle_id))
+                        .map(|(application, _)| application.to_owned())
+                        .collect(),
+                    None => registered_bundles(bundle_id)?,
+                };
+                let applications = order_copies(applications);
+                if applications.is_empty() {
+                    context.report(bundle_id, SourceOutcome::Skipped { reason: SkipReason::NotInstalled });
+                }
+                Ok::<_, Error>(
+                    applications
+                        .iter()
+                        .filter_map(|application| {
+                            let Some(browser) = extract_info_from_plist(application, browser_type, version_string)
+                            else {
+                                let error = "unreadable Info.plist".to_string();
+                                context.report(
+                                    application.to_string_lossy(),
+                                    SourceOutcome::Skipped { reason: SkipReason::ParseError { error } },
+                                );
+                                return None;
+                            };
+                            context.report(
+                                application.to_string_lossy(),
+                                SourceOutcome::Found { browser_type: browser.browser_type.clone() },
+                            );
+                            Some(browser)
+                        })
+                        .collect::<Vec<Browser>>(),
+                )
+            },
+        ) {
+            sink.extend(result?);
+        }
+        Ok(())
+    }
+}
+
+/// Lists the app bundles Launch Services knows with a bundle ID.
+#[cfg(all(target_os = "macos", feature = "launch-services"))]
+fn registered_bundles(bundle_id: &str) -> Result<Vec<PathBuf>, Error> {
+    Ok(launch_services::applications(bundle_id))
+}
+
+/// Lists the app bundles Spotlight indexed with a bundle ID.
+#[cfg(all(target_os = "macos", not(feature = "launch-services")))]
+fn registered_bundles(bundle_id: &str) -> Result<Vec<PathBuf>, Error> {
+    let output = Command::new("mdfind")
+        .arg(format!("kMDItemCFBundleIdentifier=='{bundle_id}'"))
+        .output()
+        .map_err(|e| Error::Discovery(format!("cannot run mdfind: {e}")))?;
+    // split as bytes, since paths need not be UTF-8
+    Ok(output
+        .stdout
+        .split(|byte| *byte == b'\n')
+        .filter(|line| !line.is_empty())
+        .map(|line| PathBuf::from(OsStr::from_bytes(line)))
+        .collect())
+}
+
+/// Finds browsers registered under `StartMenuInternet` and reads their versions from the executables.
+#[cfg(target_os = "windows")]
+pub struct RegistryBackend;
+
+#[cfg(target_os = "windows")]
+impl DetectionBackend for RegistryBackend {
+    fn name(&self) -> &str {
+        "registry"
+    }
+
+    fn detect(&self, context: &ScanContext, sink: &mut Vec<Browser>) -> Result<(), Error> {
+        let mut browsers: Vec<Browser> = vec![];
+
+        // per-user installs register under HKCU, and a key missing from either root just has no browsers
+        let roots = [(HKEY_LOCAL_MACHINE, "HKLM"), (HKEY_CURRENT_USER, "HKCU")];
+        let start_menus = roots.into_iter().filter_map(|(root, name)| {
+            Some((RegKey::predef(root).open_subkey(r"Software\Clients\StartMenuInternet").ok()?, name))
+        });
+        for (smi, root) in start_menus {
+            // keys that cannot be enumerated, e.g. names that aren't valid UTF-16, are skipped
+            for key in smi.enum_keys().filter_map(Result::ok) {
+                let source = format!(r"{root}\Software\Clients\StartMenuInternet\{key}");
+                let skipped = |reason| context.report(source.as_str(), SourceOutcome::Skipped { reason });
+                let Ok(browser) = smi.open_subkey(&key) else {
+                    continue;
+                };
+                let display_name: String = match browser.get_value("") {
+                    Ok(display_name) => display_name,
+                    Err(_) => key.to_string(),
+                };
+
+                let Ok(command) = smi.open_subkey(format!(r"{key}\shell\open\command")) else {
+                    skipped(SkipReason::ParseError { error: r"no shell\open\command key".to_string() });
+                    continue;
+                };
+                let (path, args) = match command.get_value::<OsString, _>("") {
+                    Ok(command) => split_command(&command),
+                    Err(e) => {
+                        skipped(SkipReason::ParseError { error: format!("cannot read the command: {e}") });
+                        continue;
+                    }
+                };
+                // display names may be localized, so they only refine the type
+                let Some(type_str) = windows_browser_type(&key, &path.to_string_lossy(), &display_name) else {
+                    skipped(SkipReason::UnknownName);
+                    continue;
+                };
+                // e.g. a browser installed for all users that also registered for the current one
+                if browsers.iter().any(|known| known.path == path) {
+                    skipped(SkipReason::Duplicate);
+                    continue;
+                }
+                context.report(source.as_str(), SourceOutcome::Found { browser_type: type_str.clone() });
+                // the version is read in parallel below
+                let version = String::new();
+
+                let browser = Browser {
+                    browser_type: type_str,
+                    display_name,
+                    path,
+                    exec: None,
+                    args,
+                    version,
+                    raw_version: None,
+                    install_source: InstallSource::System,
+                    source_id: Some(key.clone()),
+                    source_path: None,
+                    package: None,
+                };
+
+                browsers.push(browser);
+            }
+        }
+
+        sink.extend(parallel::map(browsers, context.concurrency(), |mut browser| {
+            browser.version = get_version_info(&browser.path);
+            browser
+        }));
+        Ok(())
+    }
+}
+
+/// Finds browsers that are not registered under `StartMenuInternet`, e.g. portable Chromium builds or some
+/// Waterfox installs, through their `App Paths` entries and the usual install directories in Program Files
+/// and `%LOCALAPPDATA%`. Executables already found by an earlier backend are skipped.
+#[cfg(target_os = "windows")]
+pub struct AppPathsBackend;
+
+#[cfg(target_os = "windows")]
+impl DetectionBackend for AppPathsBackend {
+    fn name(&self) -> &str {
+        "app-paths"
+    }
+
+    fn detect(&self, context: &ScanContext, sink: &mut Vec<Browser>) -> Result<(), Error> {
+        let mut seen: Vec<String> = sink.iter().map(|browser| browser.path.to_string_lossy().to_lowercase()).collect();
+        let mut browsers = vec![];
+
+        let registered = app_paths().into_iter().map(|path| (path, true));
+        for (path, registered) in registered.chain(install_paths().into_iter().map(|path| (path, false))) {
+            let source = path.to_string_lossy().to_string();
+            let skipped = |reason| context.report(source.as_str(), SourceOutcome::Skipped { reason });
+            if seen.contains(&source.to_lowercase()) {
+                skipped(SkipReason::Duplicate);
+                continue;
+            }
+            if !path.is_file() {
+                // install directories are only guessed, App Paths entries should point to an executable
+                skipped(match registered {
+                    true => SkipReason::MissingExecutable { path },
+                    false => SkipReason::NotInstalled,
+                });
+                continue;
+            }
+            seen.push(source.to_lowercase());
+            match fallback_browser(path) {
+                Some(browser) => {
+                    context.report(source, SourceOutcome::Found { browser_type: browser.browser_type.clone() });
+                    browsers.push(browser);
+                }
+                None => skipped(SkipReason::UnknownName),
+            }
+        }
+
+        sink.extend(parallel::map(browsers, context.concurrency(), |mut browser| {
+            browser.version = get_version_info(&browser.path);
+            browser
+        }));
+        Ok(())
+    }
+}
+
+/// Reads the executables of known browsers registered under `App Paths`, machine-wide and for the user.
+#[cfg(target_os = "windows")]
+fn app_paths() -> Vec<PathBuf> {
+    let mut names: Vec<&str> = WINDOWS_EXECUTABLE_LIST.iter().map(|(name, _, _)| *name).collect();
+    names.dedup();
+
+    [HKEY_LOCAL_MACHINE, HKEY_CURRENT_USER]
+        .iter()
+        .filter_map(|root| {
+            RegKey::predef(*root).open_subkey(r"Software\Microsoft\Windows\CurrentVersion\App Paths").ok()
+        })
+        .flat_map(|app_paths| {
+            names
+                .iter()
+                .filter_map(|name| app_paths.open_subkey(name).ok()?.get_value::<OsString, _>("").ok())
+                .map(|command| split_command(&command).0)
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Lists the executables of `WINDOWS_INSTALL_PATH_LIST` below each Program Files directory and
+/// `%LOCALAPPDATA%`, whether or not they exist.
+#[cfg(target_os = "windows")]
+pub(crate) fn install_paths() -> Vec<PathBuf> {
+    let mut roots: Vec<PathBuf> = ["ProgramW6432", "ProgramFiles", "ProgramFiles(x86)", "LOCALAPPDATA"]
+        .iter()
+        .filter_map(env::var_os)
+        .map(PathBuf::from)
+        .collect();
+    roots.dedup();
+
+    roots.iter().flat_map(|root| WINDOWS_INSTALL_PATH_LIST.iter().map(|(relative, _)| root.join(relative))).collect()
+}
+
+/// Splits a registry command into the executable and the arguments it is registered with, e.g.
+/// `"C:\...\launcher.exe" --flag "%1"`, leaving out the placeholder for the URLs. Like the shell, an unquoted
+/// command naming an existing file is taken as a path with spaces.
+#[cfg(target_os = "windows")]
+fn split_command(command: &OsStr) -> (PathBuf, Vec<String>) {
+    use std::os::windows::ffi::{OsStrExt, OsStringExt};
+
+    if Path::new(command).is_file() {
+        return (PathBuf::from(command), vec![]);
+    }
+    let units: Vec<u16> = command.encode_wide().collect();
+    let mut argv = cmdline::split(&units).into_iter();
+    let executable = PathBuf::from(OsString::from_wide(&argv.next().unwrap_or_default()));
+    let args = argv
+        .map(|argument| String::from_utf16_lossy(&argument))
+        // the shell's placeholders for the URLs, which launching appends
+        .filter(|argument| !matches!(argument.as_str(), "%1" | "%l" | "%L" | "%*"))
+        .collect();
+    (executable, args)
+}
+
+/// Names the browser at an executable found by `AppPathsBackend` or in a package manager's directory, taking
+/// the display name from the install directory table. The version is left empty.
+#[cfg(target_os = "windows")]
+pub(crate) fn fallback_browser(path: PathBuf) -> Option<Browser> {
+    let lowercase = path.to_string_lossy().to_lowercase();
+    let browser_type = windows_browser_type("", &lowercase, "")?;
+    let display_name = WINDOWS_INSTALL_PATH_LIST
+        .iter()
+        .find(|(relative, _)| lowercase.ends_with(&format!(r"\{}", relative.to_lowercase())))
+        .map_or(browser_type.clone(), |(_, display_name)| display_name.to_string());
+
+    Some(Browser {
+        browser_type,
+        display_name,
+        path,
+        exec: None,
+        args: vec![],
+        version: String::new(),
+        raw_version: None,
+        install_source: InstallSource::System,
+        source_id: None,
+        source_path: None,
+        package: None,
+    })
+}
+
+/// Finds browsers installed as Microsoft Store packages, which carry their version in the package name.
+#[cfg(target_os = "windows")]
+pub struct MsStoreBackend;
+
+#[cfg(target_os = "windows")]
+impl DetectionBackend for MsStoreBackend {
+    fn name(&self) -> &str {
+        "msstore"
+    }
+
+    fn detect(&self, _context: &ScanContext, sink: &mut Vec<Browser>) -> Result<(), Error> {
+        sink.extend(msstore::detect());
+        Ok(())
+    }
+}
+
+/// Finds browsers declared by desktop entries in the user's and the system's XDG data directories, including
+/// the ones exported by Flatpak and Snap, and probes their versions.
+#[cfg(target_os = "linux")]
+pub struct DesktopEntryBackend;
+
+#[cfg(target_os = "linux")]
+impl DetectionBackend for DesktopEntryBackend {
+    fn name(&self) -> &str {
+        "desktop-entries"
+    }
+
+    fn detect(&self, context: &ScanContext, sink: &mut Vec<Browser>) -> Result<(), Error> {
+        // the user's entries override the system ones with the same ID, e.g. to hide them
+        let browsers = desktop_files(&application_dirs())
+            .into_iter()
+            .filter_map(|file| {
+                let browser = match file.shadowed {
+                    true => Err(SkipReason::Duplicate),
+                    false => desktop_entry_browser(&file, context.include_no_display),
+                };
+                context.report(file.path.to_string_lossy(), SourceOutcome::of(&browser));
+                browser.ok()
+            })
+            .collect();
+
+        // the version is probed in parallel
+        sink.extend(parallel::map(browsers, context.concurrency(), |mut browser| {
+            if let Some(version) = context.known_version(&browser) {
+                browser.version = version;
+            } else if let Some(exec) = browser.exec.as_deref().filter(|_| {
+                context.version_probe() && !LINUX_VERSION_PROBE_IGNORE_LIST.contains(&browser.browser_type.as_str())
+            }) {
+                browser.version = get_version_output(exec, context.probe_timeout());
+            }
+            browser
+        }));
+        Ok(())
+    }
+}
+
+/// Reads a desktop entry naming a known browser or declaring itself as a web browser. The version is
+/// left empty.
+///
+/// Entries marked `Hidden=true` are skipped as deleted, and `NoDisplay=true` ones unless `include_no_display`
+/// is set. An entry whose `TryExec` program is missing is skipped as not installed.
+#[cfg(target_os = "linux")]
+pub(crate) fn desktop_entry_browser(file: &DesktopFile, include_no_display: bool) -> Result<Browser, SkipReason> {
+    let path = file.path.as_path();
+    let parse_error = |error: String| SkipReason::ParseError { error };
+    let bytes = fs::read_to_string(path).map_err(|error| parse_error(error.to_string()))?;
+    let entry = DesktopEntry::decode(path, &bytes).map_err(|error| parse_error(error.to_string()))?;
+    let base_name = path.file_stem().and_then(|stem| stem.to_str()).ok_or(SkipReason::UnknownName)?;
+    // unknown entries are accepted if they declare themselves as web browsers
+    let browser_type = match LINUX_DESKTOP_ENTRY_NAME_LIST.get(base_name) {
+        Some(browser_type) => browser_type.to_string(),
+        None if LINUX_DESKTOP_ENTRY_IGNORE_LIST.contains(&base_name) => return Err(SkipReason::Ignored),
+        None if is_web_browser_entry(&entry) => generic_browser_type(base_name),
+        None => return Err(SkipReason::UnknownName),
+    };
+    if entry.desktop_entry("Hidden") == Some("true") || (entry.no_display() && !include_no_display) {
+        return Err(SkipReason::Ignored);
+    }
+    if let Some(try_exec) = entry.desktop_entry("TryExec").filter(|try_exec| !try_exec.is_empty()) {
+        // a bare name is looked up on PATH
+        let executable = match try_exec.contains('/') {
+            true => Some(PathBuf::from(try_exec)),
+            false => crate::find_on_path(try_exec),
+        };
+        let metadata = executable.and_then(|executable| fs::metadata(executable).ok());
+        if !metadata.is_some_and(|metadata| metadata.is_file() && is_executable(&metadata)) {
+            return Err(SkipReason::MissingExecutable { path: try_exec.into() });
+        }
+    }
+    let exec = entry.exec().ok_or_else(|| parse_error("no Exec key".to_string()))?;
+
+    let display_name = entry.name(None).map_or(browser_type.clone(), |name| name.to_string());
+    // keep the Exec line so launching can expand the field codes for URLs, the others are resolved here
+    let fields = exec::EntryFields { icon: entry.icon(), name: &display_name, location: path };
+    let exec = exec::resolve(exec, &fields).map_err(parse_error)?;
+    let executable = exec::executable(&exec).ok_or_else(|| parse_error("empty Exec line".to_string()))?;
+
+    Ok(Browser {
+        browser_type,
+        display_name,
+        path: executable,
+        exec: Some(exec),
+        args: vec![],
+        version: String::new(),
+        raw_version: None,
+        install_source: InstallSource::System,
+        source_id: Some(file.id.clone()),
+        source_path: Some(path.to_path_buf()),
+        package: None,
+    })
+}
+
+/// Finds Tor Browser, which is extracted into a user directory instead of being registered.
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+pub struct TorBackend;
+
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+impl DetectionBackend for TorBackend {
+    fn name(&self) -> &str {
+        "tor"
+    }
+
+    fn detect(&self, _context: &ScanContext, sink: &mut Vec<Browser>) -> Result<(), Error> {
+        sink.extend(tor::detect());
+        Ok(())
+    }
+}
+
+/// Finds the browsers installed on the Windows side when running under WSL.
+#[cfg(target_os = "linux")]
+pub struct WslBackend;
+
+#[cfg(target_os = "linux")]
+impl DetectionBackend for WslBackend {
+    fn name(&self) -> &str {
+        "wsl"
+    }
+
+    fn detect(&self, context: &ScanContext, sink: &mut Vec<Browser>) -> Result<(), Error> {
+        if wsl::is_wsl() {
+            sink.extend(wsl::detect(context));
+        }
+        Ok(())
+    }
+}
+
+/// Lists a fixed set of browsers, so tests don't depend on what is installed on the machine.
+///
+/// Use with `BrowserFinder::without_builtin_backends`. Fixtures can be recorded with
+/// `rsbrowsers list --format json`.
+#[derive(Clone, Default, Debug)]
+pub struct MockBackend {
+    browsers: Vec<Browser>,
+}
+
+impl MockBackend {
+    pub fn new(browsers: Vec<Browser>) -> Self {
+        MockBackend { browsers }
+    }
+
+    /// Reads a JSON array of browsers in the `Browser` serialization, e.g.
+    /// `[{"browserType": "chrome", "path": "/usr/bin/chrome", "displayName": "Chrome", "version": "120.0"}]`.
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        let browsers = serde_json::from_str(json).map_err(|e| Error::Discovery(format!("invalid fixture: {e}")))?;
+        Ok(MockBackend { browsers })
+    }
+
+    /// Reads a JSON fixture from a file, see `from_json`.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let json = fs::read_to_string(path)
+            .map_err(|e| Error::Discovery(format!("cannot read fixture {}: {e}", path.display())))?;
+        Self::from_json(&json)
+    }
+}
+
+impl DetectionBackend for MockBackend {
+    fn name(&self) -> &str {
+        "mock"
+    }
+
+    fn detect(&self, _context: &ScanContext, sink: &mut Vec<Browser>) -> Result<(), Error> {
+        sink.extend(self.browsers.iter().cloned());
+        Ok(())
+    }
+}
+
+/// The platform's backends in the order their results are listed.
+pub(crate) fn builtin() -> Vec<Arc<dyn DetectionBackend>> {
+    vec![
+        #[cfg(target_os = "macos")]
+        Arc::new(SpotlightBackend),
+        #[cfg(target_os = "windows")]
+        Arc::new(RegistryBackend),
+        #[cfg(target_os = "windows")]
+        Arc::new(AppPathsBackend),
+        #[cfg(target_os = "windows")]
+        Arc::new(MsStoreBackend),
+        #[cfg(target_os = "linux")]
+        Arc::new(DesktopEntryBackend),
+        #[cfg(any(target_os = "windows", target_os = "linux"))]
+        Arc::new(TorBackend),
+        #[cfg(target_os = "linux")]
+        Arc::new(WslBackend),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_desktop_entry_browser() {
+        use crate::SkipReason;
+        use crate::backend::desktop_entry_browser;
+        use crate::desktop_files::DesktopFile;
+        use std::{env, fs};
+
+        let dir = env::temp_dir().join(format!("rsbrowsers-desktop-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("firefox.desktop");
+        let file = DesktopFile { id: "firefox.desktop".to_string(), path: path.clone(), shadowed: false };
+        let entry = |keys: &str| {
+            let contents = format!("[Desktop Entry]\nType=Application\nName=Firefox\nExec=firefox %u\n{keys}");
+            fs::write(&path, contents).unwrap();
+            desktop_entry_browser(&file, false)
+        };
+
+        let browser = entry("").unwrap();
+        assert_eq!((browser.browser_type.as_str(), browser.source_id.as_deref()), ("firefox", Some("firefox.desktop")));
+        assert_eq!(browser.exec.as_deref(), Some("firefox"));
+        assert_eq!(browser.path, crate::find_on_path("firefox").unwrap_or("firefox".into()));
+        assert_eq!(entry("Hidden=true").unwrap_err(), SkipReason::Ignored);
+        assert_eq!(entry("NoDisplay=true").unwrap_err(), SkipReason::Ignored);
+        assert_eq!(desktop_entry_browser(&file, true).unwrap().browser_type, "firefox");
+        assert!(entry("TryExec=sh").is_ok());
+        assert!(entry("TryExec=/bin/sh").is_ok());
+        assert_eq!(
+            entry("TryExec=/opt/missing/firefox").unwrap_err(),
+            SkipReason::MissingExecutable { path: "/opt/missing/firefox".into() }
+        );
+        // not executable
+        assert!(matches!(entry(&format!("TryExec={}", path.display())), Err(SkipReason::MissingExecutable { .. })));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(target_os = "windows")]
+    #[test]
+    fn test_fallback_browser() {
+        use crate::backend::fallback_browser;
+        use std::path::PathBuf;
+
+        let vivaldi =
+            fallback_browser(PathBuf::from(r"C:\Users\me\AppData\Local\Vivaldi\Application\vivaldi.exe")).unwrap();
+        assert_eq!((vivaldi.browser_type.as_str(), vivaldi.display_name.as_str()), ("vivaldi", "Vivaldi"));
+
+        let edge =
+            fallback_browser(PathBuf::from(r"C:\Program Files (x86)\Microsoft\Edge Beta\Application\msedge.exe"))
+                .unwrap();
+        assert_eq!((edge.browser_type.as_str(), edge.display_name.as_str()), ("msedge-beta", "Microsoft Edge Beta"));
+
+        let portable = fallback_browser(PathBuf::from(r"D:\Tools\Waterfox Portable\waterfox.exe")).unwrap();
+        assert_eq!((portable.browser_type.as_str(), portable.display_name.as_str()), ("waterfox", "waterfox"));
+
+        assert!(fallback_browser(PathBuf::from(r"C:\Windows\notepad.exe")).is_none());
+    }
+
+    #[cfg(target_os = "windows")]
+    #[test]
+    fn test_split_command() {
+        use crate::backend::split_command;
+        use std::ffi::OsStr;
+        use std::path::PathBuf;
+
+        let launcher = r"C:\Program Files\Example\launcher.exe";
+        assert_eq!(split_command(OsStr::new(&format!(r#""{launcher}""#))), (PathBuf::from(launcher), vec![]));
+        assert_eq!(
+            split_command(OsStr::new(&format!(r#""{launcher}" --flag "%1""#))),
+            (PathBuf::from(launcher), vec!["--flag".to_string()])
+        );
+    }
+}
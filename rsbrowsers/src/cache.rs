@@ -0,0 +1,191 @@
+use crate::Browser;
+use crate::custom::Bundle;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+/// The cache shared by all finders in the process.
+static CACHE: LazyLock<Cache> = LazyLock::new(|| Cache::new(disk_cache_path()));
+
+/// Versions read from executables, by path, with the modification time they were read at.
+#[cfg(target_os = "windows")]
+static FILE_VERSIONS: LazyLock<Mutex<HashMap<PathBuf, (SystemTime, String)>>> = LazyLock::new(Default::default);
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct DiskCache {
+    key: String,
+    scanned_at: SystemTime,
+    browsers: Vec<Browser>,
+}
+
+/// Scan results by `key`, in memory and optionally in a file.
+pub(crate) struct Cache {
+    memory: Mutex<HashMap<String, (Instant, Vec<Browser>)>>,
+    disk_path: Option<PathBuf>,
+}
+
+fn disk_cache_path() -> Option<PathBuf> {
+    dirs::cache_dir().map(|path| path.join("rsbrowsers").join("browsers.json"))
+}
+
+pub(crate) fn shared() -> &'static Cache {
+    &CACHE
+}
+
+/// Tells apart the results of finders that scan differently, i.e. with another config file or extra bundle IDs.
+pub(crate) fn key(config_path: Option<&Path>, bundles: &[Bundle]) -> String {
+    let mut key = config_path.map(|path| path.to_string_lossy().into_owned()).unwrap_or_default();
+    for Bundle { browser_type, bundle_id, version_key } in bundles {
+        key.push_str(&format!("\n{browser_type} {bundle_id} {version_key}"));
+    }
+    key
+}
+
+impl Cache {
+    /// A cache persisting results to the file at `disk_path` if given.
+    pub(crate) fn new(disk_path: Option<PathBuf>) -> Self {
+        Cache { memory: Mutex::new(HashMap::new()), disk_path }
+    }
+
+    /// Returns the results of the last scan with `key` if they are younger than `ttl`, checking memory first.
+    pub(crate) fn load(&self, key: &str, ttl: Duration, disk: bool) -> Option<Vec<Browser>> {
+        if let Some((_, browsers)) =
+            self.memory.lock().unwrap().get(key).filter(|(scanned_at, _)| scanned_at.elapsed() < ttl)
+        {
+            return Some(browsers.clone());
+        }
+
+        if !disk {
+            return None;
+        }
+
+        let cache: DiskCache = serde_json::from_slice(&fs::read(self.disk_path.as_ref()?).ok()?).ok()?;
+        let age = SystemTime::now().duration_since(cache.scanned_at).ok()?;
+        if cache.key != key || age >= ttl {
+            return None;
+        }
+
+        // keep the disk cache's age so both expire together
+        if let Some(scanned_at) = Instant::now().checked_sub(age) {
+            self.memory.lock().unwrap().insert(cache.key, (scanned_at, cache.browsers.clone()));
+        }
+        Some(cache.browsers)
+    }
+
+    pub(crate) fn store(&self, key: &str, browsers: &[Browser], disk: bool) {
+        self.memory.lock().unwrap().insert(key.to_owned(), (Instant::now(), browsers.to_vec()));
+
+        // paths that aren't valid Unicode would be changed by JSON, so such scans are only kept in memory
+        let unicode = browsers.iter().all(|browser| {
+            browser.path.to_str().is_some() && browser.source_path.as_ref().is_none_or(|path| path.to_str().is_some())
+        });
+        if let Some(path) = self.disk_path.as_ref().filter(|_| disk && unicode) {
+            let cache = DiskCache { key: key.to_owned(), scanned_at: SystemTime::now(), browsers: browsers.to_vec() };
+            // a cache that cannot be written only costs a rescan
+            if let Some(parent) = path.parent() {
+                let _ = fs::create_dir_all(parent);
+            }
+            let _ = fs::write(path, serde_json::to_vec(&cache).unwrap());
+        }
+    }
+
+    fn clear(&self) {
+        self.memory.lock().unwrap().clear();
+        if let Some(path) = &self.disk_path {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+/// Returns the version `read` from the executable at `path`, reusing the previous result until the file is
+/// modified, e.g. by an update.
+#[cfg(target_os = "windows")]
+pub(crate) fn file_version(path: &Path, read: impl FnOnce(&Path) -> String) -> String {
+    let Some(modified) = fs::metadata(path).and_then(|metadata| metadata.modified()).ok() else {
+        return read(path);
+    };
+    if let Some((_, version)) = FILE_VERSIONS.lock().unwrap().get(path).filter(|(at, _)| *at == modified) {
+        return version.to_owned();
+    }
+
+    // read without the lock since executables are probed in parallel
+    let version = read(path);
+    FILE_VERSIONS.lock().unwrap().insert(path.to_path_buf(), (modified, version.to_owned()));
+    version
+}
+
+/// Clears the shared cache and the versions read from executables.
+pub(crate) fn clear() {
+    CACHE.clear();
+    #[cfg(target_os = "windows")]
+    FILE_VERSIONS.lock().unwrap().clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Browser;
+    use crate::cache::{Cache, key};
+    use crate::custom::Bundle;
+    use std::path::Path;
+    use std::time::Duration;
+    use std::{env, fs};
+
+    #[test]
+    fn test_memory_cache() {
+        let browser = Browser { version: "128.0".to_string(), ..Browser::test_fixture("firefox", "/usr/bin/firefox") };
+        let cache = Cache::new(None);
+
+        cache.store("", &[browser], false);
+        assert_eq!(cache.load("", Duration::from_secs(60), false).unwrap().len(), 1);
+        assert!(cache.load("", Duration::ZERO, false).is_none());
+        assert!(cache.load("other", Duration::from_secs(60), false).is_none());
+
+        cache.clear();
+        assert!(cache.load("", Duration::from_secs(60), false).is_none());
+    }
+
+    #[test]
+    fn test_disk_cache() {
+        let browser = Browser { version: "128.0".to_string(), ..Browser::test_fixture("firefox", "/usr/bin/firefox") };
+        let path = env::temp_dir().join(format!("rsbrowsers-cache-{}/browsers.json", std::process::id()));
+
+        Cache::new(Some(path.clone())).store("", &[browser], true);
+        // a new process only has the file
+        let cache = Cache::new(Some(path.clone()));
+        assert!(cache.load("other", Duration::from_secs(60), true).is_none());
+        assert_eq!(cache.load("", Duration::from_secs(60), true).unwrap().len(), 1);
+
+        cache.clear();
+        assert!(!path.exists());
+        fs::remove_dir(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_key() {
+        let bundle = Bundle {
+            browser_type: "thorium".to_string(),
+            bundle_id: "org.chromium.Thorium".to_string(),
+            version_key: "CFBundleShortVersionString".to_string(),
+        };
+        assert_eq!(key(None, &[]), "");
+        assert_ne!(key(None, &[bundle]), "");
+        assert_ne!(key(Some(Path::new("browsers.toml")), &[]), "");
+    }
+
+    #[cfg(target_os = "windows")]
+    #[test]
+    fn test_file_version() {
+        use crate::cache::file_version;
+        use std::{env, fs};
+
+        let path = env::temp_dir().join(format!("rsbrowsers-version-{}.exe", std::process::id()));
+        fs::write(&path, b"MZ").unwrap();
+
+        assert_eq!(file_version(&path, |_| "1.0".to_string()), "1.0");
+        // unchanged files are not read again
+        assert_eq!(file_version(&path, |_| "2.0".to_string()), "1.0");
+        fs::remove_file(&path).unwrap();
+    }
+}
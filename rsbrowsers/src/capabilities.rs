@@ -0,0 +1,106 @@
+use crate::launch::{is_chromium, is_gecko, private_flag};
+use crate::{Browser, Engine};
+
+/// What a browser supports, derived from its type and version with `Browser::capabilities`.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Capabilities {
+    pub engine: Engine,
+    /// See `LaunchOptions::headless`.
+    pub supports_headless: bool,
+    /// The Chrome DevTools Protocol, see `LaunchOptions::remote_debugging`.
+    pub supports_cdp: bool,
+    /// WebDriver BiDi, through the browser's WebDriver server or, for Firefox, the browser itself, see
+    /// `LaunchOptions::remote_debugging`.
+    pub supports_webdriver_bidi: bool,
+    /// A command-line flag for a private window, see `LaunchOptions::incognito`.
+    pub supports_incognito_flag: bool,
+    /// Chromium's `--app` window without tabs or toolbars.
+    pub supports_app_mode: bool,
+    /// A temporary profile, see `LaunchOptions::ephemeral_profile`.
+    pub supports_ephemeral_profile: bool,
+    /// See `LaunchOptions::load_extension`.
+    pub supports_extensions: bool,
+}
+
+const HEADLESS_VERSION_LIST: &[(&str, u64)] = &[
+    // browser family, first major version with headless mode (other browsers don't report the engine version)
+    ("chrome", 59),
+    ("chromium", 59),
+    ("firefox", 56),
+];
+
+const WEBDRIVER_BIDI_VERSION_LIST: &[(&str, u64)] = &[
+    // browser family, first major version with WebDriver BiDi
+    ("chrome", 115),
+    ("chromium", 115),
+    ("msedge", 115),
+    ("firefox", 102),
+];
+
+impl Browser {
+    /// Returns what the browser supports, e.g. to pick one an automation framework can drive.
+    pub fn capabilities(&self) -> Capabilities {
+        let browser_type = self.browser_type.as_str();
+        let chromium = is_chromium(browser_type);
+        let gecko = is_gecko(browser_type);
+        Capabilities {
+            engine: self.engine(),
+            supports_headless: self.supports_headless(),
+            supports_cdp: chromium,
+            supports_webdriver_bidi: (chromium || gecko) && self.at_least(WEBDRIVER_BIDI_VERSION_LIST),
+            supports_incognito_flag: private_flag(browser_type).is_some(),
+            supports_app_mode: chromium,
+            supports_ephemeral_profile: chromium || gecko,
+            supports_extensions: chromium || gecko,
+        }
+    }
+
+    /// Returns whether the browser can run without a window, see `LaunchOptions::headless`.
+    pub fn supports_headless(&self) -> bool {
+        let browser_type = self.browser_type.as_str();
+        (is_chromium(browser_type) || is_gecko(browser_type)) && self.at_least(HEADLESS_VERSION_LIST)
+    }
+
+    /// Returns whether the version is at least the one listed for the browser's family. Browsers that are
+    /// not listed or have no known version pass.
+    fn at_least(&self, versions: &[(&str, u64)]) -> bool {
+        let minimum = versions.iter().find(|(family, _)| *family == self.family()).map(|(_, major)| *major);
+        match (minimum, self.parsed_version()) {
+            (Some(minimum), Some(version)) => version.major() >= minimum,
+            _ => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Browser, Engine};
+    use std::path::PathBuf;
+
+    fn browser(browser_type: &str, version: &str) -> Browser {
+        Browser { version: version.to_string(), ..Browser::test_fixture(browser_type, PathBuf::new()) }
+    }
+
+    #[test]
+    fn test_capabilities() {
+        let chrome = browser("chrome", "120.0.6099.109").capabilities();
+        assert_eq!(chrome.engine, Engine::Blink);
+        assert!(chrome.supports_cdp && chrome.supports_webdriver_bidi && chrome.supports_app_mode);
+
+        let firefox = browser("firefox-esr", "91.13.0").capabilities();
+        assert!(firefox.supports_incognito_flag && !firefox.supports_cdp && !firefox.supports_webdriver_bidi);
+
+        let safari = browser("safari", "17.2.1").capabilities();
+        assert!(!safari.supports_headless && !safari.supports_incognito_flag && !safari.supports_extensions);
+    }
+
+    #[test]
+    fn test_supports_headless() {
+        assert!(browser("chrome-beta", "121.0.6167.16").supports_headless());
+        assert!(browser("firefox", "").supports_headless());
+        assert!(!browser("firefox", "52.9.0").supports_headless());
+        assert!(browser("vivaldi", "6.5.3206.48").supports_headless());
+        assert!(!browser("safari", "17.2.1").supports_headless());
+    }
+}
@@ -0,0 +1,219 @@
+//! A minimal client for the HTTP endpoints of the Chrome DevTools Protocol, enough to check that a browser
+//! launched with `LaunchOptions::remote_debugging` is up, or to find one already serving them, without
+//! pulling in a full CDP crate.
+
+use crate::{Browser, Version, devtools, parallel, process};
+use serde::de::DeserializeOwned;
+use std::io;
+use std::ops::RangeInclusive;
+
+/// The ports `discover_endpoints` is usually given, from the common `--remote-debugging-port=9222` up.
+pub const DEFAULT_PORTS: RangeInclusive<u16> = 9222..=9230;
+
+/// A client for the DevTools server of a Chromium-based browser on a localhost port, see
+/// `LaunchedBrowser::cdp`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Client {
+    port: u16,
+}
+
+/// The answer of `/json/version`.
+#[derive(serde::Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct BrowserVersion {
+    /// The product and version, e.g. `Chrome/120.0.6099.109`.
+    #[serde(rename = "Browser")]
+    pub browser: String,
+    #[serde(rename = "Protocol-Version")]
+    pub protocol_version: String,
+    #[serde(rename = "User-Agent")]
+    pub user_agent: String,
+    /// The `ws://` endpoint of the browser target, e.g. `ws://127.0.0.1:9222/devtools/browser/<id>`.
+    #[serde(rename = "webSocketDebuggerUrl")]
+    pub websocket_debugger_url: String,
+}
+
+/// A page, worker or other target listed by `/json/list`.
+#[derive(serde::Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct Target {
+    pub id: String,
+    /// e.g. `page`, `iframe` or `service_worker`.
+    #[serde(rename = "type")]
+    pub target_type: String,
+    pub title: String,
+    pub url: String,
+    /// `None` while another client is attached to the target.
+    #[serde(rename = "webSocketDebuggerUrl")]
+    pub websocket_debugger_url: Option<String>,
+}
+
+/// A live DevTools endpoint found by `discover_endpoints`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Endpoint {
+    pub port: u16,
+    pub version: BrowserVersion,
+    /// The detected browser serving the endpoint, `None` if no browser matches its product and version.
+    pub browser: Option<Browser>,
+}
+
+/// Probes localhost `ports`, e.g. `DEFAULT_PORTS`, for DevTools endpoints that are already open, to attach
+/// to a browser started for debugging by something else. Returns them in the order of `ports`.
+///
+/// Each endpoint is matched to one of `browsers` by the product in `/json/version`, e.g.
+/// `Edg/120.0.2210.91` for Edge or `Chrome/120.0.6099.109` for a Chrome or Chromium of that version,
+/// preferring a browser that is running. Other Chromium-based browsers report a Chrome version and stay
+/// unmatched.
+pub fn discover_endpoints<I: IntoIterator<Item = u16>>(ports: I, browsers: &[Browser]) -> Vec<Endpoint> {
+    let ports: Vec<u16> = ports.into_iter().collect();
+    // a port held by something other than an HTTP server only answers once the read times out
+    let concurrency = ports.len().max(1);
+    parallel::map(ports, concurrency, |port| Some((port, Client::new(port).version().ok()?)))
+        .into_iter()
+        .flatten()
+        .map(|(port, version)| {
+            let browser = serving_browser(&version, browsers).cloned();
+            Endpoint { port, version, browser }
+        })
+        .collect()
+}
+
+/// Picks the browser whose product and version are the ones reported by `/json/version`.
+fn serving_browser<'a>(version: &BrowserVersion, browsers: &'a [Browser]) -> Option<&'a Browser> {
+    let (product, product_version) = version.browser.split_once('/')?;
+    let product_version: Version = product_version.parse().ok()?;
+    let candidates: Vec<&Browser> = browsers
+        .iter()
+        .filter(|browser| match product {
+            "Edg" => browser.family() == "msedge" && browser.parsed_version().as_ref() == Some(&product_version),
+            "Chrome" | "HeadlessChrome" => browser.chromium_version().as_ref() == Some(&product_version),
+            _ => false,
+        })
+        .collect();
+
+    match candidates.len() {
+        0 | 1 => candidates.first().copied(),
+        _ => candidates.iter().find(|browser| process::is_running(&browser.path)).or(candidates.first()).copied(),
+    }
+}
+
+impl Client {
+    /// Creates a client for the DevTools server on `port` of localhost. Nothing is sent until a method is
+    /// called.
+    pub fn new(port: u16) -> Self {
+        Client { port }
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Fetches `/json/version`, with the browser's version, user agent and WebSocket debugger URL.
+    pub fn version(&self) -> io::Result<BrowserVersion> {
+        self.get("/json/version")
+    }
+
+    /// Fetches `/json/list`, the targets that can be attached to.
+    pub fn targets(&self) -> io::Result<Vec<Target>> {
+        self.get("/json/list")
+    }
+
+    /// Returns whether the browser answers `/json/version`, which it stops doing when it hangs or exits.
+    pub fn is_responsive(&self) -> bool {
+        self.version().is_ok()
+    }
+
+    fn get<T: DeserializeOwned>(&self, path: &str) -> io::Result<T> {
+        let body = devtools::get(self.port, path)?;
+        serde_json::from_str(&body).map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Browser;
+    use crate::cdp::{Client, Target, discover_endpoints, serving_browser};
+    use crate::devtools::tests::serve;
+
+    #[test]
+    fn test_client() {
+        let version = r#"{
+            "Browser": "Chrome/120.0.6099.109",
+            "Protocol-Version": "1.3",
+            "User-Agent": "Mozilla/5.0 (X11; Linux x86_64) Chrome/120.0.6099.109 Safari/537.36",
+            "V8-Version": "12.0.267.8",
+            "WebKit-Version": "537.36",
+            "webSocketDebuggerUrl": "ws://127.0.0.1:9222/devtools/browser/1"
+        }"#;
+        let targets = r#"[{
+            "description": "",
+            "id": "A1",
+            "title": "Example",
+            "type": "page",
+            "url": "https://example.com/"
+        }]"#;
+        let client = Client::new(serve(vec![version, targets, "not json"]));
+
+        let version = client.version().unwrap();
+        assert_eq!(version.browser, "Chrome/120.0.6099.109");
+        assert_eq!(version.websocket_debugger_url, "ws://127.0.0.1:9222/devtools/browser/1");
+        assert!(version.user_agent.contains("Chrome/120"));
+        assert_eq!(
+            client.targets().unwrap(),
+            [Target {
+                id: "A1".to_string(),
+                target_type: "page".to_string(),
+                title: "Example".to_string(),
+                url: "https://example.com/".to_string(),
+                websocket_debugger_url: None,
+            }]
+        );
+        assert!(!client.is_responsive());
+    }
+
+    fn browser(browser_type: &str, version: &str) -> Browser {
+        Browser {
+            version: version.to_string(),
+            ..Browser::test_fixture(browser_type, format!("/nonexistent/{browser_type}"))
+        }
+    }
+
+    #[test]
+    fn test_discover_endpoints() {
+        let version = r#"{
+            "Browser": "Edg/120.0.2210.91",
+            "Protocol-Version": "1.3",
+            "User-Agent": "Mozilla/5.0 (Windows NT 10.0; Win64; x64) Chrome/120.0.0.0 Edg/120.0.2210.91",
+            "webSocketDebuggerUrl": "ws://127.0.0.1:9222/devtools/browser/1"
+        }"#;
+        let port = serve(vec![version]);
+        let browsers = [browser("chrome", "120.0.6099.109"), browser("msedge", "120.0.2210.91")];
+
+        let endpoints = discover_endpoints([port], &browsers);
+        assert_eq!(endpoints.len(), 1);
+        assert_eq!(endpoints[0].port, port);
+        assert_eq!(endpoints[0].browser, Some(browsers[1].clone()));
+        assert!(discover_endpoints([], &browsers).is_empty());
+    }
+
+    #[test]
+    fn test_serving_browser() {
+        let browsers = [
+            browser("brave", "1.61.109"),
+            browser("chrome", "120.0.6099.109"),
+            browser("chromium", "120.0.6099.109"),
+            browser("msedge", "120.0.2210.91"),
+        ];
+        let version = |product: &str| crate::cdp::BrowserVersion {
+            browser: product.to_string(),
+            protocol_version: "1.3".to_string(),
+            user_agent: "".to_string(),
+            websocket_debugger_url: "".to_string(),
+        };
+
+        // neither is running, so the first one is taken
+        assert_eq!(serving_browser(&version("Chrome/120.0.6099.109"), &browsers), Some(&browsers[1]));
+        assert_eq!(serving_browser(&version("HeadlessChrome/120.0.6099.109"), &browsers), Some(&browsers[1]));
+        assert_eq!(serving_browser(&version("Edg/120.0.2210.91"), &browsers), Some(&browsers[3]));
+        assert_eq!(serving_browser(&version("Chrome/121.0.6167.85"), &browsers), None);
+        assert_eq!(serving_browser(&version("Opera/105.0.4970.48"), &browsers), None);
+    }
+}
@@ -0,0 +1,98 @@
+//! Splitting of Windows command lines, such as the `shell\open\command` values browsers register, with the
+//! rules of `CommandLineToArgvW`. Works on UTF-16 so paths that aren't valid Unicode survive.
+
+const QUOTE: u16 = b'"' as u16;
+const BACKSLASH: u16 = b'\\' as u16;
+
+fn is_blank(unit: u16) -> bool {
+    unit == u16::from(b' ') || unit == u16::from(b'\t')
+}
+
+/// Splits `command` into the program and its arguments.
+///
+/// The program ends at the first blank, or at the closing quote if it starts with one, and takes no escapes.
+/// In arguments, backslashes only escape quotes: `2n` backslashes and a quote become `n` backslashes and
+/// toggle quoting, `2n + 1` backslashes and a quote become `n` backslashes and a literal quote, and `""`
+/// inside quotes is a literal quote.
+pub(crate) fn split(command: &[u16]) -> Vec<Vec<u16>> {
+    let mut rest = command;
+    while rest.first().is_some_and(|unit| is_blank(*unit)) {
+        rest = &rest[1..];
+    }
+    let program = match rest.strip_prefix(&[QUOTE]) {
+        Some(quoted) => {
+            let end = quoted.iter().position(|unit| *unit == QUOTE).unwrap_or(quoted.len());
+            rest = quoted.get(end + 1..).unwrap_or_default();
+            &quoted[..end]
+        }
+        None => {
+            let end = rest.iter().position(|unit| is_blank(*unit)).unwrap_or(rest.len());
+            let program = &rest[..end];
+            rest = &rest[end..];
+            program
+        }
+    };
+    if program.is_empty() && rest.is_empty() {
+        return vec![];
+    }
+
+    let mut arguments = vec![program.to_vec()];
+    let mut argument: Option<Vec<u16>> = None;
+    let mut quoted = false;
+    let mut units = rest.iter().copied().peekable();
+    while let Some(unit) = units.next() {
+        match unit {
+            BACKSLASH => {
+                let mut backslashes = 1;
+                while units.next_if_eq(&BACKSLASH).is_some() {
+                    backslashes += 1;
+                }
+                let argument = argument.get_or_insert_with(Vec::new);
+                match units.next_if_eq(&QUOTE) {
+                    Some(_) => {
+                        argument.extend(vec![BACKSLASH; backslashes / 2]);
+                        match backslashes % 2 {
+                            1 => argument.push(QUOTE),
+                            _ => quoted = !quoted,
+                        }
+                    }
+                    None => argument.extend(vec![BACKSLASH; backslashes]),
+                }
+            }
+            QUOTE if quoted && units.next_if_eq(&QUOTE).is_some() => argument.get_or_insert_with(Vec::new).push(QUOTE),
+            QUOTE => {
+                argument.get_or_insert_with(Vec::new);
+                quoted = !quoted;
+            }
+            unit if is_blank(unit) && !quoted => arguments.extend(argument.take()),
+            unit => argument.get_or_insert_with(Vec::new).push(unit),
+        }
+    }
+    arguments.extend(argument);
+    arguments
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmdline::split;
+
+    fn split_str(command: &str) -> Vec<String> {
+        let units: Vec<u16> = command.encode_utf16().collect();
+        split(&units).iter().map(|argument| String::from_utf16_lossy(argument)).collect()
+    }
+
+    #[test]
+    fn test_split() {
+        let chrome = r"C:\Program Files\Google\Chrome\Application\chrome.exe";
+        assert_eq!(split_str(&format!(r#""{chrome}""#)), [chrome]);
+        assert_eq!(split_str(&format!(r#""{chrome}" --single-argument "%1""#)), [chrome, "--single-argument", "%1"]);
+        assert_eq!(split_str(r"C:\Tools\browser.exe --flag"), [r"C:\Tools\browser.exe", "--flag"]);
+        // the program takes no escapes
+        assert_eq!(split_str(r#""C:\Tools\" a"#), [r"C:\Tools\", "a"]);
+        assert_eq!(
+            split_str(r#"a.exe "b c" d\e \"f\" "g\\" h\\\"i "" "j""k""#),
+            ["a.exe", "b c", r"d\e", r#""f""#, r"g\", r#"h\"i"#, "", r#"j"k"#]
+        );
+        assert!(split_str("  ").is_empty());
+    }
+}
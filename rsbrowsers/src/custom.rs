@@ -0,0 +1,203 @@
+use crate::version::{find_version, probe_output};
+use crate::{Browser, Error, InstallSource};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+/// A browser the built-in platform tables don't know about, e.g. a portable install.
+#[derive(Clone, Debug)]
+pub struct CustomBrowser {
+    pub browser_type: String,
+    pub path: PathBuf,
+    /// Defaults to `browser_type`.
+    pub display_name: Option<String>,
+    pub version_probe: VersionProbe,
+}
+
+/// How the version of a custom browser is determined.
+#[derive(Clone, Debug)]
+pub enum VersionProbe {
+    /// Runs the executable with these arguments and takes the first version in its output.
+    Args(Vec<String>),
+    /// Uses this version as-is.
+    Fixed(String),
+    /// Leaves the version empty.
+    None,
+}
+
+impl Default for VersionProbe {
+    fn default() -> Self {
+        VersionProbe::Args(vec!["--version".to_string()])
+    }
+}
+
+/// Contents of the config file.
+#[derive(Default)]
+pub(crate) struct Config {
+    pub(crate) browsers: Vec<CustomBrowser>,
+    pub(crate) bundles: Vec<Bundle>,
+    pub(crate) search_paths: Vec<PathBuf>,
+}
+
+/// An extra entry for the macOS bundle list, in the same shape as the built-in table.
+#[derive(Clone, Debug, serde::Deserialize)]
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub(crate) struct Bundle {
+    pub(crate) browser_type: String,
+    pub(crate) bundle_id: String,
+    #[serde(default = "default_version_key")]
+    pub(crate) version_key: String,
+}
+
+fn default_version_key() -> String {
+    "CFBundleShortVersionString".to_string()
+}
+
+#[derive(serde::Deserialize)]
+struct ConfigFile {
+    #[serde(default)]
+    browser: Vec<ConfigBrowser>,
+    #[serde(default)]
+    bundle: Vec<Bundle>,
+    #[serde(default)]
+    search_paths: Vec<PathBuf>,
+}
+
+#[derive(serde::Deserialize)]
+struct ConfigBrowser {
+    browser_type: String,
+    path: PathBuf,
+    display_name: Option<String>,
+    version: Option<String>,
+    version_args: Option<Vec<String>>,
+}
+
+impl CustomBrowser {
+    pub fn new<P: Into<PathBuf>>(browser_type: String, path: P) -> Self {
+        CustomBrowser { browser_type, path: path.into(), display_name: None, version_probe: VersionProbe::default() }
+    }
+
+    /// Returns the browser if its executable exists, probing its version unless `probe` is false.
+    pub(crate) fn resolve(&self, timeout: Duration, probe: bool) -> Option<Browser> {
+        if !self.path.exists() {
+            return None;
+        }
+
+        let version = match &self.version_probe {
+            VersionProbe::Args(args) if probe => probe_output(Command::new(&self.path).args(args), timeout)
+                .and_then(|output| find_version(&output))
+                .map_or("".to_string(), |version| version.to_string()),
+            VersionProbe::Fixed(version) => version.to_owned(),
+            VersionProbe::Args(_) | VersionProbe::None => "".to_string(),
+        };
+
+        Some(Browser {
+            browser_type: self.browser_type.to_owned(),
+            path: self.path.clone(),
+            exec: None,
+            args: vec![],
+            display_name: self.display_name.to_owned().unwrap_or(self.browser_type.to_owned()),
+            version,
+            raw_version: None,
+            install_source: InstallSource::Custom,
+            source_id: None,
+            source_path: None,
+            package: None,
+        })
+    }
+}
+
+/// Returns the default config file location, e.g. `~/.config/rsbrowsers/browsers.toml` on Linux.
+pub(crate) fn default_config_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|path| path.join("rsbrowsers").join("browsers.toml"))
+}
+
+/// Loads custom browsers from a TOML file with `[[browser]]` tables, and additions to the macOS bundle
+/// list with `[[bundle]]` tables so new browsers can be detected without waiting for a release.
+/// `search_paths` lists extra directories to look for portable browsers in:
+///
+/// ```toml
+/// search_paths = ["D:/PortableApps", "//fileserver/browsers"]
+///
+/// [[browser]]
+/// browser_type = "chromium-portable"
+/// path = "D:/PortableApps/Chromium/chrome.exe"
+/// display_name = "Portable Chromium"  # optional
+/// version = "120.0.6099.109"          # optional, skips probing
+/// version_args = ["--version"]        # optional
+///
+/// [[bundle]]
+/// browser_type = "helium"
+/// bundle_id = "net.imput.helium"
+/// version_key = "CFBundleVersion"     # optional, defaults to CFBundleShortVersionString
+/// ```
+pub(crate) fn load_config(path: &Path) -> Result<Config, Error> {
+    let contents =
+        fs::read_to_string(path).map_err(|e| Error::Discovery(format!("cannot read {}: {e}", path.display())))?;
+    let config: ConfigFile =
+        toml::from_str(&contents).map_err(|e| Error::Discovery(format!("invalid {}: {e}", path.display())))?;
+
+    let browsers = config
+        .browser
+        .into_iter()
+        .map(|browser| CustomBrowser {
+            browser_type: browser.browser_type,
+            path: browser.path,
+            display_name: browser.display_name,
+            version_probe: match (browser.version, browser.version_args) {
+                (Some(version), _) => VersionProbe::Fixed(version),
+                (None, Some(args)) => VersionProbe::Args(args),
+                (None, None) => VersionProbe::default(),
+            },
+        })
+        .collect();
+    Ok(Config { browsers, bundles: config.bundle, search_paths: config.search_paths })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::custom::{VersionProbe, load_config};
+    use std::env;
+    use std::fs;
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    #[test]
+    fn test_load_config() {
+        let path = env::temp_dir().join(format!("rsbrowsers-test-{}.toml", std::process::id()));
+        fs::write(
+            &path,
+            r#"
+            search_paths = ["/opt/portable"]
+
+            [[browser]]
+            browser_type = "chromium-portable"
+            path = "/opt/chromium/chrome"
+            version = "120.0"
+
+            [[browser]]
+            browser_type = "nightly"
+            path = "/opt/nightly/firefox"
+            version_args = ["-v"]
+
+            [[bundle]]
+            browser_type = "helium"
+            bundle_id = "net.imput.helium"
+            "#,
+        )
+        .unwrap();
+
+        let config = load_config(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        let browsers = config.browsers;
+        assert_eq!(browsers.len(), 2);
+        assert!(matches!(&browsers[0].version_probe, VersionProbe::Fixed(v) if v == "120.0"));
+        assert!(matches!(&browsers[1].version_probe, VersionProbe::Args(args) if args == &["-v"]));
+        assert!(browsers[0].resolve(Duration::from_secs(5), true).is_none());
+        assert_eq!(config.bundles.len(), 1);
+        assert_eq!(config.bundles[0].version_key, "CFBundleShortVersionString");
+        assert_eq!(config.search_paths, [PathBuf::from("/opt/portable")]);
+    }
+}
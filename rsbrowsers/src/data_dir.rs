@@ -0,0 +1,165 @@
+use crate::Browser;
+use std::path::PathBuf;
+
+/// The directory a user data path is relative to. Each platform's table uses only some of them.
+#[derive(Clone, Copy)]
+#[allow(dead_code)]
+enum Base {
+    Home,
+    Config,
+    Data,
+    LocalData,
+}
+
+#[cfg(target_os = "macos")]
+const USER_DATA_DIR_LIST: &[(&str, Base, &str)] = &[
+    // browser name, base directory, user data directory relative to it
+    ("arc", Base::Data, "Arc/User Data"),
+    ("brave", Base::Data, "BraveSoftware/Brave-Browser"),
+    ("brave-beta", Base::Data, "BraveSoftware/Brave-Browser-Beta"),
+    ("brave-dev", Base::Data, "BraveSoftware/Brave-Browser-Dev"),
+    ("brave-nightly", Base::Data, "BraveSoftware/Brave-Browser-Nightly"),
+    ("chrome", Base::Data, "Google/Chrome"),
+    ("chrome-beta", Base::Data, "Google/Chrome Beta"),
+    ("chrome-canary", Base::Data, "Google/Chrome Canary"),
+    ("chrome-dev", Base::Data, "Google/Chrome Dev"),
+    ("chrome-test", Base::Data, "Google/Chrome for Testing"),
+    ("chromium", Base::Data, "Chromium"),
+    ("firefox", Base::Data, "Firefox"),
+    ("firefox-developer", Base::Data, "Firefox"),
+    ("firefox-nightly", Base::Data, "Firefox"),
+    ("floorp", Base::Data, "Floorp"),
+    ("librewolf", Base::Data, "librewolf"),
+    ("msedge", Base::Data, "Microsoft Edge"),
+    ("msedge-beta", Base::Data, "Microsoft Edge Beta"),
+    ("msedge-canary", Base::Data, "Microsoft Edge Canary"),
+    ("msedge-dev", Base::Data, "Microsoft Edge Dev"),
+    ("opera", Base::Data, "com.operasoftware.Opera"),
+    ("safari", Base::Home, "Library/Safari"),
+    ("vivaldi", Base::Data, "Vivaldi"),
+    ("waterfox", Base::Data, "Waterfox"),
+    ("zen", Base::Data, "zen"),
+];
+
+#[cfg(target_os = "windows")]
+const USER_DATA_DIR_LIST: &[(&str, Base, &str)] = &[
+    ("brave", Base::LocalData, r"BraveSoftware\Brave-Browser\User Data"),
+    ("brave-beta", Base::LocalData, r"BraveSoftware\Brave-Browser-Beta\User Data"),
+    ("brave-nightly", Base::LocalData, r"BraveSoftware\Brave-Browser-Nightly\User Data"),
+    ("chrome", Base::LocalData, r"Google\Chrome\User Data"),
+    ("chrome-beta", Base::LocalData, r"Google\Chrome Beta\User Data"),
+    ("chrome-canary", Base::LocalData, r"Google\Chrome SxS\User Data"),
+    ("chrome-dev", Base::LocalData, r"Google\Chrome Dev\User Data"),
+    ("chromium", Base::LocalData, r"Chromium\User Data"),
+    ("firefox", Base::Data, r"Mozilla\Firefox"),
+    ("firefox-developer", Base::Data, r"Mozilla\Firefox"),
+    ("firefox-nightly", Base::Data, r"Mozilla\Firefox"),
+    ("floorp", Base::Data, "Floorp"),
+    ("librewolf", Base::Data, "librewolf"),
+    ("msedge", Base::LocalData, r"Microsoft\Edge\User Data"),
+    ("msedge-beta", Base::LocalData, r"Microsoft\Edge Beta\User Data"),
+    ("msedge-canary", Base::LocalData, r"Microsoft\Edge SxS\User Data"),
+    ("msedge-dev", Base::LocalData, r"Microsoft\Edge Dev\User Data"),
+    ("opera", Base::Data, r"Opera Software\Opera Stable"),
+    ("vivaldi", Base::LocalData, r"Vivaldi\User Data"),
+    ("waterfox", Base::Data, "Waterfox"),
+];
+
+#[cfg(target_os = "linux")]
+const USER_DATA_DIR_LIST: &[(&str, Base, &str)] = &[
+    ("brave", Base::Config, "BraveSoftware/Brave-Browser"),
+    ("brave-beta", Base::Config, "BraveSoftware/Brave-Browser-Beta"),
+    ("brave-nightly", Base::Config, "BraveSoftware/Brave-Browser-Nightly"),
+    ("chrome", Base::Config, "google-chrome"),
+    ("chrome-beta", Base::Config, "google-chrome-beta"),
+    ("chrome-dev", Base::Config, "google-chrome-unstable"),
+    ("chromium", Base::Config, "chromium"),
+    ("firefox", Base::Home, ".mozilla/firefox"),
+    ("floorp", Base::Home, ".floorp"),
+    ("librewolf", Base::Home, ".librewolf"),
+    ("msedge", Base::Config, "microsoft-edge"),
+    ("msedge-beta", Base::Config, "microsoft-edge-beta"),
+    ("msedge-dev", Base::Config, "microsoft-edge-dev"),
+    ("opera", Base::Config, "opera"),
+    ("ungoogled-chromium", Base::Config, "chromium"),
+    ("vivaldi", Base::Config, "vivaldi"),
+    ("waterfox", Base::Home, ".waterfox"),
+    ("zen", Base::Home, ".zen"),
+];
+
+impl Browser {
+    /// Returns the default user data directory, where profiles, bookmarks and cookies live, e.g.
+    /// `~/Library/Application Support/Google/Chrome` or `%LOCALAPPDATA%\Google\Chrome\User Data`.
+    ///
+    /// For Firefox-based browsers this is the directory holding `profiles.ini`. On Linux, Snap and Flatpak
+    /// installs are redirected into their sandbox. The directory may not exist if the browser was never
+    /// started. `None` for browsers without a known location.
+    pub fn user_data_dir(&self) -> Option<PathBuf> {
+        let (_, base, relative) = USER_DATA_DIR_LIST.iter().find(|(name, _, _)| *name == self.browser_type)?;
+        Some(self.base_dir(*base)?.join(relative))
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn base_dir(&self, base: Base) -> Option<PathBuf> {
+        match base {
+            Base::Home => dirs::home_dir(),
+            Base::Config => dirs::config_dir(),
+            Base::Data => dirs::data_dir(),
+            Base::LocalData => dirs::data_local_dir(),
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    fn base_dir(&self, base: Base) -> Option<PathBuf> {
+        let home = dirs::home_dir()?;
+
+        // e.g. "/snap/bin/chromium" keeps its data in "~/snap/chromium/common"
+        if let Ok(name) = self.path.strip_prefix("/snap/bin") {
+            return Some(home.join("snap").join(name).join("common"));
+        }
+        // e.g. "flatpak run org.mozilla.firefox"
+        if let Some(app_id) = self.flatpak_app_id() {
+            let app_dir = home.join(".var/app").join(app_id);
+            return Some(match base {
+                Base::Config => app_dir.join("config"),
+                _ => app_dir,
+            });
+        }
+
+        match base {
+            Base::Home => Some(home),
+            Base::Config => dirs::config_dir(),
+            Base::Data => dirs::data_dir(),
+            Base::LocalData => dirs::data_local_dir(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Browser;
+
+    #[test]
+    fn test_user_data_dir() {
+        assert!(
+            Browser::test_fixture("chrome", "chrome")
+                .user_data_dir()
+                .is_some_and(|dir| dir.to_string_lossy().contains("hrome"))
+        );
+        assert_eq!(Browser::test_fixture("ladybird", "ladybird").user_data_dir(), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_user_data_dir_sandboxed() {
+        let home = dirs::home_dir().unwrap();
+
+        assert_eq!(
+            Browser::test_fixture("chromium", "/snap/bin/chromium").user_data_dir(),
+            Some(home.join("snap/chromium/common/chromium"))
+        );
+        let exec = "/usr/bin/flatpak run --branch=stable org.mozilla.firefox @@u @@".to_string();
+        let flatpak = Browser { exec: Some(exec), ..Browser::test_fixture("firefox", "/usr/bin/flatpak") };
+        assert_eq!(flatpak.user_data_dir(), Some(home.join(".var/app/org.mozilla.firefox/.mozilla/firefox")));
+    }
+}
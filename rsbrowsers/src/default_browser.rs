@@ -0,0 +1,403 @@
+use crate::{Browser, BrowserFinder, InstallSource};
+use std::env;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+#[cfg(target_os = "macos")]
+use {crate::OSX_BROWSER_BUNDLE_LIST, plist::Value};
+#[cfg(target_os = "linux")]
+use {
+    crate::backend::desktop_entry_browser,
+    crate::desktop_files::{self, application_dirs, desktop_files},
+    crate::{LINUX_DESKTOP_ENTRY_NAME_LIST, exec},
+    freedesktop_desktop_entry::DesktopEntry,
+    std::fs,
+};
+#[cfg(target_os = "windows")]
+use {
+    winreg::RegKey,
+    winreg::enums::{HKEY_CLASSES_ROOT, HKEY_CURRENT_USER},
+};
+
+#[cfg(target_os = "windows")]
+const WINDOWS_PROG_ID_LIST: &[(&str, &str)] = &[
+    // ProgId prefix, browser name (Firefox-based browsers append an install hash)
+    ("BraveBHTML", "brave-beta"),
+    ("BraveHTML", "brave"),
+    ("BraveSSHTM", "brave-nightly"),
+    ("ChromeBHTML", "chrome-beta"),
+    ("ChromeDHTML", "chrome-dev"),
+    ("ChromeHTML", "chrome"),
+    ("ChromeSSHTM", "chrome-canary"),
+    ("ChromiumHTM", "chromium"),
+    ("FirefoxURL", "firefox"),
+    ("FloorpHTML", "floorp"),
+    ("IE.HTTP", "msie"),
+    ("LibreWolfHTM", "librewolf"),
+    ("MSEdgeBHTML", "msedge-beta"),
+    ("MSEdgeDHTML", "msedge-dev"),
+    ("MSEdgeHTM", "msedge"),
+    ("MSEdgeSHTML", "msedge-canary"),
+    ("Operabeta", "opera-beta"),
+    ("Operadeveloper", "opera-developer"),
+    ("OperaStable", "opera"),
+    ("VivaldiHTM", "vivaldi"),
+    ("WaterfoxURL", "waterfox"),
+];
+
+/// Returns the system default web browser, i.e. the handler of `http` URLs.
+///
+/// The `BROWSER` environment variable takes precedence if it names an installed browser or an executable.
+/// Under WSL without a Linux default, this is `wslview`, which opens the Windows default browser.
+pub fn default_browser() -> Option<Browser> {
+    if let Some(browser) = from_env(&BrowserFinder::new()) {
+        return Some(browser);
+    }
+    let browser = match handler_for_scheme("http") {
+        Some(SchemeHandler::Browser(browser)) => Some(browser),
+        _ => None,
+    };
+
+    #[cfg(target_os = "linux")]
+    let browser = browser.or_else(crate::wsl::default_browser);
+
+    browser
+}
+
+/// Resolves the first usable command in the `BROWSER` environment variable, a list separated like `PATH`
+/// such as `firefox:google-chrome %s`.
+///
+/// A command naming a browser type or the executable of a browser found by `finder` returns that browser.
+/// Any other executable is returned as a custom browser named after it.
+pub(crate) fn from_env(finder: &BrowserFinder) -> Option<Browser> {
+    let commands = env::var("BROWSER").ok()?;
+    let separator = if cfg!(target_os = "windows") { ';' } else { ':' };
+    let mut installed: Option<Vec<Browser>> = None;
+
+    for command in commands.split(separator) {
+        // the command may end with "%s" in place of the URL, which is passed as an argument anyway
+        let Some(program) = command.split_whitespace().next() else { continue };
+        let Some(name) = Path::new(program).file_stem().and_then(|stem| stem.to_str()) else { continue };
+
+        // only scan if BROWSER is set
+        let installed = installed.get_or_insert_with(|| finder.try_all().map(Iterator::collect).unwrap_or_default());
+        let browser = installed.iter().find(|browser| {
+            browser.browser_type.eq_ignore_ascii_case(name)
+                || browser.path.file_stem().is_some_and(|stem| stem.eq_ignore_ascii_case(name))
+        });
+        if let Some(browser) = browser {
+            return Some(browser.clone());
+        }
+
+        let path = match program.contains(['/', '\\']) {
+            true => Some(Path::new(program).to_path_buf()).filter(|path| path.is_file()),
+            false => crate::find_on_path(program),
+        };
+        if let Some(path) = path {
+            return Some(Browser {
+                browser_type: name.to_string(),
+                path,
+                exec: None,
+                args: vec![],
+                display_name: name.to_string(),
+                version: String::new(),
+                raw_version: None,
+                install_source: InstallSource::Custom,
+                source_id: None,
+                source_path: None,
+                package: None,
+            });
+        }
+    }
+
+    None
+}
+
+/// The application that opens URLs of a scheme, see `handler_for_scheme`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum SchemeHandler {
+    /// An installed browser this crate knows about.
+    Browser(Browser),
+    /// Any other application, e.g. a mail client for `mailto`.
+    Application {
+        /// The bundle ID on macOS, the ProgId on Windows or the desktop entry ID on Linux.
+        id: String,
+        /// The executable or app bundle, if it could be found.
+        path: Option<PathBuf>,
+    },
+}
+
+/// Returns the application registered for URLs with `scheme`, e.g. `mailto` or `zoommtg`.
+///
+/// Reads the LaunchServices handlers on macOS, the `UrlAssociations` user choice or the registered URL
+/// protocol on Windows and asks `xdg-mime` on Linux. Returns `None` if nothing handles the scheme or the
+/// scheme is not valid.
+pub fn handler_for_scheme(scheme: &str) -> Option<SchemeHandler> {
+    // e.g. "web+app" is fine, but the scheme ends up in registry paths and command arguments
+    let valid = scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    if !valid {
+        return None;
+    }
+    let scheme = scheme.to_ascii_lowercase();
+    let id = handler_id(&scheme)?;
+
+    let browser = browser_type(&id).and_then(|browser_type| BrowserFinder::new().with_type(browser_type).all().next());
+    match browser {
+        Some(browser) => Some(SchemeHandler::Browser(browser)),
+        None => Some(SchemeHandler::Application { path: handler_path(&id), id }),
+    }
+}
+
+/// What `set_default_browser` does on this platform.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DefaultBrowserChange {
+    /// The default was changed right away, as with `xdg-settings` on Linux.
+    Changed,
+    /// macOS asked the user to confirm the change, which only takes effect once they do.
+    Prompted,
+    /// Windows doesn't let programs change the default, so its Default Apps settings were opened for the
+    /// user to pick the browser.
+    SettingsOpened,
+}
+
+impl DefaultBrowserChange {
+    /// What `set_default_browser` will do on this platform, e.g. so provisioning tools that run unattended
+    /// can skip it unless it is `Changed`.
+    pub fn expected() -> Self {
+        if cfg!(target_os = "macos") {
+            DefaultBrowserChange::Prompted
+        } else if cfg!(target_os = "windows") {
+            DefaultBrowserChange::SettingsOpened
+        } else {
+            DefaultBrowserChange::Changed
+        }
+    }
+}
+
+/// Makes `browser` the handler of `http` and `https` URLs as far as the platform permits, see
+/// `DefaultBrowserChange`.
+///
+/// Fails with `ErrorKind::Unsupported` if the browser has no desktop entry on Linux or app bundle on macOS,
+/// e.g. a browser from an automation cache.
+pub fn set_default_browser(browser: &Browser) -> io::Result<DefaultBrowserChange> {
+    set_default(browser)
+}
+
+/// Runs a command, turning a failure exit status into an error carrying its output.
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn run(command: &mut Command) -> io::Result<String> {
+    let output = command.output()?;
+    match output.status.success() {
+        true => Ok(String::from_utf8_lossy(&output.stdout).trim().to_string()),
+        false => Err(io::Error::other(String::from_utf8_lossy(&output.stderr).trim().to_string())),
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn set_default(browser: &Browser) -> io::Result<DefaultBrowserChange> {
+    let bundle_id = Path::new(&browser.path)
+        .ancestors()
+        .find(|path| path.extension().is_some_and(|extension| extension == "app"))
+        .and_then(|application| Value::from_file(application.join("Contents/Info.plist")).ok())
+        .and_then(|properties| Some(properties.as_dictionary()?.get("CFBundleIdentifier")?.as_string()?.to_string()))
+        // the ID is embedded in the script
+        .filter(|id| id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_')))
+        .ok_or_else(|| {
+            io::Error::new(io::ErrorKind::Unsupported, format!("{} is not an app bundle", browser.display_name))
+        })?;
+
+    // LaunchServices has no command-line tool, but its functions can be called from JavaScript for Automation
+    let script = format!(
+        "ObjC.import('CoreServices'); \
+         ['http', 'https'].map(scheme => $.LSSetDefaultHandlerForURLScheme($(scheme), $('{bundle_id}'))).join(' ')"
+    );
+    let status = run(Command::new("osascript").args(["-l", "JavaScript", "-e", &script]))?;
+    match status.split(' ').all(|status| status == "0") {
+        true => Ok(DefaultBrowserChange::Prompted),
+        false => Err(io::Error::other(format!("LaunchServices returned {status}"))),
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn set_default(_browser: &Browser) -> io::Result<DefaultBrowserChange> {
+    // the user choice is protected by a hash, so only the user can change it
+    let status = Command::new("cmd").args(["/C", "start", "", "ms-settings:defaultapps"]).status()?;
+    match status.success() {
+        true => Ok(DefaultBrowserChange::SettingsOpened),
+        false => Err(io::Error::other(format!("cannot open the Default Apps settings: {status}"))),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn set_default(browser: &Browser) -> io::Result<DefaultBrowserChange> {
+    let desktop_id = desktop_files(&application_dirs())
+        .into_iter()
+        .find(|file| {
+            !file.shadowed
+                && desktop_entry_browser(file, true)
+                    .is_ok_and(|entry| entry.browser_type == browser.browser_type && entry.path == browser.path)
+        })
+        .map(|file| file.id)
+        .ok_or_else(|| {
+            io::Error::new(io::ErrorKind::Unsupported, format!("{} has no desktop entry", browser.display_name))
+        })?;
+
+    run(Command::new("xdg-settings").args(["set", "default-web-browser", &desktop_id]))?;
+    Ok(DefaultBrowserChange::Changed)
+}
+
+#[cfg(target_os = "macos")]
+fn handler_id(scheme: &str) -> Option<String> {
+    let path = PathBuf::from(env::var_os("HOME")?)
+        .join("Library/Preferences/com.apple.LaunchServices/com.apple.launchservices.secure.plist");
+
+    let bundle_id = Value::from_file(path).ok().and_then(|properties| {
+        properties.as_dictionary()?.get("LSHandlers")?.as_array()?.iter().find_map(|handler| {
+            let handler = handler.as_dictionary()?;
+            match handler.get("LSHandlerURLScheme")?.as_string()?.eq_ignore_ascii_case(scheme) {
+                true => handler.get("LSHandlerRoleAll")?.as_string().map(String::from),
+                false => None,
+            }
+        })
+    });
+
+    // Safari is the default until the user picks another browser
+    match scheme {
+        "http" | "https" => Some(bundle_id.unwrap_or("com.apple.Safari".to_string())),
+        _ => bundle_id,
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn browser_type(bundle_id: &str) -> Option<String> {
+    OSX_BROWSER_BUNDLE_LIST
+        .iter()
+        .find(|(_, id, _)| id.eq_ignore_ascii_case(bundle_id))
+        .map(|(browser_type, _, _)| browser_type.to_string())
+}
+
+#[cfg(target_os = "macos")]
+fn handler_path(bundle_id: &str) -> Option<PathBuf> {
+    crate::application_bundles().into_iter().find(|(_, id)| id.eq_ignore_ascii_case(bundle_id)).map(|(path, _)| path)
+}
+
+#[cfg(target_os = "windows")]
+fn handler_id(scheme: &str) -> Option<String> {
+    let user_choice = RegKey::predef(HKEY_CURRENT_USER)
+        .open_subkey(format!(r"Software\Microsoft\Windows\Shell\Associations\UrlAssociations\{scheme}\UserChoice"))
+        .ok()
+        .and_then(|user_choice| user_choice.get_value("ProgId").ok());
+    // apps such as Zoom register their protocol directly, the key name then serves as the ProgId
+    user_choice.or_else(|| {
+        let protocol = RegKey::predef(HKEY_CLASSES_ROOT).open_subkey(scheme).ok()?;
+        protocol.get_value::<String, _>("URL Protocol").ok().map(|_| scheme.to_string())
+    })
+}
+
+#[cfg(target_os = "windows")]
+fn browser_type(prog_id: &str) -> Option<String> {
+    WINDOWS_PROG_ID_LIST
+        .iter()
+        .find(|(prefix, _)| prog_id.starts_with(prefix))
+        .map(|(_, browser_type)| browser_type.to_string())
+}
+
+#[cfg(target_os = "windows")]
+fn handler_path(prog_id: &str) -> Option<PathBuf> {
+    let command: String = RegKey::predef(HKEY_CLASSES_ROOT)
+        .open_subkey(format!(r"{prog_id}\shell\open\command"))
+        .ok()?
+        .get_value("")
+        .ok()?;
+    command_executable(&command)
+}
+
+/// Extracts the executable from a command such as `"C:\Program Files\Zoom\bin\Zoom.exe" "--url=%1"`.
+#[cfg(target_os = "windows")]
+fn command_executable(command: &str) -> Option<PathBuf> {
+    let command = command.trim_start();
+    let executable = match command.strip_prefix('"') {
+        Some(rest) => rest.split_once('"').map_or(rest, |(executable, _)| executable),
+        None => command.split_whitespace().next()?,
+    };
+    Some(PathBuf::from(executable)).filter(|path| !path.as_os_str().is_empty())
+}
+
+#[cfg(target_os = "linux")]
+fn handler_id(scheme: &str) -> Option<String> {
+    let query = |args: &[&str]| {
+        Command::new(args[0])
+            .args(&args[1..])
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .and_then(|output| String::from_utf8(output.stdout).ok())
+            .map(|stdout| stdout.trim().to_string())
+            .filter(|id| !id.is_empty())
+    };
+    let mime_type = format!("x-scheme-handler/{scheme}");
+
+    match scheme {
+        "http" | "https" => query(&["xdg-settings", "get", "default-web-browser"]),
+        _ => None,
+    }
+    .or_else(|| query(&["xdg-mime", "query", "default", &mime_type]))
+}
+
+#[cfg(target_os = "linux")]
+fn browser_type(desktop_id: &str) -> Option<String> {
+    let base_name = desktop_id.strip_suffix(".desktop")?;
+    LINUX_DESKTOP_ENTRY_NAME_LIST.get(base_name).map(|browser_type| browser_type.to_string())
+}
+
+#[cfg(target_os = "linux")]
+fn handler_path(desktop_id: &str) -> Option<PathBuf> {
+    let path = desktop_files::find(desktop_id)?;
+    let bytes = fs::read_to_string(&path).ok()?;
+    let entry = DesktopEntry::decode(&path, &bytes).ok()?;
+    let argv = exec::split(entry.exec()?).ok()?;
+    let program = exec::program(&argv)?;
+    match program.contains('/') {
+        true => Some(PathBuf::from(program)),
+        false => crate::find_on_path(program),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::handler_for_scheme;
+    #[cfg(not(target_os = "windows"))]
+    use {crate::Browser, crate::InstallSource, crate::set_default_browser, std::io::ErrorKind};
+    #[cfg(target_os = "windows")]
+    use {crate::default_browser::command_executable, std::path::PathBuf};
+
+    #[test]
+    fn test_invalid_scheme() {
+        assert_eq!(handler_for_scheme("http://"), None);
+        assert_eq!(handler_for_scheme("1password"), None);
+        assert_eq!(handler_for_scheme(r"http\..\x"), None);
+    }
+
+    #[test]
+    #[cfg(not(target_os = "windows"))]
+    fn test_set_default_browser() {
+        let browser = Browser {
+            version: "120.0.6099.109".to_string(),
+            install_source: InstallSource::ChromeForTesting,
+            ..Browser::test_fixture("chrome-test", "/tmp/chrome-linux64/chrome")
+        };
+        assert_eq!(set_default_browser(&browser).unwrap_err().kind(), ErrorKind::Unsupported);
+    }
+
+    #[test]
+    #[cfg(target_os = "windows")]
+    fn test_command_executable() {
+        assert_eq!(
+            command_executable(r#""C:\Program Files\Zoom\bin\Zoom.exe" "--url=%1""#),
+            Some(PathBuf::from(r"C:\Program Files\Zoom\bin\Zoom.exe"))
+        );
+        assert_eq!(command_executable(r"C:\Windows\notepad.exe %1"), Some(PathBuf::from(r"C:\Windows\notepad.exe")));
+        assert_eq!(command_executable(""), None);
+    }
+}
@@ -0,0 +1,123 @@
+//! Lookup of desktop entry files in the XDG data directories, see
+//! <https://specifications.freedesktop.org/desktop-entry-spec/latest/file-naming.html>.
+
+use std::collections::HashSet;
+use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A desktop entry file found below an `applications` directory.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub(crate) struct DesktopFile {
+    /// The desktop file ID, e.g. `kde4-konqueror.desktop` for `/usr/share/applications/kde4/konqueror.desktop`.
+    pub(crate) id: String,
+    pub(crate) path: PathBuf,
+    /// Whether a file with the same ID in a directory that comes first overrides this one.
+    pub(crate) shadowed: bool,
+}
+
+/// Returns the system data directories in `XDG_DATA_DIRS`, or `/usr/local/share` and `/usr/share` if it is
+/// unset.
+pub(crate) fn data_dirs() -> Vec<PathBuf> {
+    parse_data_dirs(env::var_os("XDG_DATA_DIRS"))
+}
+
+fn parse_data_dirs(value: Option<OsString>) -> Vec<PathBuf> {
+    match value.filter(|value| !value.is_empty()) {
+        // relative paths are invalid and ignored
+        Some(dirs) => env::split_paths(&dirs).filter(|dir| dir.is_absolute()).collect(),
+        None => vec![PathBuf::from("/usr/local/share"), PathBuf::from("/usr/share")],
+    }
+}
+
+/// Returns the `applications` directories in order of precedence, the user's in `XDG_DATA_HOME`, by default
+/// `~/.local/share`, before the ones in `data_dirs`.
+pub(crate) fn application_dirs() -> Vec<PathBuf> {
+    dirs::data_dir().into_iter().chain(data_dirs()).map(|dir| dir.join("applications")).collect()
+}
+
+/// Lists the desktop entry files below `dirs` in order of precedence, including their subdirectories.
+pub(crate) fn desktop_files(dirs: &[PathBuf]) -> Vec<DesktopFile> {
+    let mut ids = HashSet::new();
+    let mut files = vec![];
+    for dir in dirs {
+        let mut found = vec![];
+        collect(dir, "", &mut found);
+        found.sort();
+        files.extend(found.into_iter().map(|(id, path)| {
+            let shadowed = !ids.insert(id.clone());
+            DesktopFile { id, path, shadowed }
+        }));
+    }
+    files
+}
+
+/// Returns the desktop entry file that is in effect for a desktop file ID.
+pub(crate) fn find(desktop_id: &str) -> Option<PathBuf> {
+    desktop_files(&application_dirs()).into_iter().find(|file| file.id == desktop_id).map(|file| file.path)
+}
+
+/// Collects the `.desktop` files below `dir` with their IDs, which join subdirectories with `-`.
+fn collect(dir: &Path, prefix: &str, found: &mut Vec<(String, PathBuf)>) {
+    let Ok(entries) = fs::read_dir(dir) else { return };
+
+    for entry in entries.filter_map(Result::ok) {
+        // IDs must be valid UTF-8
+        let Ok(name) = entry.file_name().into_string() else { continue };
+        // symlinked directories are not followed, so loops can't recurse forever
+        if entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+            collect(&entry.path(), &format!("{prefix}{name}-"), found);
+        } else if name.ends_with(".desktop") {
+            found.push((format!("{prefix}{name}"), entry.path()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::desktop_files::{DesktopFile, desktop_files, parse_data_dirs};
+    use std::env;
+    use std::fs;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_parse_data_dirs() {
+        let defaults = [PathBuf::from("/usr/local/share"), PathBuf::from("/usr/share")];
+        assert_eq!(parse_data_dirs(None), defaults);
+        assert_eq!(parse_data_dirs(Some("".into())), defaults);
+        assert_eq!(
+            parse_data_dirs(Some("/var/lib/flatpak/exports/share:relative/share:/usr/share".into())),
+            [PathBuf::from("/var/lib/flatpak/exports/share"), PathBuf::from("/usr/share")]
+        );
+    }
+
+    #[test]
+    fn test_desktop_files() {
+        let root = env::temp_dir().join(format!("rsbrowsers-applications-{}", std::process::id()));
+        let user = root.join("home/applications");
+        let system = root.join("usr/applications");
+        for path in [
+            user.join("firefox.desktop"),
+            system.join("firefox.desktop"),
+            system.join("kde4/konqueror.desktop"),
+            system.join("mimeinfo.cache"),
+        ] {
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, "").unwrap();
+        }
+
+        let files = desktop_files(&[user.clone(), root.join("missing"), system.clone()]);
+        fs::remove_dir_all(&root).unwrap();
+
+        let file = |id: &str, path: PathBuf, shadowed: bool| DesktopFile { id: id.to_string(), path, shadowed };
+        assert_eq!(
+            files,
+            [
+                file("firefox.desktop", user.join("firefox.desktop"), false),
+                file("firefox.desktop", system.join("firefox.desktop"), true),
+                file("kde4-konqueror.desktop", system.join("kde4/konqueror.desktop"), false),
+            ]
+        );
+    }
+}
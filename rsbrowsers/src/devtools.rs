@@ -0,0 +1,102 @@
+use std::io::{self, Read, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Asks the OS for a port that is currently free on localhost.
+pub(crate) fn free_port() -> io::Result<u16> {
+    Ok(TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?.local_addr()?.port())
+}
+
+/// Sends a plain HTTP GET to a localhost endpoint and returns the body of a `200` response.
+pub(crate) fn get(port: u16, path: &str) -> io::Result<String> {
+    let mut stream = TcpStream::connect_timeout(&(Ipv4Addr::LOCALHOST, port).into(), Duration::from_secs(1))?;
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    write!(stream, "GET {path} HTTP/1.0\r\nHost: 127.0.0.1:{port}\r\n\r\n")?;
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response)?;
+
+    let (head, body) = response.split_once("\r\n\r\n").unwrap_or((response.as_str(), ""));
+    match head.split_whitespace().nth(1) {
+        Some("200") => Ok(body.to_string()),
+        status => Err(io::Error::other(format!("unexpected HTTP status {status:?} from {path}"))),
+    }
+}
+
+/// Polls `/json/version` until the DevTools endpoint answers, returning its `ws://` URL.
+pub(crate) fn wait_for_websocket_url(port: u16, timeout: Duration) -> io::Result<String> {
+    let started = Instant::now();
+    loop {
+        let url = get(port, "/json/version").ok().and_then(|body| {
+            let version: serde_json::Value = serde_json::from_str(&body).ok()?;
+            version.get("webSocketDebuggerUrl")?.as_str().map(String::from)
+        });
+        if let Some(url) = url {
+            return Ok(url);
+        }
+        if started.elapsed() >= timeout {
+            return Err(io::Error::new(io::ErrorKind::TimedOut, format!("DevTools did not start on port {port}")));
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+}
+
+/// Polls until a localhost port accepts connections, for protocols without an HTTP endpoint to ask.
+pub(crate) fn wait_for_port(port: u16, timeout: Duration) -> io::Result<()> {
+    let started = Instant::now();
+    loop {
+        if TcpStream::connect_timeout(&(Ipv4Addr::LOCALHOST, port).into(), Duration::from_secs(1)).is_ok() {
+            return Ok(());
+        }
+        if started.elapsed() >= timeout {
+            return Err(io::Error::new(io::ErrorKind::TimedOut, format!("nothing listened on port {port}")));
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use crate::devtools::{free_port, wait_for_port, wait_for_websocket_url};
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+    use std::time::Duration;
+
+    /// Answers each of `bodies` to one request on localhost, in order, and returns the port.
+    pub(crate) fn serve(bodies: Vec<&'static str>) -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        thread::spawn(move || {
+            for body in bodies {
+                let (mut stream, _) = listener.accept().unwrap();
+                // read the whole request, closing with unread data would reset the connection
+                let mut request = vec![];
+                let mut buffer = [0; 1024];
+                while !request.ends_with(b"\r\n\r\n") {
+                    let read = stream.read(&mut buffer).unwrap();
+                    request.extend_from_slice(&buffer[..read]);
+                }
+                write!(stream, "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{body}", body.len()).unwrap();
+            }
+        });
+        port
+    }
+
+    #[test]
+    fn test_wait_for_websocket_url() {
+        let port = serve(vec![r#"{"webSocketDebuggerUrl": "ws://127.0.0.1/devtools/browser/1"}"#]);
+        let url = wait_for_websocket_url(port, Duration::from_secs(5)).unwrap();
+        assert_eq!(url, "ws://127.0.0.1/devtools/browser/1");
+    }
+
+    #[test]
+    fn test_wait_for_port() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        assert!(wait_for_port(listener.local_addr().unwrap().port(), Duration::from_secs(5)).is_ok());
+
+        let port = free_port().unwrap();
+        assert!(wait_for_port(port, Duration::ZERO).is_err());
+    }
+}
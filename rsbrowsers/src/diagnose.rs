@@ -0,0 +1,229 @@
+use crate::{Browser, BrowserFinder, Error};
+use serde::Serializer;
+use std::fmt;
+use std::mem;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// What a scan looked at and why browsers were not listed, see `BrowserFinder::diagnose`.
+#[derive(serde::Serialize, Clone, PartialEq, Eq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostics {
+    /// The backends in the order they ran.
+    pub backends: Vec<BackendReport>,
+    /// Browsers found by a backend but left out by the finder's filters.
+    pub filtered_out: Vec<FilteredOut>,
+    /// The browsers the finder lists, as `BrowserFinder::all` would.
+    pub browsers: Vec<Browser>,
+}
+
+/// A run of one backend.
+#[derive(serde::Serialize, Clone, PartialEq, Eq, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BackendReport {
+    /// See `DetectionBackend::name`.
+    pub name: String,
+    /// How many browsers the backend added.
+    pub found: usize,
+    #[serde(rename = "elapsedMs", serialize_with = "milliseconds")]
+    pub elapsed: Duration,
+    /// The error the backend failed with, which makes `all` fail while `diagnose` goes on, or why it was
+    /// skipped.
+    pub error: Option<String>,
+    /// The registry keys, desktop files, bundle IDs or paths the backend examined. Backends that only
+    /// read a fixed location, e.g. `tor`, don't report any.
+    pub sources: Vec<SourceReport>,
+}
+
+/// One place a backend looked for a browser, see `ScanContext::report`.
+#[derive(serde::Serialize, Clone, PartialEq, Eq, Debug)]
+pub struct SourceReport {
+    /// e.g. `/usr/share/applications/firefox.desktop` or `HKLM\Software\Clients\StartMenuInternet\Firefox`.
+    pub source: String,
+    #[serde(flatten)]
+    pub outcome: SourceOutcome,
+}
+
+/// What a backend made of a source.
+#[derive(serde::Serialize, Clone, PartialEq, Eq, Debug)]
+#[serde(tag = "outcome", rename_all = "kebab-case")]
+pub enum SourceOutcome {
+    Found {
+        #[serde(rename = "browserType")]
+        browser_type: String,
+    },
+    Skipped {
+        reason: SkipReason,
+    },
+}
+
+/// Why a source did not yield a browser.
+#[derive(serde::Serialize, Clone, PartialEq, Eq, Debug)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum SkipReason {
+    /// Not a browser this crate knows, e.g. the desktop entry of a text editor.
+    UnknownName,
+    /// A known entry that is left out on purpose, e.g. a shortcut to a private window or a desktop entry
+    /// marked `Hidden` or `NoDisplay`.
+    Ignored,
+    /// The entry could not be read or lacks a required field.
+    ParseError { error: String },
+    /// The entry points to an executable that doesn't exist.
+    MissingExecutable {
+        #[serde(serialize_with = "crate::serialize_path")]
+        path: PathBuf,
+    },
+    /// The executable was already found, e.g. by an earlier backend.
+    Duplicate,
+    /// Nothing is installed there, e.g. an install directory or bundle ID that was checked just in case.
+    NotInstalled,
+}
+
+/// A browser left out by the filters, with the reason.
+#[derive(serde::Serialize, Clone, PartialEq, Eq, Debug)]
+pub struct FilteredOut {
+    pub browser: Browser,
+    /// e.g. `type does not match` or `version does not meet the requirement`.
+    pub reason: String,
+}
+
+impl fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SkipReason::UnknownName => write!(f, "unknown name"),
+            SkipReason::Ignored => write!(f, "ignored"),
+            SkipReason::ParseError { error } => write!(f, "parse error: {error}"),
+            SkipReason::MissingExecutable { path } => write!(f, "missing executable {}", path.display()),
+            SkipReason::Duplicate => write!(f, "already found"),
+            SkipReason::NotInstalled => write!(f, "not installed"),
+        }
+    }
+}
+
+impl SourceOutcome {
+    #[cfg(target_os = "linux")]
+    pub(crate) fn of(result: &Result<Browser, SkipReason>) -> Self {
+        match result {
+            Ok(browser) => SourceOutcome::Found { browser_type: browser.browser_type.clone() },
+            Err(reason) => SourceOutcome::Skipped { reason: reason.clone() },
+        }
+    }
+}
+
+fn milliseconds<S: Serializer>(elapsed: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(elapsed.as_secs_f64() * 1000.0)
+}
+
+/// Collects the report while `diagnose` scans. Backends may run on several threads.
+#[derive(Default)]
+pub(crate) struct Recorder {
+    backends: Mutex<Vec<BackendReport>>,
+    /// The sources reported by the backend that is running.
+    sources: Mutex<Vec<SourceReport>>,
+    filtered_out: Mutex<Vec<FilteredOut>>,
+}
+
+impl Recorder {
+    pub(crate) fn source(&self, source: String, outcome: SourceOutcome) {
+        self.sources.lock().unwrap().push(SourceReport { source, outcome });
+    }
+
+    pub(crate) fn backend(&self, name: &str, found: usize, elapsed: Duration, error: Option<String>) {
+        let sources = mem::take(&mut *self.sources.lock().unwrap());
+        let report = BackendReport { name: name.to_string(), found, elapsed, error, sources };
+        self.backends.lock().unwrap().push(report);
+    }
+
+    pub(crate) fn filtered_out(&self, browser: &Browser, reason: &str) {
+        let filtered_out = FilteredOut { browser: browser.clone(), reason: reason.to_string() };
+        self.filtered_out.lock().unwrap().push(filtered_out);
+    }
+}
+
+impl BrowserFinder {
+    /// Scans like `try_all` and reports what each backend examined: which registry keys, desktop files or
+    /// bundles it looked at, what it found there and why the rest was skipped, as well as the browsers the
+    /// filters left out. The report serializes to JSON for bug reports.
+    ///
+    /// The cache is bypassed so every backend runs. A failing backend is recorded with its error instead
+    /// of failing the scan, only invalid filters return an error.
+    pub fn diagnose(&self) -> Result<Diagnostics, Error> {
+        let recorder = Recorder::default();
+        let browsers = self.clone().bypass_cache().find(Some(&recorder))?;
+        Ok(Diagnostics {
+            backends: recorder.backends.into_inner().unwrap(),
+            filtered_out: recorder.filtered_out.into_inner().unwrap(),
+            browsers,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Browser, BrowserFinder, DetectionBackend, Error, MockBackend, ScanContext, SkipReason, SourceOutcome};
+
+    struct FailingBackend;
+
+    impl DetectionBackend for FailingBackend {
+        fn name(&self) -> &str {
+            "failing"
+        }
+
+        fn detect(&self, context: &ScanContext, _sink: &mut Vec<Browser>) -> Result<(), Error> {
+            context.report("/opt/broken.desktop", SourceOutcome::Skipped { reason: SkipReason::UnknownName });
+            Err(Error::Discovery("cannot read /opt".to_string()))
+        }
+    }
+
+    #[test]
+    fn test_diagnose() {
+        let browser = |browser_type: &str, version: &str| Browser {
+            version: version.to_string(),
+            ..Browser::test_fixture(browser_type, format!("/opt/{browser_type}/{browser_type}"))
+        };
+        let finder = BrowserFinder::new()
+            .without_builtin_backends()
+            .with_backend(FailingBackend)
+            .with_backend(MockBackend::new(vec![browser("chrome", "120.0"), browser("firefox", "121.0")]))
+            .with_type("chrome".to_string())
+            .include_stale(true);
+
+        let diagnostics = finder.diagnose().unwrap();
+        assert_eq!(diagnostics.backends.len(), 2);
+        let failing = &diagnostics.backends[0];
+        assert_eq!((failing.name.as_str(), failing.found), ("failing", 0));
+        assert_eq!(failing.error.as_deref(), Some("browser discovery failed: cannot read /opt"));
+        assert_eq!(failing.sources.len(), 1);
+        assert_eq!(failing.sources[0].source, "/opt/broken.desktop");
+        assert_eq!((diagnostics.backends[1].name.as_str(), diagnostics.backends[1].found), ("mock", 2));
+        assert!(diagnostics.backends[1].sources.is_empty());
+        assert_eq!(diagnostics.filtered_out.len(), 1);
+        assert_eq!(diagnostics.filtered_out[0].browser.browser_type, "firefox");
+        assert_eq!(diagnostics.filtered_out[0].reason, "type does not match");
+        assert_eq!(diagnostics.browsers, [browser("chrome", "120.0")]);
+
+        let json = serde_json::to_value(&diagnostics).unwrap();
+        assert_eq!(
+            json["backends"][0]["sources"][0],
+            serde_json::json!({"source": "/opt/broken.desktop", "outcome": "skipped", "reason": {"kind": "unknown-name"}})
+        );
+        assert!(json["backends"][1]["elapsedMs"].is_f64());
+
+        // a failing backend still fails a normal scan
+        assert!(finder.try_all().is_err());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_non_unicode_missing_executable() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let reason = SkipReason::MissingExecutable { path: OsStr::from_bytes(b"/opt/br\xf6wser/chrome").into() };
+        assert_eq!(
+            serde_json::to_value(&reason).unwrap(),
+            serde_json::json!({"kind": "missing-executable", "path": "/opt/br\u{fffd}wser/chrome"})
+        );
+    }
+}
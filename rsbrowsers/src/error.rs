@@ -0,0 +1,113 @@
+use crate::VersionError;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub enum Error {
+    /// The query is invalid or the platform's browser sources could not be read.
+    Discovery(String),
+    /// A type, version, exclude or preference pattern could not be parsed.
+    Pattern(PatternError),
+    /// The requirement given to `BrowserFinder::with_version_req` could not be parsed, e.g. `>=abc`.
+    VersionReq(VersionError),
+}
+
+/// A filter that is not a valid pattern, e.g. the glob `chrome[`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PatternError {
+    /// The pattern as given.
+    pub pattern: String,
+    /// Why it was rejected.
+    pub message: String,
+}
+
+/// Why a browser could not be launched.
+#[derive(Debug)]
+pub enum LaunchError {
+    /// No installed browser matches the query.
+    NotFound,
+    /// The browser was found by a scan but its executable no longer exists, e.g. it was uninstalled since.
+    ExecutableMissing(PathBuf),
+    /// The executable exists but may not be run by the current user.
+    PermissionDenied(PathBuf),
+    /// The process could not be started or did not come up, e.g. the DevTools endpoint never answered.
+    SpawnFailed(io::Error),
+    /// The browser does not support a requested launch option.
+    Unsupported(String),
+    /// The browsers could not be scanned, see `Error::Discovery`.
+    Discovery(Error),
+    /// The URL could not be parsed or its scheme is not allowed, see `BrowserFinder::launch_url`. Also returned
+    /// by `BrowserFinder::launch_file` for a file that doesn't exist.
+    InvalidUrl(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Discovery(message) => write!(f, "browser discovery failed: {message}"),
+            Error::Pattern(error) => write!(f, "{error}"),
+            Error::VersionReq(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Pattern(error) => Some(error),
+            Error::VersionReq(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid pattern {:?}: {}", self.pattern, self.message)
+    }
+}
+
+impl std::error::Error for PatternError {}
+
+impl From<PatternError> for Error {
+    fn from(error: PatternError) -> Self {
+        Error::Pattern(error)
+    }
+}
+
+impl From<VersionError> for Error {
+    fn from(error: VersionError) -> Self {
+        Error::VersionReq(error)
+    }
+}
+
+impl fmt::Display for LaunchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LaunchError::NotFound => write!(f, "browser not found"),
+            LaunchError::ExecutableMissing(path) => write!(f, "browser executable is missing: {}", path.display()),
+            LaunchError::PermissionDenied(path) => write!(f, "permission denied: {}", path.display()),
+            LaunchError::SpawnFailed(error) => write!(f, "cannot launch browser: {error}"),
+            LaunchError::Unsupported(message) => write!(f, "unsupported: {message}"),
+            LaunchError::Discovery(error) => write!(f, "{error}"),
+            LaunchError::InvalidUrl(message) => write!(f, "invalid URL {message}"),
+        }
+    }
+}
+
+impl std::error::Error for LaunchError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LaunchError::SpawnFailed(error) => Some(error),
+            LaunchError::Discovery(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<Error> for LaunchError {
+    fn from(error: Error) -> Self {
+        LaunchError::Discovery(error)
+    }
+}
@@ -0,0 +1,311 @@
+//! Parsing of desktop entry `Exec` lines, see
+//! <https://specifications.freedesktop.org/desktop-entry-spec/latest/exec-variables.html>.
+
+use std::path::{Path, PathBuf};
+
+/// The keys of a desktop entry that the `%i`, `%c` and `%k` field codes stand for.
+pub(crate) struct EntryFields<'a> {
+    pub(crate) icon: Option<&'a str>,
+    /// The translated name.
+    pub(crate) name: &'a str,
+    /// The desktop entry file.
+    pub(crate) location: &'a Path,
+}
+
+/// Splits an `Exec` line into arguments, honoring double quotes and backslash escapes.
+pub(crate) fn split(line: &str) -> Result<Vec<String>, String> {
+    let mut arguments = vec![];
+    let mut argument: Option<String> = None;
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            ' ' | '\t' | '\n' => arguments.extend(argument.take()),
+            '"' => {
+                let argument = argument.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        // only these characters may be escaped inside quotes
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '`' | '$' | '\\')) => argument.push(c),
+                            Some(c) => argument.extend(['\\', c]),
+                            None => return Err(format!("unterminated escape in {line:?}")),
+                        },
+                        Some(c) => argument.push(c),
+                        None => return Err(format!("unterminated quote in {line:?}")),
+                    }
+                }
+            }
+            '\\' => match chars.next() {
+                Some(c) => argument.get_or_insert_with(String::new).push(c),
+                None => return Err(format!("unterminated escape in {line:?}")),
+            },
+            c => argument.get_or_insert_with(String::new).push(c),
+        }
+    }
+    arguments.extend(argument);
+
+    if arguments.is_empty() {
+        return Err("empty Exec line".to_string());
+    }
+    Ok(arguments)
+}
+
+/// Quotes an argument for an `Exec` line if it contains spaces or characters that need escaping.
+pub(crate) fn quote(argument: &str) -> String {
+    if !argument.is_empty() && !argument.contains([' ', '\t', '\n', '"', '\\', '`', '$', '\'']) {
+        return argument.to_string();
+    }
+    let mut quoted = String::from('"');
+    for c in argument.chars() {
+        if matches!(c, '"' | '`' | '$' | '\\') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Replaces the field codes of an `Exec` line that stand for keys of the desktop entry and requotes it, keeping
+/// the ones for URLs and files for `expand`. A trailing `%u`, `%U`, `%f` or `%F` is left out since `expand`
+/// puts URLs at the end anyway, e.g. `env MOZ_APP_REMOTINGNAME=firefox firefox %u` becomes
+/// `env MOZ_APP_REMOTINGNAME=firefox firefox`.
+pub(crate) fn resolve(line: &str, fields: &EntryFields) -> Result<String, String> {
+    let mut argv = vec![];
+    for argument in split(line)? {
+        match argument.as_str() {
+            "%u" | "%U" | "%f" | "%F" => argv.push(argument),
+            // the icon expands to two arguments, or none without an icon
+            "%i" => {
+                if let Some(icon) = fields.icon.filter(|icon| !icon.is_empty()) {
+                    argv.extend(["--icon", icon].map(|argument| quote(argument).replace('%', "%%")));
+                }
+            }
+            _ => {
+                if let Some(argument) = substitute(&argument, Some(fields)) {
+                    argv.push(quote(&argument).replace('%', "%%"));
+                }
+            }
+        }
+    }
+
+    if argv.last().is_some_and(|argument| matches!(argument.as_str(), "%u" | "%U" | "%f" | "%F")) {
+        argv.pop();
+    }
+    match argv.is_empty() {
+        true => Err(format!("no program in {line:?}")),
+        false => Ok(argv.join(" ")),
+    }
+}
+
+/// Returns the program an `Exec` line runs, looking through `env` and its variables, e.g. `firefox` for
+/// `env MOZ_ENABLE_WAYLAND=1 firefox %u`.
+pub(crate) fn program(argv: &[String]) -> Option<&str> {
+    let mut argv = argv.iter().map(String::as_str);
+    let first = argv.next()?;
+    if Path::new(first).file_name().is_none_or(|name| name != "env") {
+        return Some(first);
+    }
+    while let Some(argument) = argv.next() {
+        match argument {
+            // options taking a value
+            "-u" | "--unset" | "-C" | "--chdir" => {
+                argv.next();
+            }
+            argument if argument.starts_with('-') || argument.contains('=') => {}
+            argument => return Some(argument),
+        }
+    }
+    None
+}
+
+/// Returns the executable an `Exec` line runs, looking up a bare command name on `PATH`.
+pub(crate) fn executable(line: &str) -> Option<PathBuf> {
+    let argv = split(line).ok()?;
+    let command = program(&argv)?;
+    if command.contains('/') {
+        return Some(PathBuf::from(command));
+    }
+    Some(crate::find_on_path(command).unwrap_or(PathBuf::from(command)))
+}
+
+/// Builds the argv for an `Exec` line, putting `args` followed by `urls` in place of the first
+/// `%u`, `%U`, `%f` or `%F` field code, or at the end if there is none.
+///
+/// Other field codes are dropped and `%%` becomes `%`.
+pub(crate) fn expand(line: &str, args: &[String], urls: &[String]) -> Result<Vec<String>, String> {
+    let mut argv: Vec<String> = vec![];
+    let mut expanded = false;
+
+    for argument in split(line)? {
+        match argument.as_str() {
+            "%u" | "%U" | "%f" | "%F" => {
+                if !expanded {
+                    // flatpak forwards the files between `@@u` and `@@`, so keep the arguments outside
+                    let at = match argv.last() {
+                        Some(argument) if argument.starts_with("@@") => argv.len() - 1,
+                        _ => argv.len(),
+                    };
+                    argv.splice(at..at, args.iter().cloned());
+                    argv.extend_from_slice(urls);
+                    expanded = true;
+                }
+            }
+            _ => {
+                if let Some(argument) = substitute(&argument, None) {
+                    argv.push(argument);
+                }
+            }
+        }
+    }
+
+    if !expanded {
+        argv.extend_from_slice(args);
+        argv.extend_from_slice(urls);
+    }
+    Ok(argv)
+}
+
+/// Replaces the `%c` and `%k` field codes in an argument with the keys of the desktop entry and removes the
+/// others, returning `None` if nothing but field codes is left.
+fn substitute(argument: &str, fields: Option<&EntryFields>) -> Option<String> {
+    let mut substituted = String::new();
+    let mut had_code = false;
+    let mut chars = argument.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '%' => match (chars.next(), fields) {
+                (Some('%'), _) => substituted.push('%'),
+                (Some('c'), Some(fields)) => substituted.push_str(fields.name),
+                (Some('k'), Some(fields)) => substituted.push_str(&fields.location.to_string_lossy()),
+                _ => had_code = true,
+            },
+            c => substituted.push(c),
+        }
+    }
+
+    if had_code && substituted.is_empty() { None } else { Some(substituted) }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::exec::{EntryFields, executable, expand, program, quote, resolve, split};
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn test_split() {
+        assert_eq!(split("/usr/bin/firefox %u").unwrap(), ["/usr/bin/firefox", "%u"]);
+        assert_eq!(
+            split(r#""/opt/My Browser/browser" --name="a \"b\"" c\ d"#).unwrap(),
+            ["/opt/My Browser/browser", "--name=a \"b\"", "c d"]
+        );
+        assert!(split(r#"/usr/bin/firefox "%u"#).is_err());
+        assert!(split("  ").is_err());
+    }
+
+    #[test]
+    fn test_quote() {
+        assert_eq!(
+            quote("/home/me/tor-browser/Browser/start-tor-browser"),
+            "/home/me/tor-browser/Browser/start-tor-browser"
+        );
+        let path = r#"/home/me/My "Tor" Browser/start-tor-browser"#;
+        assert_eq!(split(&quote(path)).unwrap(), [path]);
+    }
+
+    #[test]
+    fn test_expand() {
+        let args = ["--incognito".to_string()];
+        let urls = ["https://example.com/?a=1&b=2;c".to_string()];
+
+        assert_eq!(
+            expand("/usr/bin/chromium %U", &args, &urls).unwrap(),
+            ["/usr/bin/chromium", "--incognito", "https://example.com/?a=1&b=2;c"]
+        );
+        assert_eq!(
+            expand("flatpak run org.chromium.Chromium @@u %U @@", &args, &urls).unwrap(),
+            ["flatpak", "run", "org.chromium.Chromium", "--incognito", "@@u", "https://example.com/?a=1&b=2;c", "@@"]
+        );
+        assert_eq!(
+            expand("firefox --class=%c --icon %i 100%%", &[], &urls).unwrap(),
+            ["firefox", "--class=", "--icon", "100%", "https://example.com/?a=1&b=2;c"]
+        );
+    }
+
+    #[test]
+    fn test_resolve() {
+        let location = Path::new("/usr/share/applications/browser.desktop");
+        let fields = EntryFields { icon: Some("web-browser"), name: "Web Browser", location };
+        let without_icon = EntryFields { icon: None, ..fields };
+        let resolve = |line: &str| resolve(line, &fields).unwrap();
+
+        // Exec lines of the desktop entries shipped by distributions, Flatpak and Snap
+        assert_eq!(resolve("/usr/lib/firefox/firefox %u"), "/usr/lib/firefox/firefox");
+        assert_eq!(resolve("/usr/bin/google-chrome-stable %U"), "/usr/bin/google-chrome-stable");
+        assert_eq!(resolve("/usr/bin/brave-browser-stable --incognito"), "/usr/bin/brave-browser-stable --incognito");
+        assert_eq!(resolve("epiphany %U"), "epiphany");
+        assert_eq!(resolve("falkon %u"), "falkon");
+        assert_eq!(
+            resolve(
+                "env BAMF_DESKTOP_FILE_HINT=/var/lib/snapd/desktop/applications/firefox_firefox.desktop /snap/bin/firefox %u"
+            ),
+            "env BAMF_DESKTOP_FILE_HINT=/var/lib/snapd/desktop/applications/firefox_firefox.desktop /snap/bin/firefox"
+        );
+        assert_eq!(
+            resolve(
+                "/usr/bin/flatpak run --branch=stable --arch=x86_64 --command=firefox --file-forwarding org.mozilla.firefox @@u %u @@"
+            ),
+            "/usr/bin/flatpak run --branch=stable --arch=x86_64 --command=firefox --file-forwarding org.mozilla.firefox @@u %u @@"
+        );
+        assert_eq!(resolve("konqueror %u --new-tab"), "konqueror %u --new-tab");
+        assert_eq!(resolve("qutebrowser --untrusted-args %u"), "qutebrowser --untrusted-args");
+
+        // keys of the entry, requoted and with literal percent signs escaped
+        assert_eq!(
+            resolve(r#""/opt/My Browser/browser" --class=%c %i --desktop-file %k --zoom=100%% %F"#),
+            r#""/opt/My Browser/browser" "--class=Web Browser" --icon web-browser --desktop-file /usr/share/applications/browser.desktop --zoom=100%%"#
+        );
+        assert_eq!(resolve("browser --title=100%%%c %u"), r#"browser "--title=100%%Web Browser""#);
+        let resolved = |line: &str| super::resolve(line, &without_icon);
+        assert_eq!(resolved("firefox %i %u").as_deref(), Ok("firefox"));
+        // deprecated codes are dropped
+        assert_eq!(resolved("netscape %d %D %n %N %v %m %u").as_deref(), Ok("netscape"));
+        assert!(resolved(r#"/usr/bin/firefox "%u"#).is_err());
+        assert!(resolved("%u").is_err());
+
+        // launching expands what is left
+        let urls = ["https://example.com/".to_string()];
+        let exec = resolve("env MOZ_ENABLE_WAYLAND=1 firefox %u");
+        assert_eq!(
+            expand(&exec, &["--private-window".to_string()], &urls).unwrap(),
+            ["env", "MOZ_ENABLE_WAYLAND=1", "firefox", "--private-window", "https://example.com/"]
+        );
+        assert_eq!(
+            expand(&resolve("browser --zoom=100%% %U"), &[], &urls).unwrap(),
+            ["browser", "--zoom=100%", "https://example.com/"]
+        );
+    }
+
+    #[test]
+    fn test_program() {
+        let program = |line: &str| program(&split(line).unwrap()).map(str::to_string);
+        assert_eq!(program("/usr/bin/firefox %u").as_deref(), Some("/usr/bin/firefox"));
+        assert_eq!(program("env MOZ_ENABLE_WAYLAND=1 firefox %u").as_deref(), Some("firefox"));
+        assert_eq!(
+            program("/usr/bin/env -u DISPLAY -C /tmp GDK_BACKEND=wayland /opt/brave/brave").as_deref(),
+            Some("/opt/brave/brave")
+        );
+        assert_eq!(program("env A=1").as_deref(), None);
+    }
+
+    #[test]
+    fn test_executable() {
+        let firefox = "env MOZ_ENABLE_WAYLAND=1 /opt/firefox/firefox %u";
+        assert_eq!(executable(firefox), Some(PathBuf::from("/opt/firefox/firefox")));
+        assert_eq!(executable("sh -c true"), crate::find_on_path("sh"));
+        assert_eq!(executable("env A=1"), None);
+    }
+}
@@ -0,0 +1,192 @@
+use crate::{Browser, BrowserRecord};
+use std::io;
+use std::ops::Deref;
+
+/// The columns of `Browsers::to_csv`, in the spelling of the `BrowserInfo` serialization.
+const CSV_COLUMN_LIST: &[&str] = &[
+    "schema",
+    "browserType",
+    "displayName",
+    "path",
+    "version",
+    "engine",
+    "channel",
+    "architecture",
+    "installScope",
+    "installSource",
+];
+
+/// A list of browsers that can be exported, e.g. `BrowserFinder::new().all().collect::<Browsers>()`.
+///
+/// Every export writes `BrowserRecord` schema `2`, so consumers can tell the layout apart from the plain
+/// `Browser` serialization and from later schemas.
+#[derive(Clone, Default, PartialEq, Eq, Debug)]
+pub struct Browsers(Vec<Browser>);
+
+/// A difference between two scans, see `Browsers::diff`.
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, Eq, Debug)]
+#[serde(tag = "change", rename_all = "kebab-case")]
+// changes are few and matched on by value, boxing the browsers isn't worth the indirection
+#[allow(clippy::large_enum_variant)]
+pub enum Change {
+    Added(Browser),
+    Removed(Browser),
+    /// The browser with the same type and path reports another version, e.g. after an update.
+    VersionChanged {
+        old: Browser,
+        new: Browser,
+    },
+}
+
+impl Browsers {
+    /// Compares two scans, matching browsers by type and path. Added and changed browsers are listed in
+    /// the order of `new`, followed by the removed ones in the order of `old`.
+    pub fn diff(old: &[Browser], new: &[Browser]) -> Vec<Change> {
+        let same = |a: &Browser, b: &Browser| a.browser_type == b.browser_type && a.path == b.path;
+        let mut changes = vec![];
+
+        for browser in new {
+            match old.iter().find(|old| same(old, browser)) {
+                None => changes.push(Change::Added(browser.clone())),
+                Some(old) if old.version != browser.version => {
+                    changes.push(Change::VersionChanged { old: old.clone(), new: browser.clone() })
+                }
+                Some(_) => {}
+            }
+        }
+        for browser in old {
+            if !new.iter().any(|new| same(browser, new)) {
+                changes.push(Change::Removed(browser.clone()));
+            }
+        }
+        changes
+    }
+
+    fn records(&self) -> Vec<BrowserRecord> {
+        self.0.iter().map(|browser| BrowserRecord::V2(browser.info())).collect()
+    }
+
+    /// Serializes the browsers as a pretty-printed JSON array of records.
+    pub fn to_json(&self) -> io::Result<String> {
+        Ok(serde_json::to_string_pretty(&self.records())?)
+    }
+
+    /// Serializes the browsers as a YAML sequence of records.
+    #[cfg(feature = "yaml")]
+    pub fn to_yaml(&self) -> io::Result<String> {
+        serde_yaml::to_string(&self.records()).map_err(io::Error::other)
+    }
+
+    /// Serializes the browsers as CSV with a header row. Missing versions are left empty.
+    pub fn to_csv(&self) -> io::Result<String> {
+        let mut csv = CSV_COLUMN_LIST.join(",") + "\r\n";
+        for record in self.records() {
+            let record = serde_json::to_value(record)?;
+            let row: Vec<String> = CSV_COLUMN_LIST
+                .iter()
+                .map(|column| match &record[column] {
+                    serde_json::Value::Null => String::new(),
+                    serde_json::Value::String(value) => csv_field(value),
+                    value => csv_field(&value.to_string()),
+                })
+                .collect();
+            csv.push_str(&row.join(","));
+            csv.push_str("\r\n");
+        }
+        Ok(csv)
+    }
+}
+
+/// Quotes a field containing a separator, quote or line break as in RFC 4180.
+fn csv_field(value: &str) -> String {
+    match value.contains([',', '"', '\r', '\n']) {
+        true => format!("\"{}\"", value.replace('"', "\"\"")),
+        false => value.to_string(),
+    }
+}
+
+impl Deref for Browsers {
+    type Target = [Browser];
+
+    fn deref(&self) -> &[Browser] {
+        &self.0
+    }
+}
+
+impl From<Vec<Browser>> for Browsers {
+    fn from(browsers: Vec<Browser>) -> Self {
+        Browsers(browsers)
+    }
+}
+
+impl FromIterator<Browser> for Browsers {
+    fn from_iter<I: IntoIterator<Item = Browser>>(browsers: I) -> Self {
+        Browsers(browsers.into_iter().collect())
+    }
+}
+
+impl IntoIterator for Browsers {
+    type Item = Browser;
+    type IntoIter = std::vec::IntoIter<Browser>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Browser, BrowserInfo, BrowserRecord, Browsers, Change};
+
+    #[test]
+    fn test_export() {
+        let browsers: Browsers = [
+            ("chrome", "Google Chrome", "/opt/google/chrome/chrome", "120.0.6099.109"),
+            ("vivaldi", "Vivaldi, \"Snapshot\"", "/opt/vivaldi/vivaldi", ""),
+        ]
+        .iter()
+        .map(|(browser_type, display_name, path, version)| Browser {
+            display_name: display_name.to_string(),
+            version: version.to_string(),
+            ..Browser::test_fixture(browser_type, path)
+        })
+        .collect();
+
+        let records: Vec<BrowserRecord> = serde_json::from_str(&browsers.to_json().unwrap()).unwrap();
+        let infos: Vec<BrowserInfo> = records.into_iter().map(BrowserInfo::from).collect();
+        assert_eq!(infos, browsers.iter().map(Browser::info).collect::<Vec<_>>());
+
+        let csv = browsers.to_csv().unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines[0],
+            "schema,browserType,displayName,path,version,engine,channel,architecture,installScope,installSource"
+        );
+        assert!(lines[1].starts_with("2,chrome,Google Chrome,/opt/google/chrome/chrome,120.0.6099.109,blink,"));
+        assert!(lines[2].starts_with(r#"2,vivaldi,"Vivaldi, ""Snapshot""",/opt/vivaldi/vivaldi,,blink,"#));
+    }
+
+    #[test]
+    fn test_diff() {
+        let browser = |browser_type: &str, version: &str| Browser {
+            version: version.to_string(),
+            ..Browser::test_fixture(browser_type, format!("/opt/{browser_type}/{browser_type}"))
+        };
+
+        let old = [browser("chrome", "120.0"), browser("firefox", "121.0")];
+        let new = [browser("chrome", "121.0"), browser("brave", "1.61")];
+
+        assert_eq!(
+            Browsers::diff(&old, &new),
+            [
+                Change::VersionChanged { old: browser("chrome", "120.0"), new: browser("chrome", "121.0") },
+                Change::Added(browser("brave", "1.61")),
+                Change::Removed(browser("firefox", "121.0")),
+            ]
+        );
+        assert!(Browsers::diff(&new, &new).is_empty());
+
+        let json = serde_json::to_string(&Change::Added(browser("brave", "1.61"))).unwrap();
+        assert!(json.starts_with(r#"{"change":"added","browserType":"brave""#));
+    }
+}
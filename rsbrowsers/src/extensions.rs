@@ -0,0 +1,89 @@
+//! Loading of unpacked or packaged extensions into a launched browser, see `LaunchOptions::load_extension`.
+
+use crate::LaunchError;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Lets Firefox enable add-ons found in the profile without asking, and load unsigned ones where the
+/// build allows it (Developer Edition, Nightly, ESR and unbranded builds).
+pub(crate) const FIREFOX_PREFS: &str =
+    "user_pref(\"extensions.autoDisableScopes\", 0);\nuser_pref(\"xpinstall.signatures.required\", false);\n";
+
+/// Returns the flags loading the unpacked extensions in `paths` and no others.
+pub(crate) fn chromium_args(paths: &[PathBuf]) -> Vec<String> {
+    let paths = paths.iter().map(|path| path.to_string_lossy()).collect::<Vec<_>>().join(",");
+    vec![format!("--load-extension={paths}"), format!("--disable-extensions-except={paths}")]
+}
+
+/// Returns the files to add to a Firefox profile, relative to it. Unpacked extensions get a proxy file
+/// named after the add-on ID that points at their directory, and `.xpi` packages are copied as they are.
+pub(crate) fn firefox_files(paths: &[PathBuf]) -> Result<Vec<(PathBuf, Vec<u8>)>, LaunchError> {
+    let mut files = vec![];
+    for path in paths {
+        let extensions = Path::new("extensions");
+        if path.is_dir() {
+            let id = gecko_id(path)?;
+            let path = path.canonicalize().map_err(LaunchError::SpawnFailed)?;
+            files.push((extensions.join(id), path.to_string_lossy().as_bytes().to_vec()));
+        } else if path.extension().is_some_and(|extension| extension == "xpi") {
+            let file_name = path.file_name().unwrap_or_default();
+            files.push((extensions.join(file_name), fs::read(path).map_err(LaunchError::SpawnFailed)?));
+        } else {
+            return Err(LaunchError::SpawnFailed(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{} is neither an extension directory nor an .xpi file", path.display()),
+            )));
+        }
+    }
+    Ok(files)
+}
+
+/// Reads the add-on ID from `browser_specific_settings.gecko.id`, or the older `applications.gecko.id`, in
+/// the extension's `manifest.json`. Firefox needs it to know the add-on by.
+fn gecko_id(dir: &Path) -> Result<String, LaunchError> {
+    let manifest = fs::read_to_string(dir.join("manifest.json")).map_err(LaunchError::SpawnFailed)?;
+    let manifest: serde_json::Value = serde_json::from_str(&manifest)
+        .map_err(|e| LaunchError::SpawnFailed(io::Error::new(io::ErrorKind::InvalidData, e)))?;
+
+    ["browser_specific_settings", "applications"]
+        .iter()
+        .find_map(|key| manifest[key]["gecko"]["id"].as_str())
+        .map(String::from)
+        .ok_or_else(|| LaunchError::Unsupported(format!("{} has no add-on ID for Firefox", dir.display())))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::extensions::{chromium_args, firefox_files};
+    use std::env;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn test_chromium_args() {
+        assert_eq!(
+            chromium_args(&[PathBuf::from("/ext/a"), PathBuf::from("/ext/b")]),
+            ["--load-extension=/ext/a,/ext/b", "--disable-extensions-except=/ext/a,/ext/b"]
+        );
+    }
+
+    #[test]
+    fn test_firefox_files() {
+        let dir = env::temp_dir().join(format!("rsbrowsers-extension-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("manifest.json"), r#"{"browser_specific_settings": {"gecko": {"id": "a@example.com"}}}"#)
+            .unwrap();
+
+        let canonical = dir.canonicalize().unwrap();
+        let files = firefox_files(std::slice::from_ref(&dir));
+        fs::write(dir.join("manifest.json"), "{}").unwrap();
+        let missing_id = firefox_files(std::slice::from_ref(&dir));
+        fs::remove_dir_all(&dir).unwrap();
+
+        let files = files.unwrap();
+        assert_eq!(files[0].0, Path::new("extensions/a@example.com"));
+        assert_eq!(files[0].1, canonical.to_string_lossy().as_bytes());
+        assert!(missing_id.is_err());
+    }
+}
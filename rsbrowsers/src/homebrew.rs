@@ -0,0 +1,218 @@
+//! Homebrew casks on macOS, read from the metadata Homebrew keeps in `Caskroom/<cask>/.metadata`.
+
+use crate::custom::Bundle;
+use crate::{Browser, InstallSource, OSX_BROWSER_BUNDLE_LIST, extract_info_from_plist};
+use plist::Value;
+use serde_json::Value as Json;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Marks the browsers installed by a cask and adds the known browsers that casks installed where the
+/// backends didn't look, see `BrowserFinder::with_package_managers`.
+pub(crate) fn apply(browsers: &mut Vec<Browser>, bundles: &[Bundle]) {
+    let app_dirs = app_dirs();
+    for caskroom in caskrooms() {
+        let Ok(entries) = fs::read_dir(&caskroom) else { continue };
+
+        for entry in entries.filter_map(Result::ok) {
+            let Ok(cask) = entry.file_name().into_string() else { continue };
+            for application in cask_apps(&entry.path(), &cask, &app_dirs) {
+                let application = application.canonicalize().unwrap_or(application);
+                let mut found = false;
+                for browser in browsers.iter_mut().filter(|browser| installed_at(browser, &application)) {
+                    browser.install_source = InstallSource::Homebrew;
+                    browser.package = Some(cask.clone());
+                    found = true;
+                }
+                if found {
+                    continue;
+                }
+                if let Some(browser) = known_browser(&application, bundles) {
+                    let package = Some(cask.clone());
+                    browsers.push(Browser { install_source: InstallSource::Homebrew, package, ..browser });
+                }
+            }
+        }
+    }
+}
+
+fn installed_at(browser: &Browser, application: &Path) -> bool {
+    browser.source_path.as_ref().is_some_and(|bundle| bundle.canonicalize().as_deref().unwrap_or(bundle) == application)
+}
+
+/// Reads a bundle the way `SpotlightBackend` does if its bundle ID is one of a known browser.
+fn known_browser(application: &Path, bundles: &[Bundle]) -> Option<Browser> {
+    let properties = Value::from_file(application.join("Contents/Info.plist")).ok()?;
+    let bundle_id = properties.as_dictionary()?.get("CFBundleIdentifier")?.as_string()?;
+    let (browser_type, _, version_key) = OSX_BROWSER_BUNDLE_LIST
+        .iter()
+        .copied()
+        .chain(
+            bundles
+                .iter()
+                .map(|bundle| (bundle.browser_type.as_str(), bundle.bundle_id.as_str(), bundle.version_key.as_str())),
+        )
+        .find(|(_, id, _)| id.eq_ignore_ascii_case(bundle_id))?;
+    extract_info_from_plist(application, browser_type, version_key)
+}
+
+/// Returns the `Caskroom` directories of `HOMEBREW_PREFIX` and the default prefixes of Apple silicon and
+/// Intel Macs.
+pub(crate) fn caskrooms() -> Vec<PathBuf> {
+    let defaults = [PathBuf::from("/opt/homebrew"), PathBuf::from("/usr/local")];
+    let mut caskrooms: Vec<PathBuf> = vec![];
+    for prefix in env::var_os("HOMEBREW_PREFIX").map(PathBuf::from).into_iter().chain(defaults) {
+        let caskroom = prefix.join("Caskroom");
+        if !caskrooms.contains(&caskroom) {
+            caskrooms.push(caskroom);
+        }
+    }
+    caskrooms
+}
+
+/// Returns the directories casks move apps into, the `--appdir` of `HOMEBREW_CASK_OPTS` first.
+fn app_dirs() -> Vec<PathBuf> {
+    let home = dirs::home_dir();
+    let appdir = env::var("HOMEBREW_CASK_OPTS").ok().and_then(|options| appdir_option(&options, home.as_deref()));
+    appdir
+        .into_iter()
+        .chain([PathBuf::from("/Applications")])
+        .chain(home.map(|home| home.join("Applications")))
+        .collect()
+}
+
+/// Parses `--appdir=<dir>` or `--appdir <dir>` out of `HOMEBREW_CASK_OPTS`, e.g. `--appdir=~/Applications`.
+fn appdir_option(options: &str, home: Option<&Path>) -> Option<PathBuf> {
+    let mut options = options.split_whitespace();
+    let dir = loop {
+        let option = options.next()?;
+        if let Some(dir) = option.strip_prefix("--appdir=") {
+            break dir;
+        } else if option == "--appdir" {
+            break options.next()?;
+        }
+    };
+    let dir = dir.trim_matches(['"', '\'']);
+    match (dir.strip_prefix("~/"), home) {
+        (Some(relative), Some(home)) => Some(home.join(relative)),
+        _ => Some(PathBuf::from(dir)),
+    }
+}
+
+/// Lists the app bundles a cask installed that still exist.
+fn cask_apps(cask_dir: &Path, cask: &str, app_dirs: &[PathBuf]) -> Vec<PathBuf> {
+    let metadata = cask_dir.join(".metadata");
+    // the install receipt of recent versions lists what uninstalling removes, the cask definition what
+    // installing adds
+    let receipt = read_json(&metadata.join("INSTALL_RECEIPT.json"));
+    let artifacts = match receipt.as_ref().and_then(|receipt| receipt.get("uninstall_artifacts")) {
+        Some(artifacts) => app_artifacts(artifacts),
+        None => latest_definition(&metadata, cask)
+            .and_then(|path| read_json(&path))
+            .map_or(vec![], |definition| definition.get("artifacts").map_or(vec![], app_artifacts)),
+    };
+
+    artifacts
+        .into_iter()
+        .filter_map(|app| match app.is_absolute() {
+            true => Some(app).filter(|app| app.is_dir()),
+            false => app_dirs.iter().map(|dir| dir.join(&app)).find(|path| path.is_dir()),
+        })
+        .collect()
+}
+
+fn read_json(path: &Path) -> Option<Json> {
+    serde_json::from_slice(&fs::read(path).ok()?).ok()
+}
+
+/// Returns the newest `<version>/<timestamp>/Casks/<cask>.json` below `.metadata`.
+fn latest_definition(metadata: &Path, cask: &str) -> Option<PathBuf> {
+    let dirs = |dir: &Path| fs::read_dir(dir).into_iter().flatten().filter_map(Result::ok).map(|entry| entry.path());
+    dirs(metadata)
+        .flat_map(|version| dirs(&version).collect::<Vec<_>>())
+        .map(|timestamp| timestamp.join("Casks").join(format!("{cask}.json")))
+        .filter(|path| path.is_file())
+        // timestamps such as `20240612093015.123` sort by time
+        .max_by_key(|path| path.parent().and_then(Path::parent).and_then(Path::file_name).map(ToOwned::to_owned))
+}
+
+/// Reads the app bundles of `app` artifacts, e.g. `[{"app": ["Firefox.app"]}]`, where a `target` renames
+/// the bundle, e.g. `[{"app": ["Chromium.app", {"target": "Ungoogled Chromium.app"}]}]`.
+fn app_artifacts(artifacts: &Json) -> Vec<PathBuf> {
+    artifacts
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|artifact| artifact.get("app")?.as_array())
+        .filter_map(|app| {
+            let target = app.iter().find_map(|item| item.get("target")?.as_str());
+            // the source may be inside a folder of the download, e.g. `Tor Browser/Tor Browser.app`
+            let source = Path::new(app.first()?.as_str()?);
+            match target {
+                Some(target) => Some(PathBuf::from(target)),
+                None if source.is_absolute() => Some(source.to_path_buf()),
+                None => source.file_name().map(PathBuf::from),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::homebrew::{app_artifacts, appdir_option, cask_apps};
+    use std::env;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn test_app_artifacts() {
+        let artifacts = serde_json::json!([
+            {"uninstall": [{"quit": "org.mozilla.firefox"}]},
+            {"app": ["Firefox.app"]},
+            {"app": ["Chromium.app", {"target": "Ungoogled Chromium.app"}]},
+            {"app": ["Tor Browser/Tor Browser.app"]},
+            {"app": ["/Applications/Arc.app"]},
+            {"zap": [{"trash": ["~/Library/Caches/Firefox"]}]},
+        ]);
+        assert_eq!(
+            app_artifacts(&artifacts),
+            ["Firefox.app", "Ungoogled Chromium.app", "Tor Browser.app", "/Applications/Arc.app"].map(PathBuf::from)
+        );
+        assert!(app_artifacts(&serde_json::json!({"app": ["Firefox.app"]})).is_empty());
+    }
+
+    #[test]
+    fn test_appdir_option() {
+        let home = Some(Path::new("/Users/me"));
+        assert_eq!(appdir_option("--no-quarantine --appdir=/opt/apps", home), Some(PathBuf::from("/opt/apps")));
+        assert_eq!(appdir_option("--appdir ~/Apps", home), Some(PathBuf::from("/Users/me/Apps")));
+        assert_eq!(appdir_option(r#"--appdir="~/My""#, home), Some(PathBuf::from("/Users/me/My")));
+        assert_eq!(appdir_option("--no-quarantine", home), None);
+    }
+
+    #[test]
+    fn test_cask_apps() {
+        let root = env::temp_dir().join(format!("rsbrowsers-caskroom-{}", std::process::id()));
+        let apps = root.join("Applications");
+        let firefox = root.join("Caskroom/firefox");
+        let definition = firefox.join(".metadata/128.0/20240709101500.123/Casks");
+        let older = firefox.join(".metadata/127.0/20240611090000.456/Casks");
+        for dir in [&apps.join("Firefox.app"), &apps.join("Firefox Old.app"), &definition, &older] {
+            fs::create_dir_all(dir).unwrap();
+        }
+        fs::write(definition.join("firefox.json"), r#"{"artifacts": [{"app": ["Firefox.app"]}]}"#).unwrap();
+        fs::write(older.join("firefox.json"), r#"{"artifacts": [{"app": ["Firefox Old.app"]}]}"#).unwrap();
+
+        let from_definition = cask_apps(&firefox, "firefox", &[root.join("missing"), apps.clone()]);
+        // the receipt takes precedence over the definitions
+        let receipt =
+            format!(r#"{{"uninstall_artifacts": [{{"app": ["{}"]}}]}}"#, apps.join("Firefox Old.app").display());
+        fs::write(firefox.join(".metadata/INSTALL_RECEIPT.json"), receipt).unwrap();
+        let from_receipt = cask_apps(&firefox, "firefox", &[]);
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(from_definition, [apps.join("Firefox.app")]);
+        assert_eq!(from_receipt, [apps.join("Firefox Old.app")]);
+    }
+}
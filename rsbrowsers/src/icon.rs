@@ -0,0 +1,230 @@
+use crate::Browser;
+#[cfg(target_os = "linux")]
+use {
+    crate::desktop_files,
+    freedesktop_desktop_entry::DesktopEntry,
+    std::fs,
+    std::path::{Path, PathBuf},
+};
+#[cfg(target_os = "windows")]
+use {pelite::FileMap, pelite::resources::Resources};
+#[cfg(target_os = "macos")]
+use {plist::Value, std::fs};
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+impl Browser {
+    /// Returns the browser's icon as PNG bytes, the smallest image at least `size` pixels wide or else the
+    /// largest one. Images are not scaled.
+    ///
+    /// The icon is read from the `.icns` file of the app bundle on macOS, the icon resource of the executable
+    /// on Windows, and on Linux from the file named by the desktop entry's `Icon` key, looked up in the
+    /// `hicolor` theme and `/usr/share/pixmaps`. `None` if there is no PNG icon, e.g. for executables that
+    /// only carry bitmap icons, or for browsers not found through a bundle or desktop entry.
+    pub fn icon(&self, size: u32) -> Option<Vec<u8>> {
+        best_png(images(self), size)
+    }
+}
+
+/// Picks the PNG that fits `size` best from images in any format.
+fn best_png(images: Vec<Vec<u8>>, size: u32) -> Option<Vec<u8>> {
+    let mut pngs: Vec<(u32, Vec<u8>)> =
+        images.into_iter().filter_map(|image| Some((png_width(&image)?, image))).collect();
+    pngs.sort_by_key(|(width, _)| *width);
+
+    match pngs.iter().position(|(width, _)| *width >= size) {
+        Some(index) => Some(pngs.swap_remove(index).1),
+        None => pngs.pop().map(|(_, image)| image),
+    }
+}
+
+/// Reads the width from the `IHDR` chunk, which always comes first.
+fn png_width(image: &[u8]) -> Option<u32> {
+    if !image.starts_with(PNG_SIGNATURE) || image.get(12..16)? != b"IHDR" {
+        return None;
+    }
+    Some(u32::from_be_bytes(image.get(16..20)?.try_into().ok()?))
+}
+
+#[cfg(target_os = "macos")]
+fn images(browser: &Browser) -> Vec<Vec<u8>> {
+    let Some(bundle) = browser.bundle() else { return vec![] };
+
+    let properties = Value::from_file(bundle.join("Contents/Info.plist")).ok();
+    let Some(icon_file) = properties.as_ref().and_then(|p| p.as_dictionary()?.get("CFBundleIconFile")?.as_string())
+    else {
+        return vec![];
+    };
+    // the extension may be left out, e.g. "app" for "app.icns"
+    let icon_file = match icon_file.ends_with(".icns") {
+        true => icon_file.to_string(),
+        false => format!("{icon_file}.icns"),
+    };
+    fs::read(bundle.join("Contents/Resources").join(icon_file)).map_or(vec![], |data| icns_images(&data))
+}
+
+/// Splits an `.icns` file into the data of its elements. Elements of 32 pixels and up hold PNG data in
+/// current files, the others are skipped by `best_png`.
+#[cfg(target_os = "macos")]
+fn icns_images(data: &[u8]) -> Vec<Vec<u8>> {
+    let mut images = vec![];
+    if data.get(..4) != Some(b"icns") {
+        return images;
+    }
+    // each element is a 4-byte type and a big-endian length that includes this header
+    let mut at = 8;
+    while let Some(header) = data.get(at..at + 8) {
+        let len = u32::from_be_bytes([header[4], header[5], header[6], header[7]]) as usize;
+        if len < 8 {
+            break;
+        }
+        images.extend(data.get(at + 8..at + len).map(<[u8]>::to_vec));
+        at += len;
+    }
+    images
+}
+
+#[cfg(target_os = "windows")]
+fn images(browser: &Browser) -> Vec<Vec<u8>> {
+    let Ok(file_map) = FileMap::open(&browser.path) else { return vec![] };
+    let resources = match pelite::pe64::PeFile::from_bytes(file_map.as_ref()) {
+        Ok(file) => {
+            use pelite::pe64::Pe;
+            file.resources().ok()
+        }
+        Err(pelite::Error::PeMagic) => {
+            use pelite::pe32::{Pe, PeFile};
+            PeFile::from_bytes(file_map.as_ref()).and_then(|file| file.resources()).ok()
+        }
+        Err(_) => None,
+    };
+    resources.map_or(vec![], application_icon)
+}
+
+/// Returns the images of the first icon group, which Explorer shows as the application's icon. Large
+/// sizes are usually stored as PNG, small ones as bitmaps.
+#[cfg(target_os = "windows")]
+fn application_icon(resources: Resources) -> Vec<Vec<u8>> {
+    let Some(Ok((_, group))) = resources.icons().next() else { return vec![] };
+    group.entries().iter().filter_map(|entry| group.image(entry.nId).ok().map(<[u8]>::to_vec)).collect()
+}
+
+#[cfg(target_os = "linux")]
+fn images(browser: &Browser) -> Vec<Vec<u8>> {
+    let Some(desktop_file) = browser.source_path.as_deref() else { return vec![] };
+    let Ok(bytes) = fs::read_to_string(desktop_file) else { return vec![] };
+    let Some(icon) = DesktopEntry::decode(desktop_file, &bytes).ok().and_then(|entry| entry.icon().map(str::to_owned))
+    else {
+        return vec![];
+    };
+    icon_files(&icon, &icon_dirs()).iter().filter_map(|path| fs::read(path).ok()).collect()
+}
+
+/// The directories icons are looked up in, the user's first, see the Icon Theme Specification.
+#[cfg(target_os = "linux")]
+fn icon_dirs() -> Vec<PathBuf> {
+    [dirs::data_dir().map(|dir| dir.join("icons")), dirs::home_dir().map(|home| home.join(".icons"))]
+        .into_iter()
+        .flatten()
+        .chain(desktop_files::data_dirs().into_iter().map(|dir| dir.join("icons")))
+        .chain([PathBuf::from("/usr/share/pixmaps")])
+        .collect()
+}
+
+/// Lists the PNG files of an `Icon` value in every size of the `hicolor` theme below `dirs`, or directly in
+/// them like in `/usr/share/pixmaps`. An absolute path, e.g. of a Snap, is taken as is.
+#[cfg(target_os = "linux")]
+fn icon_files(icon: &str, dirs: &[PathBuf]) -> Vec<PathBuf> {
+    if Path::new(icon).is_absolute() {
+        return vec![PathBuf::from(icon)];
+    }
+    let file_name = format!("{icon}.png");
+
+    let mut files = vec![];
+    for dir in dirs {
+        // e.g. "hicolor/256x256/apps/firefox.png"
+        for size_dir in fs::read_dir(dir.join("hicolor")).into_iter().flatten().filter_map(Result::ok) {
+            files.push(size_dir.path().join("apps").join(&file_name));
+        }
+        files.push(dir.join(&file_name));
+    }
+    files.retain(|path| path.is_file());
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::icon::best_png;
+
+    /// Returns the start of a PNG of the given width, which is all `best_png` reads.
+    fn png(width: u32) -> Vec<u8> {
+        let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+        png.extend(width.to_be_bytes());
+        png.extend(width.to_be_bytes());
+        png
+    }
+
+    #[test]
+    fn test_best_png() {
+        let images = || vec![png(256), b"BM bitmap".to_vec(), png(32), png(128)];
+
+        assert_eq!(best_png(images(), 48), Some(png(128)));
+        assert_eq!(best_png(images(), 128), Some(png(128)));
+        assert_eq!(best_png(images(), 1024), Some(png(256)));
+        assert_eq!(best_png(vec![b"BM bitmap".to_vec()], 16), None);
+    }
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn test_icns_images() {
+        use crate::icon::icns_images;
+
+        let mut icns = b"icns".to_vec();
+        let elements = [(b"ic07", png(128)), (b"ic08", png(256))];
+        let len = 8 + elements.iter().map(|(_, data)| 8 + data.len()).sum::<usize>();
+        icns.extend((len as u32).to_be_bytes());
+        for (kind, data) in &elements {
+            icns.extend(*kind);
+            icns.extend((8 + data.len() as u32).to_be_bytes());
+            icns.extend(data);
+        }
+
+        assert_eq!(icns_images(&icns), [png(128), png(256)]);
+        assert!(icns_images(b"not an icns").is_empty());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_icon_files() {
+        use crate::icon::icon_files;
+        use std::path::PathBuf;
+        use std::{env, fs};
+
+        let dir = env::temp_dir().join(format!("rsbrowsers-icons-{}", std::process::id()));
+        let pixmaps = dir.join("pixmaps");
+        for size in ["48x48", "256x256", "scalable"] {
+            fs::create_dir_all(dir.join("hicolor").join(size).join("apps")).unwrap();
+        }
+        fs::create_dir_all(&pixmaps).unwrap();
+        fs::write(dir.join("hicolor/48x48/apps/firefox.png"), png(48)).unwrap();
+        fs::write(dir.join("hicolor/256x256/apps/firefox.png"), png(256)).unwrap();
+        fs::write(pixmaps.join("firefox.png"), png(32)).unwrap();
+
+        let mut files = icon_files("firefox", &[dir.clone(), pixmaps.clone()]);
+        files.sort();
+        assert_eq!(
+            files,
+            [
+                dir.join("hicolor/256x256/apps/firefox.png"),
+                dir.join("hicolor/48x48/apps/firefox.png"),
+                pixmaps.join("firefox.png")
+            ]
+        );
+        assert_eq!(
+            icon_files("/snap/firefox/current/default256.png", &[]),
+            [PathBuf::from("/snap/firefox/current/default256.png")]
+        );
+        assert!(icon_files("chromium", std::slice::from_ref(&dir)).is_empty());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
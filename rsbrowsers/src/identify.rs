@@ -0,0 +1,174 @@
+use crate::version::DEFAULT_PROBE_TIMEOUT;
+use crate::{Browser, InstallSource};
+use std::path::Path;
+use std::time::Duration;
+#[cfg(target_os = "macos")]
+use {crate::OSX_BROWSER_BUNDLE_LIST, crate::extract_info_from_plist, plist::Value};
+#[cfg(target_os = "windows")]
+use {crate::get_version_info, pelite::FileMap};
+#[cfg(target_os = "linux")]
+use {
+    crate::version::{find_version, probe_output},
+    std::process::Command,
+};
+
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+const PRODUCT_NAME_LIST: &[(&str, &str)] = &[
+    // product name as reported by the executable, browser name (more specific names first)
+    ("Google Chrome for Testing", "chrome-test"),
+    ("Firefox Developer Edition", "firefox-developer"),
+    ("Opera Internet Browser", "opera"),
+    ("Internet Explorer", "msie"),
+    ("Mozilla Firefox", "firefox"),
+    ("Firefox Nightly", "firefox-nightly"),
+    ("Microsoft Edge", "msedge"),
+    ("Google Chrome", "chrome"),
+    ("Brave Browser", "brave"),
+    ("Tor Browser", "tor"),
+    ("Pale Moon", "pale-moon"),
+    ("LibreWolf", "librewolf"),
+    ("Chromium", "chromium"),
+    ("Waterfox", "waterfox"),
+    ("Basilisk", "basilisk"),
+    ("Vivaldi", "vivaldi"),
+    ("Firefox", "firefox"),
+    ("Floorp", "floorp"),
+    ("Opera", "opera"),
+];
+
+impl Browser {
+    /// Classifies the executable or app bundle at `path`, e.g. to validate a `CHROME_PATH`-style override.
+    ///
+    /// Reads the bundle ID on macOS, the `ProductName` version resource on Windows and `--version` output
+    /// on Linux, giving up on executables that don't answer within 5 seconds. Returns `None` if the file isn't
+    /// a browser this crate knows about.
+    pub fn identify(path: &Path) -> Option<Browser> {
+        Self::identify_within(path, DEFAULT_PROBE_TIMEOUT)
+    }
+
+    /// Like `identify`, killing a `--version` probe that runs longer than `timeout`.
+    pub(crate) fn identify_within(path: &Path, timeout: Duration) -> Option<Browser> {
+        let mut browser = identify(path, timeout)?;
+        browser.normalize_version();
+        Some(Browser { install_source: InstallSource::Custom, ..browser })
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn identify(path: &Path, _timeout: Duration) -> Option<Browser> {
+    // the path may point at the bundle or at the executable inside it
+    let application = path.ancestors().find(|path| path.extension().is_some_and(|extension| extension == "app"))?;
+    let properties = Value::from_file(application.join("Contents/Info.plist")).ok()?;
+    let bundle_id = properties.as_dictionary()?.get("CFBundleIdentifier")?.as_string()?;
+    let (browser_type, _, version_string) =
+        OSX_BROWSER_BUNDLE_LIST.iter().find(|(_, id, _)| id.eq_ignore_ascii_case(bundle_id))?;
+
+    extract_info_from_plist(application, browser_type, version_string)
+}
+
+#[cfg(target_os = "windows")]
+fn identify(path: &Path, _timeout: Duration) -> Option<Browser> {
+    let file_map = FileMap::open(path).ok()?;
+    let version_info = match pelite::pe64::PeFile::from_bytes(file_map.as_ref()) {
+        Ok(file) => {
+            use pelite::pe64::Pe;
+            file.resources().ok()?.version_info().ok()?
+        }
+        Err(pelite::Error::PeMagic) => {
+            use pelite::pe32::{Pe, PeFile};
+            PeFile::from_bytes(file_map.as_ref()).ok()?.resources().ok()?.version_info().ok()?
+        }
+        Err(_) => return None,
+    };
+    let language = *version_info.translation().first()?;
+    let product_name = version_info.value(language, "ProductName")?;
+    let (display_name, browser_type) = classify(&product_name)?;
+
+    Some(Browser {
+        browser_type,
+        path: path.to_path_buf(),
+        exec: None,
+        args: vec![],
+        display_name,
+        version: get_version_info(path),
+        raw_version: None,
+        install_source: InstallSource::System,
+        source_id: None,
+        source_path: None,
+        package: None,
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn identify(path: &Path, timeout: Duration) -> Option<Browser> {
+    // e.g. "Google Chrome 120.0.6099.109 beta" or "Mozilla Firefox 121.0"
+    let output = probe_output(Command::new(path).arg("--version"), timeout)?;
+    let (display_name, browser_type) = classify(&output)?;
+
+    Some(Browser {
+        browser_type,
+        path: path.to_path_buf(),
+        exec: None,
+        args: vec![],
+        display_name,
+        version: find_version(&output).map_or("".to_string(), |version| version.to_string()),
+        raw_version: None,
+        install_source: InstallSource::System,
+        source_id: None,
+        source_path: None,
+        package: None,
+    })
+}
+
+/// Maps a product name or `--version` output to a display name and browser type, appending the channel
+/// if the output ends with one such as `beta`.
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+fn classify(product: &str) -> Option<(String, String)> {
+    let product = product.trim();
+    let (name, browser_type) = PRODUCT_NAME_LIST.iter().find(|(name, _)| product.starts_with(name))?;
+    let channel = match product.rsplit(' ').next() {
+        Some("beta") => "-beta",
+        Some("dev" | "unstable") => "-dev",
+        Some("canary") => "-canary",
+        _ => "",
+    };
+    Some((name.to_string(), format!("{browser_type}{channel}")))
+}
+
+#[cfg(test)]
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+mod tests {
+    use crate::identify::classify;
+
+    #[test]
+    fn test_classify() {
+        let classify = |product| classify(product).map(|(_, browser_type)| browser_type);
+
+        assert_eq!(classify("Google Chrome 120.0.6099.109 "), Some("chrome".to_string()));
+        assert_eq!(classify("Google Chrome 121.0.6167.16 beta\n"), Some("chrome-beta".to_string()));
+        assert_eq!(classify("Google Chrome for Testing 120.0.6099.109"), Some("chrome-test".to_string()));
+        assert_eq!(classify("Mozilla Firefox 121.0"), Some("firefox".to_string()));
+        assert_eq!(classify("Microsoft Edge 120.0.2210.91 dev"), Some("msedge-dev".to_string()));
+        assert_eq!(classify("Brave Browser 120.1.61.109"), Some("brave".to_string()));
+        assert_eq!(classify("GNU bash, version 5.2.21"), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_identify_timeout() {
+        use crate::Browser;
+        use std::os::unix::fs::PermissionsExt;
+        use std::time::{Duration, Instant};
+        use std::{env, fs};
+
+        let path = env::temp_dir().join(format!("rsbrowsers-identify-{}", std::process::id()));
+        fs::write(&path, "#!/bin/sh\nsleep 10\necho 'Mozilla Firefox 121.0'\n").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        let started = Instant::now();
+        let browser = Browser::identify_within(&path, Duration::from_millis(100));
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(browser, None);
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}
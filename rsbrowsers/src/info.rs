@@ -0,0 +1,490 @@
+use crate::{Browser, InstallSource, Version};
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+/// Typed metadata about a browser, derived from a `Browser` with `Browser::info`.
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, Eq, Hash, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BrowserInfo {
+    pub browser_type: String,
+    pub display_name: String,
+    /// The executable, without any arguments from a Linux desktop entry.
+    #[serde(serialize_with = "crate::serialize_path")]
+    pub path: PathBuf,
+    /// `None` if the browser didn't report a numeric version.
+    pub version: Option<Version>,
+    /// See `Browser::chromium_version`.
+    #[serde(default)]
+    pub chromium_version: Option<Version>,
+    pub engine: Engine,
+    pub channel: Channel,
+    pub architecture: Architecture,
+    pub install_scope: InstallScope,
+    pub install_source: InstallSource,
+}
+
+/// A serialized browser tagged with its schema, e.g. `{"schema": "2", "browserType": "chrome", ...}`.
+///
+/// Schema `1` is the original `Browser` layout, schema `2` is `BrowserInfo`.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+#[serde(tag = "schema")]
+pub enum BrowserRecord {
+    #[serde(rename = "1")]
+    V1(Browser),
+    #[serde(rename = "2")]
+    V2(BrowserInfo),
+}
+
+/// The rendering engine a browser is built on.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub enum Engine {
+    /// Chromium-based browsers, including Qt WebEngine ones such as Falkon.
+    Blink,
+    Gecko,
+    WebKit,
+    Trident,
+    Servo,
+    Unknown,
+}
+
+const ENGINE_LIST: &[(&str, Engine)] = &[
+    // browser name, engine (channels such as "chrome-beta" share the engine of "chrome")
+    ("arc", Engine::Blink),
+    ("badwolf", Engine::WebKit),
+    ("basilisk", Engine::Gecko),
+    ("brave", Engine::Blink),
+    ("chrome", Engine::Blink),
+    ("chromium", Engine::Blink),
+    ("duckduckgo", Engine::WebKit),
+    ("epic", Engine::Blink),
+    ("epiphany", Engine::WebKit),
+    ("falkon", Engine::Blink),
+    ("firefox", Engine::Gecko),
+    ("floorp", Engine::Gecko),
+    ("konqueror", Engine::Blink),
+    ("librewolf", Engine::Gecko),
+    ("luakit", Engine::WebKit),
+    ("midori", Engine::Gecko),
+    ("min", Engine::Blink),
+    ("msedge", Engine::Blink),
+    ("msie", Engine::Trident),
+    ("opera", Engine::Blink),
+    ("orion", Engine::WebKit),
+    ("pale-moon", Engine::Gecko),
+    ("qutebrowser", Engine::Blink),
+    ("safari", Engine::WebKit),
+    ("servo", Engine::Servo),
+    ("sidekick", Engine::Blink),
+    ("sigmaos", Engine::WebKit),
+    ("surf", Engine::WebKit),
+    ("tor", Engine::Gecko),
+    ("ungoogled-chromium", Engine::Blink),
+    ("vivaldi", Engine::Blink),
+    ("waterfox", Engine::Gecko),
+    ("wavebox", Engine::Blink),
+    ("webkit", Engine::WebKit),
+    ("webview2", Engine::Blink),
+    ("yandex", Engine::Blink),
+    ("zen", Engine::Gecko),
+];
+
+/// The release channel of a browser.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub enum Channel {
+    Stable,
+    Beta,
+    /// Includes Firefox Developer Edition, Opera developer and Safari Technology Preview.
+    Dev,
+    Canary,
+    Nightly,
+    /// Extended Support Release, e.g. Firefox ESR.
+    Esr,
+}
+
+const CHANNEL_SUFFIX_LIST: &[(&str, Channel)] = &[
+    // browser type suffix, channel (longer suffixes first, anything else is a stable browser of its own)
+    ("-technology-preview", Channel::Dev),
+    ("-developer", Channel::Dev),
+    ("-preview", Channel::Dev),
+    ("-canary", Channel::Canary),
+    ("-nightly", Channel::Nightly),
+    ("-beta", Channel::Beta),
+    ("-dev", Channel::Dev),
+    ("-esr", Channel::Esr),
+];
+
+/// The CPU architecture of a browser's executable.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub enum Architecture {
+    X86,
+    X86_64,
+    Arm64,
+    /// A macOS universal binary with several architectures.
+    Universal,
+    /// The executable is a script or could not be read.
+    Unknown,
+}
+
+/// Whether a browser is installed for the current user only or for all users.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub enum InstallScope {
+    User,
+    System,
+}
+
+impl Browser {
+    /// Returns typed metadata about the browser. Reads the executable's header to tell its architecture.
+    pub fn info(&self) -> BrowserInfo {
+        let path = self.path.clone();
+        BrowserInfo {
+            browser_type: self.browser_type.to_owned(),
+            display_name: self.display_name.to_owned(),
+            version: self.parsed_version(),
+            chromium_version: self.chromium_version(),
+            engine: self.engine(),
+            channel: Channel::of(&self.browser_type),
+            architecture: Architecture::of(&path),
+            install_scope: InstallScope::of(&path),
+            install_source: self.install_source,
+            path,
+        }
+    }
+
+    /// Returns the rendering engine, e.g. `Engine::Blink` for `msedge-beta`.
+    pub fn engine(&self) -> Engine {
+        Engine::of(&self.browser_type)
+    }
+
+    /// Returns the version of Chromium the browser is built on, `None` for other engines and for browsers
+    /// that number their releases independently, e.g. Opera and Vivaldi.
+    ///
+    /// Chrome and Chromium builds report it as their own version. Only the major version is known for Edge,
+    /// whose releases share it, and for Brave, which prefixes its raw version with it, e.g. `120` for
+    /// `120.1.61.109`.
+    pub fn chromium_version(&self) -> Option<Version> {
+        match self.family() {
+            "chrome" | "chrome-test" | "chromium" | "ungoogled-chromium" => self.parsed_version(),
+            "msedge" => self.parsed_version()?.components().first()?.to_string().parse().ok(),
+            "brave" => {
+                let raw = self.raw_version.as_deref().unwrap_or(&self.version);
+                // a version without the Chromium major has three components, e.g. "1.61.109"
+                let components: Vec<&str> = raw.split('.').collect();
+                match components.len() {
+                    4 => components[0].parse().ok(),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the browser type without its channel suffix, e.g. `chrome` for `chrome-canary` and `safari` for
+    /// `safari-technology-preview`.
+    pub fn family(&self) -> &str {
+        Channel::split(&self.browser_type).0
+    }
+
+    /// Returns the release channel, e.g. `Channel::Canary` for `chrome-canary`.
+    pub fn channel(&self) -> Channel {
+        Channel::of(&self.browser_type)
+    }
+
+    /// Whether the executable is gone or no longer executable, e.g. a registry key or desktop entry left
+    /// behind by an uninstaller. Paths that can't be checked, e.g. for lack of permissions, are kept.
+    pub(crate) fn is_stale(&self) -> bool {
+        match fs::metadata(&self.path) {
+            // Safari is listed by its bundle
+            Ok(metadata) if metadata.is_dir() => !cfg!(target_os = "macos"),
+            Ok(metadata) => !is_executable(&metadata),
+            Err(error) => error.kind() == io::ErrorKind::NotFound,
+        }
+    }
+
+    /// Returns the application ID of a Flatpak, e.g. `org.mozilla.firefox` for
+    /// `flatpak run org.mozilla.firefox`.
+    #[cfg(target_os = "linux")]
+    pub(crate) fn flatpak_app_id(&self) -> Option<String> {
+        if self.path.file_name().is_none_or(|name| name != "flatpak") {
+            return None;
+        }
+        let argv = crate::exec::split(self.exec.as_deref()?).ok()?;
+        let program = crate::exec::program(&argv)?;
+        // the arguments after `flatpak`, which may be run through `env`
+        let mut arguments = argv.iter().skip_while(|argument| *argument != program).skip(1);
+        arguments.find(|argument| !argument.starts_with('-') && *argument != "run").cloned()
+    }
+
+    /// Returns the `.app` bundle the browser was found in, or the one containing its executable.
+    #[cfg(target_os = "macos")]
+    pub(crate) fn bundle(&self) -> Option<PathBuf> {
+        self.source_path.clone().or_else(|| {
+            self.path
+                .ancestors()
+                .find(|path| path.extension().is_some_and(|extension| extension == "app"))
+                .map(Path::to_path_buf)
+        })
+    }
+}
+
+impl Engine {
+    /// Returns the engine of a browser type such as `chrome-beta`.
+    pub fn of(browser_type: &str) -> Engine {
+        ENGINE_LIST
+            .iter()
+            .find(|(name, _)| {
+                browser_type == *name || browser_type.strip_prefix(name).is_some_and(|rest| rest.starts_with('-'))
+            })
+            .map_or(Engine::Unknown, |(_, engine)| *engine)
+    }
+}
+
+impl Channel {
+    /// Returns the channel of a browser type from its suffix, e.g. `Beta` for `msedge-beta`.
+    pub fn of(browser_type: &str) -> Channel {
+        Self::split(browser_type).1
+    }
+
+    /// Splits a browser type into its family and channel, e.g. `("msedge", Channel::Beta)` for `msedge-beta`.
+    pub(crate) fn split(browser_type: &str) -> (&str, Channel) {
+        CHANNEL_SUFFIX_LIST
+            .iter()
+            .find_map(|(suffix, channel)| Some((browser_type.strip_suffix(suffix)?, *channel)))
+            .filter(|(family, _)| !family.is_empty())
+            .unwrap_or((browser_type, Channel::Stable))
+    }
+}
+
+impl Architecture {
+    /// Reads the architecture from an ELF, Mach-O or PE executable header.
+    pub fn of(path: &Path) -> Architecture {
+        match read_header(path) {
+            Some(header) => Self::from_header(&header),
+            None => Architecture::Unknown,
+        }
+    }
+
+    /// Returns whether the executable at `path` is one of `architectures`. A universal binary matches if any
+    /// of its slices does, or if `Universal` itself is wanted.
+    pub(crate) fn any_of(path: &Path, architectures: &[Architecture]) -> bool {
+        let Some(header) = read_header(path) else {
+            return architectures.contains(&Architecture::Unknown);
+        };
+        match Self::from_header(&header) {
+            Architecture::Universal => {
+                architectures.contains(&Architecture::Universal)
+                    || Self::fat_slices(&header).iter().any(|slice| architectures.contains(slice))
+            }
+            architecture => architectures.contains(&architecture),
+        }
+    }
+
+    /// Reads the architectures of a Mach-O universal binary from its big-endian fat header.
+    fn fat_slices(header: &[u8]) -> Vec<Architecture> {
+        let u32_be = |at: usize| header.get(at..at + 4).map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]));
+        let count = u32_be(4).unwrap_or(0) as usize;
+
+        // each fat_arch entry is 20 bytes and starts with the CPU type
+        (0..count)
+            .map_while(|index| u32_be(8 + index * 20))
+            .map(|cpu_type| match cpu_type {
+                7 => Architecture::X86,
+                0x0100_0007 => Architecture::X86_64,
+                0x0100_000c => Architecture::Arm64,
+                _ => Architecture::Unknown,
+            })
+            .collect()
+    }
+
+    fn from_header(header: &[u8]) -> Architecture {
+        let u16_le = |at: usize| header.get(at..at + 2).map(|b| u16::from_le_bytes([b[0], b[1]]));
+        let u32_le = |at: usize| header.get(at..at + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]));
+
+        match header {
+            // ELF, assuming a little-endian machine field
+            [0x7f, b'E', b'L', b'F', ..] => match u16_le(18) {
+                Some(3) => Architecture::X86,
+                Some(62) => Architecture::X86_64,
+                Some(183) => Architecture::Arm64,
+                _ => Architecture::Unknown,
+            },
+            // 32 and 64-bit Mach-O
+            [0xce | 0xcf, 0xfa, 0xed, 0xfe, ..] => match u32_le(4) {
+                Some(7) => Architecture::X86,
+                Some(0x0100_0007) => Architecture::X86_64,
+                Some(0x0100_000c) => Architecture::Arm64,
+                _ => Architecture::Unknown,
+            },
+            [0xca, 0xfe, 0xba, 0xbe, ..] => Architecture::Universal,
+            [b'M', b'Z', ..] => {
+                let pe = u32_le(0x3c).map_or(0, |offset| offset as usize);
+                match (header.get(pe..pe + 4), u16_le(pe + 4)) {
+                    (Some(b"PE\0\0"), Some(0x014c)) => Architecture::X86,
+                    (Some(b"PE\0\0"), Some(0x8664)) => Architecture::X86_64,
+                    (Some(b"PE\0\0"), Some(0xaa64)) => Architecture::Arm64,
+                    _ => Architecture::Unknown,
+                }
+            }
+            _ => Architecture::Unknown,
+        }
+    }
+}
+
+fn read_header(path: &Path) -> Option<Vec<u8>> {
+    let mut header = vec![];
+    File::open(path).and_then(|file| file.take(4096).read_to_end(&mut header)).ok()?;
+    Some(header)
+}
+
+impl InstallScope {
+    /// Treats anything under the user's home directory as a per-user install.
+    pub fn of(path: &Path) -> InstallScope {
+        match dirs::home_dir() {
+            Some(home) if path.starts_with(&home) => InstallScope::User,
+            _ => InstallScope::System,
+        }
+    }
+}
+
+impl From<BrowserRecord> for BrowserInfo {
+    fn from(record: BrowserRecord) -> Self {
+        match record {
+            BrowserRecord::V1(browser) => browser.info(),
+            BrowserRecord::V2(info) => info,
+        }
+    }
+}
+
+#[cfg(unix)]
+pub(crate) fn is_executable(metadata: &fs::Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    metadata.permissions().mode() & 0o111 != 0
+}
+
+#[cfg(not(unix))]
+pub(crate) fn is_executable(_metadata: &fs::Metadata) -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Architecture, Browser, BrowserInfo, BrowserRecord, Channel, Engine, Version};
+
+    #[test]
+    fn test_engine_and_channel() {
+        assert_eq!(Engine::of("msedge-canary"), Engine::Blink);
+        assert_eq!(Engine::of("firefox-developer"), Engine::Gecko);
+        assert_eq!(Engine::of("safari-technology-preview"), Engine::WebKit);
+        assert_eq!(Engine::of("servo"), Engine::Servo);
+        assert_eq!(Engine::of("chromium-snapshot"), Engine::Blink);
+        assert_eq!(Engine::of("ungoogled-chromium"), Engine::Blink);
+        assert_eq!(Engine::of("ladybird"), Engine::Unknown);
+
+        assert_eq!(Channel::of("msedge-canary"), Channel::Canary);
+        assert_eq!(Channel::of("firefox-developer"), Channel::Dev);
+        assert_eq!(Channel::of("safari-technology-preview"), Channel::Dev);
+        assert_eq!(Channel::of("pale-moon"), Channel::Stable);
+        assert_eq!(Channel::of("dev"), Channel::Stable);
+        assert_eq!(Channel::of("firefox-esr"), Channel::Esr);
+    }
+
+    #[test]
+    fn test_family() {
+        let family = |browser_type: &str| Channel::split(browser_type).0.to_string();
+
+        assert_eq!(family("chrome-canary"), "chrome");
+        assert_eq!(family("opera-developer"), "opera");
+        assert_eq!(family("safari-technology-preview"), "safari");
+        assert_eq!(family("firefox-esr"), "firefox");
+        assert_eq!(family("pale-moon"), "pale-moon");
+    }
+
+    #[test]
+    fn test_engine_covers_platform_lists() {
+        #[cfg(target_os = "macos")]
+        let browser_types = crate::OSX_BROWSER_BUNDLE_LIST.iter().map(|(browser_type, _, _)| *browser_type);
+        #[cfg(target_os = "windows")]
+        let browser_types = crate::WINDOWS_REGISTRY_BROWSER_NAMES
+            .values()
+            .copied()
+            .chain(crate::WINDOWS_EXECUTABLE_LIST.iter().map(|(_, _, browser_type)| *browser_type));
+        #[cfg(target_os = "linux")]
+        let browser_types = crate::LINUX_DESKTOP_ENTRY_NAME_LIST.values().copied();
+
+        for browser_type in browser_types {
+            assert_ne!(Engine::of(browser_type), Engine::Unknown, "{browser_type}");
+        }
+    }
+
+    #[test]
+    fn test_architecture() {
+        let mut elf = vec![0x7f, b'E', b'L', b'F'];
+        elf.resize(18, 0);
+        elf.extend([183, 0]);
+        assert_eq!(Architecture::from_header(&elf), Architecture::Arm64);
+
+        let mut pe = vec![0; 0x40];
+        pe[..2].copy_from_slice(b"MZ");
+        pe[0x3c] = 0x40;
+        pe.extend(b"PE\0\0");
+        pe.extend([0x64, 0x86]);
+        assert_eq!(Architecture::from_header(&pe), Architecture::X86_64);
+
+        assert_eq!(Architecture::from_header(b"#!/bin/sh"), Architecture::Unknown);
+
+        let mut fat = vec![0xca, 0xfe, 0xba, 0xbe, 0, 0, 0, 2];
+        fat.extend([1, 0, 0, 7]);
+        fat.resize(28, 0);
+        fat.extend([1, 0, 0, 12]);
+        fat.resize(48, 0);
+        assert_eq!(Architecture::from_header(&fat), Architecture::Universal);
+        assert_eq!(Architecture::fat_slices(&fat), [Architecture::X86_64, Architecture::Arm64]);
+    }
+
+    #[test]
+    fn test_chromium_version() {
+        let browser = |browser_type: &str, version: &str, raw_version: Option<&str>| Browser {
+            version: version.to_string(),
+            raw_version: raw_version.map(str::to_string),
+            ..Browser::test_fixture(browser_type, browser_type)
+        };
+        let version = |version: &str| Some(version.parse::<Version>().unwrap());
+
+        assert_eq!(browser("chrome-beta", "121.0.6167.16", None).chromium_version(), version("121.0.6167.16"));
+        assert_eq!(browser("msedge", "120.0.2210.91", None).chromium_version(), version("120"));
+        assert_eq!(browser("brave", "1.61.109", Some("120.1.61.109")).chromium_version(), version("120"));
+        assert_eq!(browser("brave", "1.61.109", None).chromium_version(), None);
+        assert_eq!(browser("opera", "105.0.4970.48", None).chromium_version(), None);
+        assert_eq!(browser("firefox", "121.0", None).chromium_version(), None);
+    }
+
+    #[test]
+    fn test_record() {
+        let v1 = r#"{"schema":"1","browserType":"chrome-beta","path":"/opt/chrome","displayName":"Chrome Beta","version":"121.0.1"}"#;
+        let info = BrowserInfo::from(serde_json::from_str::<BrowserRecord>(v1).unwrap());
+        assert_eq!(info.channel, Channel::Beta);
+        assert_eq!(info.version, Some("121.0.1".parse().unwrap()));
+
+        let v2 = serde_json::to_string(&BrowserRecord::V2(info.clone())).unwrap();
+        assert!(v2.starts_with(r#"{"schema":"2","#));
+        assert_eq!(BrowserInfo::from(serde_json::from_str::<BrowserRecord>(&v2).unwrap()), info);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_non_unicode_path() {
+        use crate::Browsers;
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let browser = Browser::test_fixture("chrome", OsStr::from_bytes(b"/opt/br\xf6wser/chrome"));
+        let json = serde_json::to_value(browser.info()).unwrap();
+        assert_eq!(json["path"], "/opt/br\u{fffd}wser/chrome");
+        assert!(Browsers::from(vec![browser]).to_csv().unwrap().contains("/opt/br\u{fffd}wser/chrome"));
+    }
+}
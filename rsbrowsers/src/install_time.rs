@@ -0,0 +1,196 @@
+use crate::Browser;
+use std::fs;
+use std::path::Path;
+#[cfg(target_os = "linux")]
+use std::path::PathBuf;
+use std::time::SystemTime;
+#[cfg(target_os = "windows")]
+use {crate::InstallSource, std::time::Duration};
+
+/// Where dpkg keeps the list of files each package installed.
+#[cfg(target_os = "linux")]
+const DPKG_INFO_DIR: &str = "/var/lib/dpkg/info";
+
+impl Browser {
+    /// Returns when the browser was installed: the `InstallDate` of its uninstall entry on Windows, the
+    /// creation time of the app bundle on macOS and of the Snap or Flatpak on Linux.
+    ///
+    /// `None` where the platform doesn't record it, e.g. for browsers from distribution packages. Some
+    /// installers, like Chrome's, rewrite `InstallDate` on every update.
+    pub fn installed_at(&self) -> Option<SystemTime> {
+        installed_at(self)
+    }
+
+    /// Returns when the browser was last installed or updated: when the current revision of a Snap or
+    /// Flatpak was deployed or the dpkg package was unpacked on Linux, when `Info.plist` of the app bundle
+    /// was written on macOS and when the package of a Microsoft Store install was added on Windows.
+    ///
+    /// Falls back to the modification time of the executable, `None` if it doesn't exist.
+    pub fn updated_at(&self) -> Option<SystemTime> {
+        updated_at(self)
+    }
+}
+
+fn created(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.created()).ok()
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+#[cfg(target_os = "macos")]
+fn installed_at(browser: &Browser) -> Option<SystemTime> {
+    created(&browser.bundle()?)
+}
+
+#[cfg(target_os = "macos")]
+fn updated_at(browser: &Browser) -> Option<SystemTime> {
+    // the bundle itself keeps its time when an updater replaces its contents
+    browser
+        .bundle()
+        .and_then(|bundle| modified(&bundle.join("Contents/Info.plist")))
+        .or_else(|| modified(&browser.path))
+}
+
+#[cfg(target_os = "windows")]
+fn installed_at(browser: &Browser) -> Option<SystemTime> {
+    let install_date: String = crate::uninstall_entry(&browser.path)?.get_value("InstallDate").ok()?;
+    install_date_time(&install_date)
+}
+
+#[cfg(target_os = "windows")]
+fn updated_at(browser: &Browser) -> Option<SystemTime> {
+    // every version of a package is installed into a new folder
+    let package = browser.source_path.as_deref().filter(|_| browser.install_source == InstallSource::MsStore);
+    package.and_then(created).or_else(|| modified(&browser.path))
+}
+
+/// Converts an `InstallDate` such as `20240115` to midnight UTC of that day.
+#[cfg(target_os = "windows")]
+fn install_date_time(install_date: &str) -> Option<SystemTime> {
+    if install_date.len() != 8 || !install_date.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    let year: i64 = install_date[..4].parse().ok()?;
+    let month: i64 = install_date[4..6].parse().ok()?;
+    let day: i64 = install_date[6..].parse().ok()?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    // days since 1970-01-01 in the proleptic Gregorian calendar, with years starting in March
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = u64::try_from(era * 146097 + day_of_era - 719468).ok()?;
+    SystemTime::UNIX_EPOCH.checked_add(Duration::from_secs(days * 86400))
+}
+
+#[cfg(target_os = "linux")]
+fn installed_at(browser: &Browser) -> Option<SystemTime> {
+    // the directory of a Snap or Flatpak is kept across updates
+    created(&package_dir(browser)?)
+}
+
+#[cfg(target_os = "linux")]
+fn updated_at(browser: &Browser) -> Option<SystemTime> {
+    if let Some(dir) = package_dir(browser) {
+        // "current" of a Snap and "active" of a Flatpak are relinked to the new revision on every update
+        let link = match browser.flatpak_app_id() {
+            Some(_) => dir.join("current/active"),
+            None => dir.join("current"),
+        };
+        return fs::symlink_metadata(link).and_then(|metadata| metadata.modified()).ok();
+    }
+
+    // unpacked files keep the time they had in the package, so the list of the package is used instead
+    let executables = [Some(browser.path.clone()), fs::canonicalize(&browser.path).ok()];
+    package_list(Path::new(DPKG_INFO_DIR), executables.iter().flatten())
+        .and_then(|list| modified(&list))
+        .or_else(|| modified(&browser.path))
+}
+
+/// Returns the directory of a Snap, e.g. `/snap/chromium`, or of a Flatpak installed for the user or
+/// system-wide.
+#[cfg(target_os = "linux")]
+fn package_dir(browser: &Browser) -> Option<PathBuf> {
+    if let Ok(name) = browser.path.strip_prefix("/snap/bin") {
+        return Some(Path::new("/snap").join(name));
+    }
+    let app_id = browser.flatpak_app_id()?;
+    [dirs::data_dir().map(|dir| dir.join("flatpak/app")), Some(PathBuf::from("/var/lib/flatpak/app"))]
+        .into_iter()
+        .flatten()
+        .map(|dir| dir.join(&app_id))
+        .find(|dir| dir.is_dir())
+}
+
+/// Finds the `.list` file in `dir` of the package that installed one of `executables`.
+#[cfg(target_os = "linux")]
+fn package_list<'a>(dir: &Path, executables: impl Iterator<Item = &'a PathBuf> + Clone) -> Option<PathBuf> {
+    fs::read_dir(dir)
+        .ok()?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "list"))
+        .find(|path| {
+            fs::read_to_string(path).is_ok_and(|list| {
+                list.lines().any(|line| executables.clone().any(|executable| Path::new(line) == executable))
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Browser, InstallSource};
+
+    #[test]
+    fn test_missing_executable() {
+        let browser = Browser {
+            install_source: InstallSource::Custom,
+            ..Browser::test_fixture("ladybird", "/nonexistent/ladybird")
+        };
+
+        assert_eq!(browser.installed_at(), None);
+        assert_eq!(browser.updated_at(), None);
+    }
+
+    #[cfg(target_os = "windows")]
+    #[test]
+    fn test_install_date_time() {
+        use crate::install_time::install_date_time;
+        use std::time::{Duration, SystemTime};
+
+        let at = |secs| Some(SystemTime::UNIX_EPOCH + Duration::from_secs(secs));
+        assert_eq!(install_date_time("19700101"), at(0));
+        assert_eq!(install_date_time("20000301"), at(951868800));
+        assert_eq!(install_date_time("20240115"), at(1705276800));
+        assert_eq!(install_date_time("2024-01-15"), None);
+        assert_eq!(install_date_time("20241315"), None);
+        assert_eq!(install_date_time("19691231"), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_package_list() {
+        use crate::install_time::package_list;
+        use std::path::PathBuf;
+        use std::{env, fs};
+
+        let dir = env::temp_dir().join(format!("rsbrowsers-dpkg-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("firefox.list"), "/.\n/usr/bin/firefox\n/usr/lib/firefox/firefox\n").unwrap();
+        fs::write(dir.join("firefox.md5sums"), "0 usr/bin/chromium\n").unwrap();
+        fs::write(dir.join("chromium.list"), "/.\n/usr/lib/chromium/chromium\n").unwrap();
+
+        let executables = [PathBuf::from("/usr/bin/chromium"), PathBuf::from("/usr/lib/chromium/chromium")];
+        assert_eq!(package_list(&dir, executables.iter()), Some(dir.join("chromium.list")));
+        assert_eq!(package_list(&dir, [PathBuf::from("/usr/bin/firefox")].iter()), Some(dir.join("firefox.list")));
+        assert_eq!(package_list(&dir, [PathBuf::from("/usr/bin/opera")].iter()), None);
+        assert_eq!(package_list(&dir.join("missing"), executables.iter()), None);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,113 @@
+use crate::Browser;
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io;
+use std::path::{Path, PathBuf};
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+use std::process::Command;
+
+/// Fingerprint of a browser's executable, to check that it is a genuine vendor binary before launching it.
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, Eq, Hash, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Integrity {
+    /// Lowercase hex SHA-256 of the main executable.
+    pub sha256: String,
+    /// The identity of a valid code signature: the Team ID on macOS, e.g. `EQHXZ8M8AV` for Google, or the
+    /// Authenticode publisher on Windows, e.g. `Google LLC`.
+    ///
+    /// `None` if the executable is unsigned, its signature does not verify, or on Linux.
+    pub signer: Option<String>,
+}
+
+impl Browser {
+    /// Hashes the executable and checks its code signature. Reads the whole executable, so this is not
+    /// part of a scan.
+    pub fn integrity(&self) -> io::Result<Integrity> {
+        let binary = main_binary(&self.path);
+
+        let mut hasher = Sha256::new();
+        io::copy(&mut File::open(&binary)?, &mut hasher)?;
+        let sha256 = hasher.finalize().iter().map(|byte| format!("{byte:02x}")).collect();
+
+        Ok(Integrity { sha256, signer: signer(&self.path) })
+    }
+}
+
+/// Returns the binary inside an app bundle such as Safari's, which is listed by its bundle path.
+fn main_binary(executable: &Path) -> PathBuf {
+    match executable.file_stem().filter(|_| executable.is_dir()) {
+        Some(name) => executable.join("Contents/MacOS").join(name),
+        None => executable.to_path_buf(),
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn signer(executable: &Path) -> Option<String> {
+    // verify the whole bundle rather than only the binary
+    let bundle = executable.ancestors().find(|path| path.extension().is_some_and(|extension| extension == "app"));
+    let target = bundle.unwrap_or(executable);
+
+    let verified = Command::new("codesign").args(["--verify", "--strict"]).arg(target).status().ok()?.success();
+    if !verified {
+        return None;
+    }
+    // details go to stderr, e.g. "TeamIdentifier=EQHXZ8M8AV"
+    let output = Command::new("codesign").args(["-dv", "--verbose=2"]).arg(target).output().ok()?;
+    team_identifier(&String::from_utf8_lossy(&output.stderr))
+}
+
+/// Takes the Team ID, or the first authority for Apple's own apps which have none.
+#[cfg(target_os = "macos")]
+fn team_identifier(details: &str) -> Option<String> {
+    let value = |key: &str| details.lines().find_map(|line| line.strip_prefix(key)).map(str::to_string);
+    value("TeamIdentifier=").filter(|team| team != "not set").or_else(|| value("Authority="))
+}
+
+#[cfg(target_os = "windows")]
+fn signer(executable: &Path) -> Option<String> {
+    // the path is passed through the environment to avoid quoting it for PowerShell
+    let script = "$s = Get-AuthenticodeSignature -LiteralPath $env:RSBROWSERS_PATH; \
+                  if ($s.Status -eq 'Valid') { $s.SignerCertificate.GetNameInfo('SimpleName', $false) }";
+    let output = Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", script])
+        .env("RSBROWSERS_PATH", executable)
+        .output()
+        .ok()?;
+    let publisher = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!publisher.is_empty()).then_some(publisher)
+}
+
+#[cfg(target_os = "linux")]
+fn signer(_executable: &Path) -> Option<String> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Browser, InstallSource};
+    use std::{env, fs};
+
+    #[test]
+    fn test_integrity() {
+        let path = env::temp_dir().join(format!("rsbrowsers-integrity-{}", std::process::id()));
+        fs::write(&path, b"abc").unwrap();
+        let browser =
+            Browser { install_source: InstallSource::Custom, ..Browser::test_fixture("chrome", path.clone()) };
+
+        let integrity = browser.integrity().unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(integrity.sha256, "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        assert_eq!(integrity.signer, None);
+    }
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn test_team_identifier() {
+        use crate::integrity::team_identifier;
+
+        let google = "Identifier=com.google.Chrome\nAuthority=Developer ID Application: Google LLC (EQHXZ8M8AV)\nTeamIdentifier=EQHXZ8M8AV\n";
+        assert_eq!(team_identifier(google), Some("EQHXZ8M8AV".to_string()));
+        let apple = "Identifier=com.apple.Safari\nAuthority=Software Signing\nTeamIdentifier=not set\n";
+        assert_eq!(team_identifier(apple), Some("Software Signing".to_string()));
+    }
+}
@@ -0,0 +1,214 @@
+//! The inventory `BrowserFinder::with_disk_cache` keeps: the last scan with fingerprints of the places browsers
+//! are registered in and of each browser's files, so later runs can tell what changed since.
+
+use crate::Browser;
+#[cfg(target_os = "linux")]
+use crate::desktop_files::application_dirs;
+#[cfg(target_os = "macos")]
+use crate::homebrew;
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+use crate::tor;
+#[cfg(target_os = "linux")]
+use crate::wsl;
+#[cfg(target_os = "windows")]
+use crate::{backend::install_paths, package_managers};
+#[cfg(target_os = "macos")]
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+#[cfg(target_os = "windows")]
+use winreg::{
+    RegKey,
+    enums::{HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE},
+};
+
+/// When a registry key or file was last written to, `None` if it doesn't exist.
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, Eq, Debug)]
+pub(crate) struct Fingerprint {
+    /// A path, or a registry key such as `HKLM\Software\Clients\StartMenuInternet`.
+    location: String,
+    modified: Option<u64>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Entry {
+    browser: Browser,
+    /// The executable and the file the browser was found by, e.g. its desktop entry.
+    files: Vec<Fingerprint>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+pub(crate) struct Inventory {
+    /// The cache key of the finder that scanned, see `cache::key`.
+    key: String,
+    sources: Vec<Fingerprint>,
+    entries: Vec<Entry>,
+}
+
+impl Inventory {
+    pub(crate) fn new(key: &str, sources: Vec<Fingerprint>, browsers: &[Browser]) -> Self {
+        let entries = browsers
+            .iter()
+            .map(|browser| {
+                let files = [Some(browser.path.clone()), browser.source_path.clone()].into_iter().flatten();
+                Entry { browser: browser.clone(), files: files.map(|path| file_fingerprint(&path)).collect() }
+            })
+            .collect();
+        Inventory { key: key.to_owned(), sources, entries }
+    }
+
+    pub(crate) fn load(path: &Path) -> Option<Self> {
+        serde_json::from_slice(&fs::read(path).ok()?).ok()
+    }
+
+    /// Writes the inventory to `path`. Paths that aren't valid Unicode would be changed by JSON, so such
+    /// inventories are not written.
+    pub(crate) fn store(&self, path: &Path) {
+        let unicode = self.entries.iter().all(|Entry { browser, .. }| {
+            browser.path.to_str().is_some() && browser.source_path.as_ref().is_none_or(|path| path.to_str().is_some())
+        });
+        if !unicode {
+            return;
+        }
+        // an inventory that cannot be written only costs a rescan
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let _ = fs::write(path, serde_json::to_vec(self).unwrap());
+    }
+
+    /// Returns the browsers of the last scan with `key` if neither `sources` nor any of their files changed
+    /// since.
+    pub(crate) fn unchanged(&self, key: &str, sources: &[Fingerprint]) -> Option<Vec<Browser>> {
+        if self.key != key || self.sources != sources || !self.entries.iter().all(Entry::unchanged) {
+            return None;
+        }
+        Some(self.entries.iter().map(|entry| entry.browser.clone()).collect())
+    }
+
+    /// Returns the version the last scan found for `browser` if its files didn't change since, so it needn't
+    /// be probed again.
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    pub(crate) fn version(&self, browser: &Browser) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|Entry { browser: known, .. }| {
+                // e.g. every Flatpak runs `flatpak`, so the Exec line tells them apart
+                known.path == browser.path && known.exec == browser.exec && !known.version.is_empty()
+            })
+            .filter(|entry| entry.unchanged())
+            .map(|entry| entry.browser.version.as_str())
+    }
+}
+
+impl Entry {
+    fn unchanged(&self) -> bool {
+        self.files.iter().all(|file| file_fingerprint(Path::new(&file.location)) == *file)
+    }
+}
+
+fn file_fingerprint(path: &Path) -> Fingerprint {
+    let modified = fs::metadata(path).and_then(|metadata| metadata.modified()).ok();
+    Fingerprint {
+        location: path.to_string_lossy().into_owned(),
+        modified: modified.and_then(|time| time.duration_since(UNIX_EPOCH).ok()).map(|age| age.as_nanos() as u64),
+    }
+}
+
+/// Fingerprints the places the built-in backends and package managers find browsers in, which change when
+/// one is installed or removed.
+pub(crate) fn sources() -> Vec<Fingerprint> {
+    source_paths().iter().map(|path| file_fingerprint(path)).chain(registry_keys()).collect()
+}
+
+/// The Applications folders app bundles are usually in and the Homebrew `Caskroom`, which changes when a cask
+/// moves an app elsewhere.
+#[cfg(target_os = "macos")]
+fn source_paths() -> Vec<PathBuf> {
+    let home = env::var_os("HOME").map(|home| Path::new(&home).join("Applications"));
+    [Some(PathBuf::from("/Applications")), Some(PathBuf::from("/System/Applications")), home]
+        .into_iter()
+        .flatten()
+        .chain(homebrew::caskrooms())
+        .collect()
+}
+
+/// The executables `AppPathsBackend` looks for in the install directories, Tor Browser and the directories of
+/// the package managers.
+#[cfg(target_os = "windows")]
+fn source_paths() -> Vec<PathBuf> {
+    [install_paths(), tor::files(), package_managers::roots()].concat()
+}
+
+/// The directories of desktop entries, Tor Browser and, on WSL, the Windows install locations.
+#[cfg(target_os = "linux")]
+fn source_paths() -> Vec<PathBuf> {
+    let wsl = if wsl::is_wsl() { wsl::files() } else { vec![] };
+    [application_dirs(), tor::files(), wsl].concat()
+}
+
+#[cfg(not(target_os = "windows"))]
+fn registry_keys() -> Vec<Fingerprint> {
+    vec![]
+}
+
+/// Fingerprints the keys `RegistryBackend`, `AppPathsBackend` and `MsStoreBackend` enumerate, whose last
+/// write time changes when a subkey is added or removed.
+#[cfg(target_os = "windows")]
+fn registry_keys() -> Vec<Fingerprint> {
+    let keys = [
+        (HKEY_LOCAL_MACHINE, "HKLM", r"Software\Clients\StartMenuInternet"),
+        (HKEY_CURRENT_USER, "HKCU", r"Software\Clients\StartMenuInternet"),
+        (HKEY_LOCAL_MACHINE, "HKLM", r"Software\Microsoft\Windows\CurrentVersion\App Paths"),
+        (HKEY_CURRENT_USER, "HKCU", r"Software\Microsoft\Windows\CurrentVersion\App Paths"),
+        (HKEY_CURRENT_USER, "HKCU", crate::msstore::PACKAGES),
+    ];
+    keys.into_iter()
+        .map(|(root, name, path)| {
+            let info = RegKey::predef(root).open_subkey(path).and_then(|key| key.query_info()).ok();
+            let modified = info.map(|info| {
+                let time = &info.last_write_time;
+                (u64::from(time.dwHighDateTime) << 32) | u64::from(time.dwLowDateTime)
+            });
+            Fingerprint { location: format!(r"{name}\{path}"), modified }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Browser;
+    use crate::inventory::{Inventory, file_fingerprint};
+    use std::env;
+    use std::fs;
+    use std::slice;
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn test_inventory() {
+        let root = env::temp_dir().join(format!("rsbrowsers-inventory-{}", std::process::id()));
+        let executable = root.join("firefox");
+        fs::create_dir_all(&root).unwrap();
+        fs::write(&executable, "").unwrap();
+        let browser = Browser { version: "128.0".to_string(), ..Browser::test_fixture("firefox", executable.clone()) };
+        let sources = vec![file_fingerprint(&root)];
+
+        let path = root.join("cache/inventory.json");
+        Inventory::new("", sources.clone(), slice::from_ref(&browser)).store(&path);
+        let inventory = Inventory::load(&path).unwrap();
+        assert_eq!(inventory.unchanged("", &sources), Some(vec![browser.clone()]));
+        assert_eq!(inventory.version(&browser), Some("128.0"));
+        assert!(inventory.unchanged("", &[file_fingerprint(&root.join("missing"))]).is_none());
+        assert!(inventory.unchanged("browsers.toml", &sources).is_none());
+
+        // an update rewrites the executable
+        let file = fs::File::options().write(true).open(&executable).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(60)).unwrap();
+        let (unchanged, version) = (inventory.unchanged("", &sources), inventory.version(&browser));
+        fs::remove_dir_all(&root).unwrap();
+
+        assert!(unchanged.is_none());
+        assert_eq!(version, None);
+    }
+}
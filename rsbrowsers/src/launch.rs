@@ -0,0 +1,1528 @@
+#[cfg(target_os = "linux")]
+use crate::exec;
+use crate::{Browser, Channel, Engine, LaunchError, ProxyConfig, devtools, extensions, process};
+use std::ffi::OsString;
+use std::io;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStderr, ChildStdout, Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use std::{env, fs};
+
+const DEVTOOLS_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Blink browsers that don't take Chromium's switches, see `is_chromium`.
+const CHROMIUM_SWITCHES_IGNORE_LIST: &[&str] = &["falkon", "konqueror", "qutebrowser", "webview2"];
+
+const CHILD_TAKEN: &str = "the child is only taken when the handle is dropped or leaked";
+
+/// Chromium flags for `LaunchOptions::kiosk`, the mode itself and the ones keeping first-run pages, error
+/// dialogs and infobars off the screen.
+const CHROMIUM_KIOSK_ARGS: &[&str] = &["--kiosk", "--no-first-run", "--noerrdialogs", "--disable-infobars"];
+
+/// Chromium flags for `LaunchOptions::automation_preset`, skipping first-run pages and prompts and turning
+/// off background traffic that makes runs flaky.
+const CHROMIUM_AUTOMATION_ARGS: &[&str] = &[
+    "--no-first-run",
+    "--no-default-browser-check",
+    "--disable-features=Translate",
+    "--disable-background-networking",
+    "--disable-component-update",
+    "--disable-sync",
+    "--metrics-recording-only",
+    "--password-store=basic",
+    "--use-mock-keychain",
+];
+
+/// Firefox preferences for `LaunchOptions::automation_preset`, as there are no flags for them.
+const FIREFOX_AUTOMATION_PREFS: &str = concat!(
+    "user_pref(\"browser.shell.checkDefaultBrowser\", false);\n",
+    "user_pref(\"browser.startup.homepage_override.mstone\", \"ignore\");\n",
+    "user_pref(\"datareporting.policy.dataSubmissionEnabled\", false);\n",
+    "user_pref(\"toolkit.telemetry.reportingpolicy.firstRun\", false);\n",
+    "user_pref(\"browser.translations.automaticallyPopup\", false);\n",
+);
+
+/// Options applied when launching a browser.
+#[derive(Clone, Debug, Default)]
+pub struct LaunchOptions {
+    urls: Vec<String>,
+    args: Vec<String>,
+    incognito: bool,
+    profile: Option<String>,
+    remote_debugging: Option<Option<u16>>,
+    marionette: Option<Option<u16>>,
+    ephemeral_profile: bool,
+    allowed_schemes: Vec<String>,
+    reuse_existing: bool,
+    new_window: bool,
+    window_per_url: bool,
+    app: Option<String>,
+    detached: bool,
+    kill_on_drop: bool,
+    envs: Vec<(OsString, OsString)>,
+    env_clear: bool,
+    current_dir: Option<PathBuf>,
+    stdout: StdioMode,
+    stderr: StdioMode,
+    proxy: Option<ProxyConfig>,
+    extensions: Vec<PathBuf>,
+    firefox_prefs: Vec<(String, FirefoxPref)>,
+    window_size: Option<(u32, u32)>,
+    window_position: Option<(i32, i32)>,
+    maximized: bool,
+    fullscreen: bool,
+    kiosk: bool,
+    automation_preset: bool,
+    headless: Option<HeadlessMode>,
+}
+
+/// Which headless mode Chromium runs in. Firefox has only one.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum HeadlessMode {
+    /// The full browser without a window, `--headless=new`.
+    New,
+    /// The separate, lighter implementation, `--headless`. Chrome 132 and later moved it to
+    /// `chrome-headless-shell`, which is always headless.
+    Old,
+}
+
+/// The value of a Firefox preference, see `LaunchOptions::firefox_pref`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum FirefoxPref {
+    Bool(bool),
+    Int(i32),
+    String(String),
+}
+
+impl FirefoxPref {
+    /// Returns the value as written in `user.js`.
+    fn to_js(&self) -> String {
+        match self {
+            FirefoxPref::Bool(value) => value.to_string(),
+            FirefoxPref::Int(value) => value.to_string(),
+            FirefoxPref::String(value) => serde_json::Value::from(value.as_str()).to_string(),
+        }
+    }
+}
+
+impl From<bool> for FirefoxPref {
+    fn from(value: bool) -> Self {
+        FirefoxPref::Bool(value)
+    }
+}
+
+impl From<i32> for FirefoxPref {
+    fn from(value: i32) -> Self {
+        FirefoxPref::Int(value)
+    }
+}
+
+impl From<&str> for FirefoxPref {
+    fn from(value: &str) -> Self {
+        FirefoxPref::String(value.to_owned())
+    }
+}
+
+impl From<String> for FirefoxPref {
+    fn from(value: String) -> Self {
+        FirefoxPref::String(value)
+    }
+}
+
+/// Where the output of a launched browser goes.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum StdioMode {
+    /// Shares this process's stream.
+    #[default]
+    Inherit,
+    /// Discards the output.
+    Null,
+    /// Captures the output, see `LaunchedBrowser::take_stdout` and `LaunchedBrowser::take_stderr`.
+    Piped,
+}
+
+impl StdioMode {
+    fn stdio(self) -> Stdio {
+        match self {
+            StdioMode::Inherit => Stdio::inherit(),
+            StdioMode::Null => Stdio::null(),
+            StdioMode::Piped => Stdio::piped(),
+        }
+    }
+}
+
+/// A browser process started by a launch.
+///
+/// A temporary profile created by `LaunchOptions::ephemeral_profile` is deleted once the process exited and
+/// was waited for. Dropping the handle leaves the browser running, unless launched with
+/// `LaunchOptions::kill_on_drop`, and reaps it on a thread once it exits so no zombie is left behind, deleting
+/// the profile then.
+#[derive(Debug)]
+pub struct LaunchedBrowser {
+    /// Only `None` once the handle is dropped or leaked.
+    child: Option<Child>,
+    devtools_url: Option<String>,
+    bidi_url: Option<String>,
+    marionette_address: Option<SocketAddr>,
+    profile_dir: Option<PathBuf>,
+    reused: bool,
+    /// The application `close` tells to quit with AppleScript, for Safari.
+    quit_application: Option<String>,
+    kill_on_drop: bool,
+}
+
+impl LaunchOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Opens the URL after launching. Can be called multiple times.
+    pub fn url(mut self, url: String) -> Self {
+        self.urls.push(url);
+        self
+    }
+
+    /// Opens the URLs after launching, in order. Can be combined with `url`.
+    pub fn urls<I: IntoIterator<Item = S>, S: Into<String>>(mut self, urls: I) -> Self {
+        self.urls.extend(urls.into_iter().map(Into::into));
+        self
+    }
+
+    /// Passes extra command-line arguments to the browser as-is.
+    pub fn args(mut self, args: Vec<String>) -> Self {
+        self.args.extend(args);
+        self
+    }
+
+    /// Opens a private window, e.g. `--incognito` for Chrome or `-private-window` for Firefox.
+    ///
+    /// Ignored for browsers without a known private browsing flag.
+    pub fn incognito(mut self) -> Self {
+        self.incognito = true;
+        self
+    }
+
+    /// Uses the named profile, e.g. `--profile-directory` for Chromium or `-P` for Firefox.
+    pub fn profile(mut self, profile: String) -> Self {
+        self.profile = Some(profile);
+        self
+    }
+
+    /// Enables the Chrome DevTools Protocol on `port`, or on a free port if `None`. Firefox serves WebDriver
+    /// BiDi instead, from the same `--remote-debugging-port` flag.
+    ///
+    /// Launching waits until the endpoint is up and exposes it as `LaunchedBrowser::devtools_url`, or
+    /// `LaunchedBrowser::bidi_url` for Firefox. Only supported by Chromium-based browsers and Firefox.
+    pub fn remote_debugging(mut self, port: Option<u16>) -> Self {
+        self.remote_debugging = Some(port);
+        self
+    }
+
+    /// Enables Marionette, Firefox's own remote protocol used by geckodriver, on `port`, or on a free port if
+    /// `None`. The port is set with the `marionette.port` preference, so Firefox gets a generated profile.
+    ///
+    /// Launching waits until the port accepts connections and exposes it as
+    /// `LaunchedBrowser::marionette_address`. Only supported by Firefox.
+    pub fn marionette(mut self, port: Option<u16>) -> Self {
+        self.marionette = Some(port);
+        self
+    }
+
+    /// Launches with a new, empty profile in a temporary directory instead of the user's profile.
+    ///
+    /// Uses `--user-data-dir` for Chromium and `-profile` for Firefox, and takes precedence over `profile`
+    /// for Firefox. The directory is deleted once the browser exited and its `LaunchedBrowser` reaped it.
+    pub fn ephemeral_profile(mut self) -> Self {
+        self.ephemeral_profile = true;
+        self
+    }
+
+    /// Opens the URLs in a running instance of the browser, as new tabs, instead of starting another one.
+    /// Falls back to a fresh launch if the browser is not running.
+    ///
+    /// Ignored with `remote_debugging`, `marionette` and `ephemeral_profile`, which need a process of their own.
+    pub fn reuse_existing(mut self, reuse: bool) -> Self {
+        self.reuse_existing = reuse;
+        self
+    }
+
+    /// Opens the URLs in a new window instead of new tabs, e.g. `--new-window` for Chromium. Safari is
+    /// scripted with AppleScript for this.
+    pub fn new_window(mut self) -> Self {
+        self.new_window = true;
+        self
+    }
+
+    /// Opens each URL in a window of its own, e.g. `-new-window` before every URL for Firefox. Chromium has
+    /// no such flag, so the first URL opens with the launch and every other one with a further
+    /// `--new-window` invocation, which the running browser picks up. Safari is scripted with AppleScript.
+    ///
+    /// Takes precedence over `new_window`. Ignored with `incognito`.
+    pub fn window_per_url(mut self) -> Self {
+        self.window_per_url = true;
+        self
+    }
+
+    /// Opens `url` as an app, in a window without tabs or toolbars, e.g. `--app` for Chromium, for
+    /// presenting a web UI like a desktop application.
+    ///
+    /// Other browsers open the URL in a plain window, before any URLs from `url`. Combine with `fullscreen`
+    /// for Firefox's kiosk mode.
+    pub fn app(mut self, url: String) -> Self {
+        self.app = Some(url);
+        self
+    }
+
+    /// Runs the browser without a window, e.g. `--headless=new` for Chromium or `-headless` for Firefox.
+    ///
+    /// Launching fails for browsers that cannot, see `Browser::supports_headless`. Ignores `reuse_existing`.
+    pub fn headless(mut self, mode: HeadlessMode) -> Self {
+        self.headless = Some(mode);
+        self
+    }
+
+    /// Opens the window with this size in pixels, e.g. `--window-size` for Chromium or `-width` and
+    /// `-height` for Firefox.
+    ///
+    /// Like the other window options, this is ignored for browsers without such a flag.
+    pub fn window_size(mut self, width: u32, height: u32) -> Self {
+        self.window_size = Some((width, height));
+        self
+    }
+
+    /// Opens the window at this position on the screen. Only Chromium has a flag for it.
+    pub fn window_position(mut self, x: i32, y: i32) -> Self {
+        self.window_position = Some((x, y));
+        self
+    }
+
+    /// Opens the window maximized. Only Chromium has a flag for it.
+    pub fn maximized(mut self) -> Self {
+        self.maximized = true;
+        self
+    }
+
+    /// Opens the window in full screen, e.g. `--start-fullscreen` for Chromium. Firefox uses `-kiosk`,
+    /// which also hides its toolbars and menus.
+    pub fn fullscreen(mut self) -> Self {
+        self.fullscreen = true;
+        self
+    }
+
+    /// Locks the browser into full screen without toolbars for unattended displays such as signage, e.g.
+    /// `--kiosk` for Chromium together with `--no-first-run`, `--noerrdialogs` and `--disable-infobars` so
+    /// no prompts cover the page, or `-kiosk` for Firefox. Safari is switched to full screen with
+    /// AppleScript, which needs the Accessibility permission.
+    ///
+    /// Launching fails for other browsers. Ignores `reuse_existing`.
+    pub fn kiosk(mut self, kiosk: bool) -> Self {
+        self.kiosk = kiosk;
+        self
+    }
+
+    /// Applies the flags automation usually needs, so that no first-run page, default browser prompt or
+    /// translation bar gets in the way, e.g. `--no-first-run`, `--no-default-browser-check` and
+    /// `--disable-features=Translate` for Chromium, or `-no-remote` for Firefox.
+    ///
+    /// The matching Firefox preferences are written into generated profiles, see `ephemeral_profile`.
+    /// Other browsers are launched as usual.
+    pub fn automation_preset(mut self) -> Self {
+        self.automation_preset = true;
+        self
+    }
+
+    /// Kills the browser when its `LaunchedBrowser` is dropped, e.g. so a service doesn't leave browsers
+    /// behind on an error path. See `LaunchedBrowser::leak` to keep one running anyway.
+    pub fn kill_on_drop(mut self, kill: bool) -> Self {
+        self.kill_on_drop = kill;
+        self
+    }
+
+    /// Returns once Safari was asked to open instead of keeping a handle that lives as long as Safari.
+    ///
+    /// Only affects Safari, which is started through `open` and otherwise waits for the app to quit.
+    pub fn detached(mut self) -> Self {
+        self.detached = true;
+        self
+    }
+
+    /// Routes the browser's traffic through `proxy`, e.g. `--proxy-server` for Chromium.
+    ///
+    /// Firefox has no proxy flag, so it is launched with a generated profile as with `ephemeral_profile`.
+    /// Other browsers, such as Safari which only follows the system settings, are not supported. Ignores
+    /// `reuse_existing`, since a running browser keeps its proxy.
+    pub fn proxy(mut self, proxy: ProxyConfig) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Loads the extension at `path`, an unpacked extension directory or, for Firefox, an `.xpi` package
+    /// named after its add-on ID. Can be called multiple times.
+    ///
+    /// Chromium gets `--load-extension` and `--disable-extensions-except`, though branded Google Chrome
+    /// ignores them since version 137. Firefox is launched with a generated profile holding the extensions,
+    /// which needs an add-on ID in `manifest.json` and, for unsigned ones, a build that allows them. Other
+    /// browsers are not supported. Ignores `reuse_existing`.
+    pub fn load_extension<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.extensions.push(path.into());
+        self
+    }
+
+    /// Sets a Firefox preference in the launched profile's `user.js`, for behavior that has no flag, e.g.
+    /// `browser.download.dir` together with `browser.download.folderList` set to `2`, or
+    /// `security.enterprise_roots.enabled` to trust the certificates installed in the system. Can be
+    /// called multiple times, and overrides the preferences set for `proxy` and `load_extension`.
+    ///
+    /// Firefox is launched with a generated profile as with `ephemeral_profile`. Other browsers ignore
+    /// these preferences. Ignores `reuse_existing`.
+    pub fn firefox_pref<V: Into<FirefoxPref>>(mut self, name: &str, value: V) -> Self {
+        self.firefox_prefs.push((name.to_owned(), value.into()));
+        self
+    }
+
+    /// Sets an environment variable for the browser process, e.g. `MOZ_HEADLESS` or `HTTPS_PROXY`. Can be
+    /// called multiple times.
+    ///
+    /// Safari is started through `open`, which doesn't pass the environment on.
+    pub fn env<K: Into<OsString>, V: Into<OsString>>(mut self, key: K, value: V) -> Self {
+        self.envs.push((key.into(), value.into()));
+        self
+    }
+
+    /// Starts the browser without inheriting this process's environment, keeping only the variables set
+    /// with `env` after this call. Executables are then not looked up on `PATH` unless it is set again.
+    pub fn env_clear(mut self) -> Self {
+        self.env_clear = true;
+        self.envs.clear();
+        self
+    }
+
+    /// Starts the browser in this working directory instead of the current one.
+    pub fn current_dir<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.current_dir = Some(path.into());
+        self
+    }
+
+    /// Sets where the browser's standard output goes, e.g. `StdioMode::Piped` to read Firefox's console
+    /// output. Inherited by default.
+    pub fn stdout(mut self, mode: StdioMode) -> Self {
+        self.stdout = mode;
+        self
+    }
+
+    /// Sets where the browser's standard error goes, e.g. `StdioMode::Piped` to read Chromium's
+    /// `DevTools listening on ws://...` line. Inherited by default.
+    pub fn stderr(mut self, mode: StdioMode) -> Self {
+        self.stderr = mode;
+        self
+    }
+
+    /// Allows `BrowserFinder::launch_url` to open URLs with `scheme`, e.g. `file`, besides `http`, `https`
+    /// and `about`. Can be called multiple times.
+    pub fn allow_scheme(mut self, scheme: &str) -> Self {
+        self.allowed_schemes.push(scheme.to_owned());
+        self
+    }
+
+    pub(crate) fn allowed_schemes(&self) -> &[String] {
+        &self.allowed_schemes
+    }
+
+    /// Returns the command-line arguments for launching `browser` with these options, except the URLs
+    /// which go wherever the platform's launcher expects them.
+    pub(crate) fn arguments(&self, browser: &Browser) -> Vec<String> {
+        let browser_type = browser.browser_type.as_str();
+        let mut arguments = vec![];
+
+        if let Some(flag) = private_flag(browser_type).filter(|_| self.incognito) {
+            arguments.push(flag.to_owned());
+        }
+
+        if let Some(profile) = &self.profile {
+            if is_chromium(browser_type) {
+                arguments.push(format!("--profile-directory={profile}"));
+            } else if is_gecko(browser_type) && !self.generated_profile(browser_type) {
+                arguments.extend(["-P".to_owned(), profile.to_owned()]);
+            }
+        }
+
+        if (self.new_window || self.window_per_url) && is_chromium(browser_type) {
+            arguments.push("--new-window".to_owned());
+        }
+
+        if let Some(url) = self.app.as_ref().filter(|_| is_chromium(browser_type)) {
+            arguments.push(format!("--app={url}"));
+        }
+
+        match self.headless {
+            Some(HeadlessMode::New) if is_chromium(browser_type) => arguments.push("--headless=new".to_owned()),
+            Some(HeadlessMode::Old) if is_chromium(browser_type) => arguments.push("--headless".to_owned()),
+            Some(_) if is_gecko(browser_type) => arguments.push("-headless".to_owned()),
+            _ => {}
+        }
+
+        arguments.extend(self.window_arguments(browser_type));
+
+        if let Some(proxy) = self.proxy.as_ref().filter(|_| is_chromium(browser_type)) {
+            arguments.extend(proxy.chromium_args());
+        }
+
+        if !self.extensions.is_empty() && is_chromium(browser_type) {
+            arguments.extend(extensions::chromium_args(&self.extensions));
+        }
+
+        if self.automation_preset {
+            if is_chromium(browser_type) {
+                for flag in CHROMIUM_AUTOMATION_ARGS {
+                    // e.g. `--no-first-run` may have come with `kiosk`
+                    if !arguments.iter().any(|argument| argument == flag) {
+                        arguments.push(flag.to_string());
+                    }
+                }
+            } else if is_gecko(browser_type) && !self.generated_profile(browser_type) {
+                // launching with a generated profile passes it already
+                arguments.push("-no-remote".to_owned());
+            }
+        }
+
+        arguments.extend_from_slice(&self.args);
+        arguments
+    }
+
+    fn window_arguments(&self, browser_type: &str) -> Vec<String> {
+        let mut arguments = vec![];
+        if is_chromium(browser_type) {
+            if let Some((width, height)) = self.window_size {
+                arguments.push(format!("--window-size={width},{height}"));
+            }
+            if let Some((x, y)) = self.window_position {
+                arguments.push(format!("--window-position={x},{y}"));
+            }
+            if self.maximized {
+                arguments.push("--start-maximized".to_owned());
+            }
+            if self.fullscreen {
+                arguments.push("--start-fullscreen".to_owned());
+            }
+            if self.kiosk {
+                arguments.extend(CHROMIUM_KIOSK_ARGS.iter().map(|argument| argument.to_string()));
+            }
+        } else if is_gecko(browser_type) {
+            if let Some((width, height)) = self.window_size {
+                arguments.extend(["-width".to_owned(), width.to_string(), "-height".to_owned(), height.to_string()]);
+            }
+            if self.fullscreen || self.kiosk {
+                arguments.push("-kiosk".to_owned());
+            }
+        }
+        arguments
+    }
+
+    /// Returns the URLs to open, including the `app` URL for browsers without an app mode.
+    fn launch_urls(&self, browser_type: &str) -> Vec<String> {
+        match self.app.as_ref().filter(|_| !is_chromium(browser_type)) {
+            Some(url) => [std::slice::from_ref(url), &self.urls].concat(),
+            None => self.urls.clone(),
+        }
+    }
+
+    /// Returns whether `browser_type` is launched with a profile in a temporary directory.
+    fn generated_profile(&self, browser_type: &str) -> bool {
+        self.ephemeral_profile || (self.needs_own_profile() && is_gecko(browser_type))
+    }
+
+    /// Returns whether Firefox needs a profile of its own for the proxy, extensions, preferences or Marionette.
+    fn needs_own_profile(&self) -> bool {
+        self.proxy.is_some()
+            || !self.extensions.is_empty()
+            || !self.firefox_prefs.is_empty()
+            || self.marionette.is_some()
+    }
+
+    /// Returns the files to put in a generated Firefox profile, relative to it.
+    fn firefox_profile_files(&self, marionette_port: Option<u16>) -> Result<Vec<(PathBuf, Vec<u8>)>, LaunchError> {
+        let mut files = extensions::firefox_files(&self.extensions)?;
+        let mut prefs = String::new();
+        if let Some(proxy) = &self.proxy {
+            prefs.push_str(&proxy.firefox_prefs()?);
+        }
+        if !self.extensions.is_empty() {
+            prefs.push_str(extensions::FIREFOX_PREFS);
+        }
+        if self.automation_preset {
+            prefs.push_str(FIREFOX_AUTOMATION_PREFS);
+        }
+        // Firefox applies the last value, so these win over the ones above
+        for (name, value) in &self.firefox_prefs {
+            prefs.push_str(&format!("user_pref({}, {});\n", FirefoxPref::from(name.as_str()).to_js(), value.to_js()));
+        }
+        // launching waits on this port, so it can't be overridden
+        if let Some(port) = marionette_port {
+            prefs.push_str(&format!("user_pref(\"marionette.port\", {port});\n"));
+        }
+        if !prefs.is_empty() {
+            files.push((PathBuf::from("user.js"), prefs.into_bytes()));
+        }
+        Ok(files)
+    }
+
+    /// Applies the environment, working directory and output streams to the browser process.
+    fn configure(&self, command: &mut Command) {
+        command.stdout(self.stdout.stdio()).stderr(self.stderr.stdio());
+        if self.env_clear {
+            command.env_clear();
+        }
+        command.envs(self.envs.iter().map(|(key, value)| (key, value)));
+        if let Some(dir) = &self.current_dir {
+            command.current_dir(dir);
+        }
+    }
+}
+
+impl LaunchedBrowser {
+    #[cfg_attr(not(target_os = "macos"), allow(unused_variables))]
+    fn new(
+        child: Child,
+        browser: &Browser,
+        options: &LaunchOptions,
+        profile_dir: Option<PathBuf>,
+        reused: bool,
+    ) -> Self {
+        #[cfg(target_os = "macos")]
+        let quit_application = (browser.browser_type == "safari").then(|| safari_application(browser));
+        #[cfg(not(target_os = "macos"))]
+        let quit_application = None;
+
+        LaunchedBrowser {
+            child: Some(child),
+            devtools_url: None,
+            bidi_url: None,
+            marionette_address: None,
+            profile_dir,
+            reused,
+            quit_application,
+            kill_on_drop: options.kill_on_drop,
+        }
+    }
+
+    /// Returns the OS-assigned process identifier.
+    pub fn id(&self) -> u32 {
+        self.child.as_ref().expect(CHILD_TAKEN).id()
+    }
+
+    /// Returns the underlying child process.
+    pub fn child(&mut self) -> &mut Child {
+        self.child.as_mut().expect(CHILD_TAKEN)
+    }
+
+    pub fn wait(&mut self) -> io::Result<ExitStatus> {
+        let status = self.child().wait()?;
+        self.remove_profile();
+        Ok(status)
+    }
+
+    pub fn kill(&mut self) -> io::Result<()> {
+        self.child().kill()
+    }
+
+    /// Gives up the handle without killing or reaping the browser, returning its process, e.g. to leave it
+    /// running after this process exits. Overrides `LaunchOptions::kill_on_drop`.
+    ///
+    /// A temporary profile is kept, since the browser still uses it, and has to be removed by the caller.
+    pub fn leak(mut self) -> Child {
+        self.profile_dir = None;
+        self.child.take().expect(CHILD_TAKEN)
+    }
+
+    /// Asks the browser to quit and waits up to `timeout` for it to exit, killing it after that. Unlike
+    /// `kill`, this lets the browser save its profile, which may be left corrupted otherwise.
+    ///
+    /// Sends `SIGTERM` on Unix, closes the windows with `taskkill`, which posts `WM_CLOSE`, on Windows and
+    /// tells Safari to quit with AppleScript. Removes a temporary profile like `wait`.
+    pub fn close(&mut self, timeout: Duration) -> io::Result<ExitStatus> {
+        if self.child().try_wait()?.is_none() {
+            // a browser that has just exited can't be signalled, which waiting picks up
+            let _ = self.request_quit();
+            if let Some(status) = self.wait_timeout(timeout)? {
+                return Ok(status);
+            }
+            let _ = self.child().kill();
+        }
+        self.wait()
+    }
+
+    /// Waits up to `timeout` for the browser to exit, returning `None` if it is still running. Removes a
+    /// temporary profile once the browser exited, like `wait`.
+    pub fn wait_timeout(&mut self, timeout: Duration) -> io::Result<Option<ExitStatus>> {
+        let started = Instant::now();
+        while self.child().try_wait()?.is_none() {
+            if started.elapsed() >= timeout {
+                return Ok(None);
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+        self.wait().map(Some)
+    }
+
+    fn request_quit(&self) -> io::Result<ExitStatus> {
+        let mut command = match &self.quit_application {
+            Some(application) => {
+                let mut command = Command::new("osascript");
+                command.args(["-e", &format!("tell application \"{application}\" to quit")]);
+                command
+            }
+            #[cfg(unix)]
+            None => {
+                let mut command = Command::new("kill");
+                command.args(["-TERM", &self.id().to_string()]);
+                command
+            }
+            #[cfg(windows)]
+            None => {
+                let mut command = Command::new("taskkill");
+                command.args(["/PID", &self.id().to_string()]);
+                command
+            }
+        };
+        command.stdout(Stdio::null()).stderr(Stdio::null()).status()
+    }
+
+    /// Takes the reader of the browser's standard output when launched with `LaunchOptions::stdout` set to
+    /// `StdioMode::Piped`. Returns `None` otherwise or once taken.
+    ///
+    /// The output should be read until the end, since the browser blocks once the pipe is full.
+    pub fn take_stdout(&mut self) -> Option<ChildStdout> {
+        self.child().stdout.take()
+    }
+
+    /// Takes the reader of the browser's standard error, see `take_stdout`.
+    pub fn take_stderr(&mut self) -> Option<ChildStderr> {
+        self.child().stderr.take()
+    }
+
+    /// Returns the `ws://` DevTools endpoint when launched with `remote_debugging`.
+    pub fn devtools_url(&self) -> Option<&str> {
+        self.devtools_url.as_deref()
+    }
+
+    /// Returns a client for the DevTools endpoint when launched with `remote_debugging`, e.g. to check that
+    /// the browser is responsive.
+    #[cfg(feature = "cdp")]
+    pub fn cdp(&self) -> Option<crate::cdp::Client> {
+        let port = url::Url::parse(self.devtools_url.as_deref()?).ok()?.port()?;
+        Some(crate::cdp::Client::new(port))
+    }
+
+    /// Returns the `ws://` WebDriver BiDi endpoint, e.g. `ws://127.0.0.1:9222/session`, when Firefox was
+    /// launched with `remote_debugging`.
+    pub fn bidi_url(&self) -> Option<&str> {
+        self.bidi_url.as_deref()
+    }
+
+    /// Returns the address Marionette listens on when Firefox was launched with `marionette`.
+    pub fn marionette_address(&self) -> Option<SocketAddr> {
+        self.marionette_address
+    }
+
+    /// Returns whether the URLs were handed to an already running instance, see
+    /// `LaunchOptions::reuse_existing`. The child is then the short-lived process that forwarded them.
+    pub fn reused_existing(&self) -> bool {
+        self.reused
+    }
+
+    /// Returns the temporary profile directory when launched with `ephemeral_profile`, or Firefox with `proxy`.
+    pub fn profile_dir(&self) -> Option<&Path> {
+        self.profile_dir.as_deref()
+    }
+
+    fn remove_profile(&mut self) {
+        if let Some(dir) = self.profile_dir.take() {
+            let _ = fs::remove_dir_all(dir);
+        }
+    }
+}
+
+impl Drop for LaunchedBrowser {
+    fn drop(&mut self) {
+        // the browser uses a temporary profile until it exits, so it is removed once reaped
+        let profile_dir = self.profile_dir.take();
+        let remove_profile = move || {
+            if let Some(dir) = profile_dir {
+                let _ = fs::remove_dir_all(dir);
+            }
+        };
+        // a leaked browser is the caller's to reap
+        let Some(mut child) = self.child.take() else { return };
+        if self.kill_on_drop {
+            let _ = child.kill();
+            let _ = child.wait();
+            remove_profile();
+        } else if matches!(child.try_wait(), Ok(Some(_))) {
+            remove_profile();
+        } else {
+            thread::spawn(move || {
+                let _ = child.wait();
+                remove_profile();
+            });
+        }
+    }
+}
+
+impl Browser {
+    /// Launches this browser, e.g. one picked from `BrowserFinder::all`, without scanning again, so it is this
+    /// install even where a `BrowserFinder` with the same type filter would pick another.
+    pub fn launch(&self, options: &LaunchOptions) -> Result<LaunchedBrowser, LaunchError> {
+        spawn(self.clone(), options).map(|(launched, _)| launched)
+    }
+}
+
+/// Starts `browser` with `options`.
+pub(crate) fn spawn(browser: Browser, options: &LaunchOptions) -> Result<(LaunchedBrowser, Browser), LaunchError> {
+    #[cfg(feature = "tracing")]
+    let _span =
+        tracing::info_span!("launch", browser_type = %browser.browser_type, path = %browser.path.display()).entered();
+    let browser_type = browser.browser_type.as_str();
+    let port = match options.remote_debugging {
+        Some(_) if !is_chromium(browser_type) && !is_gecko(browser_type) => {
+            return Err(LaunchError::Unsupported(format!("{browser_type} does not support remote debugging")));
+        }
+        Some(Some(port)) => Some(port),
+        Some(None) => Some(devtools::free_port().map_err(LaunchError::SpawnFailed)?),
+        None => None,
+    };
+    let marionette_port = match options.marionette {
+        Some(_) if !is_gecko(browser_type) => {
+            return Err(LaunchError::Unsupported(format!("{browser_type} does not support Marionette")));
+        }
+        Some(Some(port)) => Some(port),
+        Some(None) => Some(devtools::free_port().map_err(LaunchError::SpawnFailed)?),
+        None => None,
+    };
+
+    if options.ephemeral_profile && !is_chromium(browser_type) && !is_gecko(browser_type) {
+        return Err(LaunchError::Unsupported(format!("{browser_type} does not support a temporary profile")));
+    }
+    if options.proxy.is_some() && !is_chromium(browser_type) && !is_gecko(browser_type) {
+        return Err(LaunchError::Unsupported(format!("{browser_type} does not support a per-launch proxy")));
+    }
+    if options.headless.is_some() && !browser.supports_headless() {
+        return Err(LaunchError::Unsupported(format!("{browser_type} does not support headless mode")));
+    }
+    if options.kiosk && !is_chromium(browser_type) && !is_gecko(browser_type) && browser_type != "safari" {
+        return Err(LaunchError::Unsupported(format!("{browser_type} does not support kiosk mode")));
+    }
+    if !options.extensions.is_empty() && !is_chromium(browser_type) && !is_gecko(browser_type) {
+        return Err(LaunchError::Unsupported(format!("{browser_type} does not support loading extensions")));
+    }
+    let profile_files = match is_gecko(browser_type) {
+        true => options.firefox_profile_files(marionette_port)?,
+        false => vec![],
+    };
+
+    // the browser may have been uninstalled since it was scanned
+    if !browser.path.exists() {
+        return Err(LaunchError::ExecutableMissing(browser.path.clone()));
+    }
+
+    let reuse = options.reuse_existing
+        && port.is_none()
+        && !options.ephemeral_profile
+        && !options.needs_own_profile()
+        && options.headless.is_none()
+        && !options.kiosk
+        && process::is_running(&browser.path);
+    let launch_urls = options.launch_urls(browser_type);
+    let (urls, window_urls) = split_window_urls(browser_type, options, &launch_urls);
+    if reuse {
+        let args = options.arguments(&browser);
+        let child = command(&browser, args.clone(), &url_arguments(browser_type, options, urls, true), options, true)
+            .and_then(|mut command| command.spawn().map_err(|error| spawn_error(error, &browser.path)))?;
+        #[cfg(feature = "tracing")]
+        tracing::info!(pid = child.id(), ?args, "handed over to the running browser");
+        open_windows(&browser, &args, window_urls, options);
+        return Ok((LaunchedBrowser::new(child, &browser, options, None, true), browser));
+    }
+
+    let mut args = vec![];
+    if let Some(port) = port {
+        args.push(format!("--remote-debugging-port={port}"));
+    }
+    if marionette_port.is_some() {
+        args.push("-marionette".to_string());
+    }
+    let profile_dir = match options.generated_profile(browser_type) {
+        true => Some(temp_profile_dir(&profile_files).map_err(LaunchError::SpawnFailed)?),
+        false => None,
+    };
+    if let Some(dir) = &profile_dir {
+        if is_chromium(browser_type) {
+            args.push(format!("--user-data-dir={}", dir.display()));
+        } else {
+            args.extend(["-profile".to_string(), dir.to_string_lossy().to_string(), "-no-remote".to_string()]);
+        }
+    }
+    args.extend(options.arguments(&browser));
+
+    // from here on the handle owns the temporary profile and removes it on every exit path
+    let child = command(&browser, args.clone(), &url_arguments(browser_type, options, urls, false), options, false)
+        .and_then(|mut command| command.spawn().map_err(|error| spawn_error(error, &browser.path)));
+    let mut launched = match child {
+        Ok(child) => LaunchedBrowser::new(child, &browser, options, profile_dir, false),
+        Err(error) => {
+            if let Some(dir) = profile_dir {
+                let _ = fs::remove_dir_all(dir);
+            }
+            return Err(error);
+        }
+    };
+
+    #[cfg(feature = "tracing")]
+    tracing::info!(pid = launched.id(), ?args, profile = ?launched.profile_dir(), "browser started");
+
+    if let Err(error) = wait_for_endpoints(&mut launched, browser_type, port, marionette_port) {
+        let _ = launched.kill();
+        return Err(LaunchError::SpawnFailed(error));
+    }
+    open_windows(&browser, &args, window_urls, options);
+
+    Ok((launched, browser))
+}
+
+/// Waits until the remote protocols the browser was started with answer and records their endpoints.
+fn wait_for_endpoints(
+    launched: &mut LaunchedBrowser,
+    browser_type: &str,
+    port: Option<u16>,
+    marionette_port: Option<u16>,
+) -> io::Result<()> {
+    if let Some(port) = port {
+        if is_chromium(browser_type) {
+            launched.devtools_url = Some(devtools::wait_for_websocket_url(port, DEVTOOLS_TIMEOUT)?);
+        } else {
+            // Firefox has no discovery endpoint for WebDriver BiDi, sessions are made on a fixed path
+            devtools::wait_for_port(port, DEVTOOLS_TIMEOUT)?;
+            launched.bidi_url = Some(format!("ws://127.0.0.1:{port}/session"));
+        }
+    }
+    if let Some(port) = marionette_port {
+        devtools::wait_for_port(port, DEVTOOLS_TIMEOUT)?;
+        launched.marionette_address = Some((Ipv4Addr::LOCALHOST, port).into());
+    }
+    Ok(())
+}
+
+fn spawn_error(error: io::Error, executable: &Path) -> LaunchError {
+    match error.kind() {
+        io::ErrorKind::NotFound => LaunchError::ExecutableMissing(executable.to_path_buf()),
+        io::ErrorKind::PermissionDenied => LaunchError::PermissionDenied(executable.to_path_buf()),
+        _ => LaunchError::SpawnFailed(error),
+    }
+}
+
+/// Splits the URLs into the ones passed to the launch and the ones opened afterwards by `open_windows`,
+/// which is only needed for Chromium with `window_per_url`.
+fn split_window_urls<'a>(
+    browser_type: &str,
+    options: &LaunchOptions,
+    urls: &'a [String],
+) -> (&'a [String], &'a [String]) {
+    match options.window_per_url && is_chromium(browser_type) && !options.incognito && !urls.is_empty() {
+        true => urls.split_at(1),
+        false => (urls, &[]),
+    }
+}
+
+/// Returns the URLs with the flags placing them. Firefox gets `-new-window` before each URL with
+/// `window_per_url`, and when `reuse` is set, `-new-tab` or `-new-window` since a running instance decides
+/// by its settings otherwise. Chromium already has a flag for it, and `-private-window` takes the URL as
+/// its value.
+fn url_arguments(browser_type: &str, options: &LaunchOptions, urls: &[String], reuse: bool) -> Vec<String> {
+    if !is_gecko(browser_type) || options.incognito || !(reuse || options.window_per_url) {
+        return urls.to_vec();
+    }
+    let flag = if options.new_window || options.window_per_url { "-new-window" } else { "-new-tab" };
+    urls.iter().flat_map(|url| [flag.to_string(), url.to_owned()]).collect()
+}
+
+/// Opens each URL in a new window of the launched Chromium by running it again with the same profile,
+/// see `LaunchOptions::window_per_url`. The extra processes hand the URL over and exit.
+fn open_windows(browser: &Browser, args: &[String], urls: &[String], options: &LaunchOptions) {
+    for url in urls {
+        let spawned = command(browser, args.to_vec(), std::slice::from_ref(url), options, true)
+            .and_then(|mut command| command.spawn().map_err(LaunchError::SpawnFailed));
+        // reap it so no zombie is left behind
+        if let Ok(mut child) = spawned {
+            thread::spawn(move || child.wait());
+        }
+    }
+}
+
+/// Creates a directory under the system temp directory for a throwaway profile, holding `files` such as
+/// a Firefox `user.js`.
+fn temp_profile_dir(files: &[(PathBuf, Vec<u8>)]) -> io::Result<PathBuf> {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |duration| duration.subsec_nanos());
+    let name = format!("rsbrowsers-profile-{}-{nanos}-{}", std::process::id(), COUNTER.fetch_add(1, Ordering::Relaxed));
+    let dir = env::temp_dir().join(name);
+    fs::create_dir(&dir)?;
+    for (path, contents) in files {
+        let path = dir.join(path);
+        let written = fs::create_dir_all(path.parent().unwrap_or(&dir)).and_then(|_| fs::write(&path, contents));
+        if let Err(error) = written {
+            let _ = fs::remove_dir_all(&dir);
+            return Err(error);
+        }
+    }
+    Ok(dir)
+}
+
+/// Builds the command. `reuse` means the browser is running and should open the URLs itself.
+fn command(
+    browser: &Browser,
+    args: Vec<String>,
+    urls: &[String],
+    options: &LaunchOptions,
+    reuse: bool,
+) -> Result<Command, LaunchError> {
+    let mut command = command_line(browser, args, urls, options, reuse)?;
+    options.configure(&mut command);
+    Ok(command)
+}
+
+/// Builds the command line, see `command`.
+#[cfg_attr(not(target_os = "macos"), allow(unused_variables))]
+fn command_line(
+    browser: &Browser,
+    args: Vec<String>,
+    urls: &[String],
+    options: &LaunchOptions,
+    reuse: bool,
+) -> Result<Command, LaunchError> {
+    #[cfg(target_os = "macos")]
+    if browser.browser_type == "safari" {
+        return Ok(safari_command(browser, args, urls, options, reuse));
+    }
+
+    // desktop entry Exec lines are expanded and run without a shell
+    #[cfg(target_os = "linux")]
+    if let Some(exec) = &browser.exec {
+        let argv = exec::expand(exec, &args, urls)
+            .map_err(|message| LaunchError::SpawnFailed(io::Error::new(io::ErrorKind::InvalidInput, message)))?;
+        let mut command = Command::new(&argv[0]);
+        command.args(&argv[1..]);
+        return Ok(command);
+    }
+
+    let mut command = Command::new(&browser.path);
+    command.args(&browser.args).args(args).args(urls);
+    Ok(command)
+}
+
+/// Safari has no command-line flags, so private and new windows are scripted and everything else goes
+/// through `open`.
+#[cfg(target_os = "macos")]
+fn safari_command(
+    browser: &Browser,
+    args: Vec<String>,
+    urls: &[String],
+    options: &LaunchOptions,
+    reuse: bool,
+) -> Command {
+    if options.incognito || options.new_window || options.window_per_url || options.kiosk {
+        let application = safari_application(browser);
+        let mut lines = safari_script(&application, urls, options.incognito, options.window_per_url);
+        if options.kiosk {
+            // the Enter Full Screen shortcut, since the window's full screen state can't be scripted
+            lines
+                .push(r#"tell application "System Events" to keystroke "f" using {command down, control down}"#.into());
+        }
+        let mut command = Command::new("osascript");
+        for line in lines {
+            command.args(["-e", line.as_str()]);
+        }
+        return command;
+    }
+
+    let mut command = Command::new("open");
+    if !reuse {
+        if !options.detached {
+            command.arg("--wait-apps");
+        }
+        command.args(["--new", "--fresh"]);
+    }
+    command.arg("-a").arg(&browser.path).args(args).args(urls);
+    command
+}
+
+/// Returns the name Safari is scripted by, e.g. `Safari Technology Preview`.
+#[cfg(target_os = "macos")]
+fn safari_application(browser: &Browser) -> String {
+    Path::new(&browser.path).file_stem().map_or("Safari".to_string(), |stem| stem.to_string_lossy().to_string())
+}
+
+/// Returns AppleScript lines opening `urls` in a new Safari window, the first one in place of the window's
+/// empty tab, or with `separate` each one in a window of its own unless the window is private.
+#[cfg(target_os = "macos")]
+fn safari_script(application: &str, urls: &[String], private: bool, separate: bool) -> Vec<String> {
+    let quote = |text: &str| format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""));
+    let tell = |statement: String| format!("tell application {} to {statement}", quote(application));
+
+    let mut lines = vec![tell("activate".to_string())];
+    let mut urls = urls.iter();
+    if private {
+        // there is no scripting for private windows, so use the menu shortcut, which needs the
+        // Accessibility permission
+        lines.push(r#"tell application "System Events" to keystroke "n" using {command down, shift down}"#.to_string());
+        lines.extend(urls.next().map(|url| tell(format!("set URL of front document to {}", quote(url)))));
+    } else {
+        let properties = urls.next().map_or("".to_string(), |url| format!(" with properties {{URL:{}}}", quote(url)));
+        lines.push(tell(format!("make new document{properties}")));
+    }
+    for url in urls {
+        lines.push(match separate && !private {
+            true => tell(format!("make new document with properties {{URL:{}}}", quote(url))),
+            false => tell(format!(
+                "tell front window to set current tab to (make new tab with properties {{URL:{}}})",
+                quote(url)
+            )),
+        });
+    }
+    lines
+}
+
+pub(crate) fn private_flag(browser_type: &str) -> Option<&'static str> {
+    match browser_type {
+        "msie" => Some("-private"),
+        t if t.starts_with("msedge") => Some("--inprivate"),
+        t if t.starts_with("opera") => Some("--private"),
+        t if is_chromium(t) => Some("--incognito"),
+        t if is_gecko(t) => Some("-private-window"),
+        _ => None,
+    }
+}
+
+/// Whether the browser takes Chromium's switches. Qt WebEngine browsers such as Falkon are built on Blink but
+/// have options of their own, and WebView2 isn't started on its own.
+pub(crate) fn is_chromium(browser_type: &str) -> bool {
+    Engine::of(browser_type) == Engine::Blink
+        && !CHROMIUM_SWITCHES_IGNORE_LIST.contains(&Channel::split(browser_type).0)
+}
+
+/// Whether the browser takes Firefox's options.
+pub(crate) fn is_gecko(browser_type: &str) -> bool {
+    Engine::of(browser_type) == Engine::Gecko
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Browser, InstallSource, LaunchOptions};
+    use std::path::PathBuf;
+
+    fn browser(browser_type: &str) -> Browser {
+        Browser::test_fixture(browser_type, PathBuf::new())
+    }
+
+    #[test]
+    fn test_arguments() {
+        let options =
+            LaunchOptions::new().incognito().profile("Work".to_string()).url("https://example.com".to_string());
+
+        assert_eq!(options.arguments(&browser("chrome")), vec!["--incognito", "--profile-directory=Work"]);
+        assert_eq!(options.arguments(&browser("msedge-beta"))[0], "--inprivate");
+        assert_eq!(options.arguments(&browser("firefox")), vec!["-private-window", "-P", "Work"]);
+        assert!(options.arguments(&browser("safari")).is_empty());
+        // the engine decides, not the name
+        assert_eq!(options.arguments(&browser("arc"))[0], "--incognito");
+        assert_eq!(options.arguments(&browser("midori"))[0], "-private-window");
+        assert!(options.arguments(&browser("falkon")).is_empty());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_ephemeral_profile() {
+        use crate::launch::spawn;
+
+        // stands in for a browser that exits right away
+        let browser = Browser { path: "/bin/true".into(), install_source: InstallSource::Custom, ..browser("chrome") };
+        let (mut launched, _) = spawn(browser, &LaunchOptions::new().ephemeral_profile()).unwrap();
+
+        let dir = launched.profile_dir().unwrap().to_path_buf();
+        assert!(dir.is_dir());
+        launched.wait().unwrap();
+        assert!(!dir.exists());
+        assert!(launched.profile_dir().is_none());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_environment() {
+        use crate::launch::spawn;
+
+        // stands in for a browser that checks what it was started with
+        let browser = Browser { path: "/bin/sh".into(), install_source: InstallSource::Custom, ..browser("chrome") };
+        let script = r#"test "$MOZ_HEADLESS" = 1 && test -z "$HOME" && test "$(pwd)" = /"#;
+        let options = LaunchOptions::new()
+            .args(vec!["-c".to_string(), script.to_string()])
+            .env("HOME", "/home/me")
+            .env_clear()
+            .env("MOZ_HEADLESS", "1")
+            .current_dir("/");
+
+        let (mut launched, _) = spawn(browser, &options).unwrap();
+        assert!(launched.wait().unwrap().success());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_registered_args() {
+        use crate::launch::spawn;
+
+        // the registered arguments come before the ones of the launch
+        let browser = Browser {
+            path: "/bin/sh".into(),
+            args: vec!["-c".to_string(), r#"test "$0" = --flag && exit 3"#.to_string()],
+            install_source: InstallSource::Custom,
+            ..browser("sh")
+        };
+        let options = LaunchOptions::new().args(vec!["--flag".to_string()]);
+
+        let (mut launched, _) = spawn(browser, &options).unwrap();
+        assert_eq!(launched.wait().unwrap().code(), Some(3));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_piped_stdio() {
+        use crate::StdioMode;
+        use crate::launch::spawn;
+        use std::io::Read;
+
+        let browser = Browser { path: "/bin/sh".into(), install_source: InstallSource::Custom, ..browser("chrome") };
+        let script = "echo out; echo err >&2";
+        let options = LaunchOptions::new()
+            .args(vec!["-c".to_string(), script.to_string()])
+            .stdout(StdioMode::Piped)
+            .stderr(StdioMode::Piped);
+
+        let (mut launched, _) = spawn(browser, &options).unwrap();
+        let (mut stdout, mut stderr) = (String::new(), String::new());
+        launched.take_stdout().unwrap().read_to_string(&mut stdout).unwrap();
+        launched.take_stderr().unwrap().read_to_string(&mut stderr).unwrap();
+        launched.wait().unwrap();
+
+        assert_eq!((stdout.as_str(), stderr.as_str()), ("out\n", "err\n"));
+        assert!(launched.take_stdout().is_none());
+    }
+
+    #[test]
+    fn test_headless() {
+        use crate::{HeadlessMode, LaunchError, launch::spawn};
+
+        let options = LaunchOptions::new().headless(HeadlessMode::New);
+        assert_eq!(options.arguments(&browser("chrome")), ["--headless=new"]);
+        assert_eq!(options.clone().headless(HeadlessMode::Old).arguments(&browser("msedge")), ["--headless"]);
+        assert_eq!(options.arguments(&browser("firefox")), ["-headless"]);
+
+        let safari = Browser { path: "/bin/sh".into(), ..browser("safari") };
+        assert!(matches!(spawn(safari, &options), Err(LaunchError::Unsupported(_))));
+    }
+
+    #[test]
+    fn test_window_arguments() {
+        let options = LaunchOptions::new().window_size(1280, 720).window_position(0, -10).fullscreen();
+
+        assert_eq!(
+            options.arguments(&browser("chromium")),
+            ["--window-size=1280,720", "--window-position=0,-10", "--start-fullscreen"]
+        );
+        assert_eq!(options.arguments(&browser("firefox")), ["-width", "1280", "-height", "720", "-kiosk"]);
+        assert!(options.arguments(&browser("safari")).is_empty());
+    }
+
+    #[test]
+    fn test_automation_preset() {
+        use std::path::PathBuf;
+
+        let options = LaunchOptions::new().automation_preset().kiosk(true);
+        let arguments = options.arguments(&browser("chrome"));
+        assert_eq!(arguments.iter().filter(|argument| *argument == "--no-first-run").count(), 1);
+        assert!(arguments.contains(&"--disable-features=Translate".to_string()));
+        assert_eq!(options.arguments(&browser("firefox")), ["-kiosk", "-no-remote"]);
+
+        let options = options.ephemeral_profile();
+        assert_eq!(options.arguments(&browser("firefox")), ["-kiosk"]);
+        let files = options.firefox_profile_files(None).unwrap();
+        assert_eq!(files[0].0, PathBuf::from("user.js"));
+        assert!(
+            String::from_utf8_lossy(&files[0].1).contains("user_pref(\"browser.shell.checkDefaultBrowser\", false);")
+        );
+    }
+
+    #[test]
+    fn test_kiosk() {
+        use crate::LaunchError;
+        use crate::launch::spawn;
+
+        let options = LaunchOptions::new().kiosk(true).fullscreen();
+        assert_eq!(
+            options.arguments(&browser("chrome")),
+            ["--start-fullscreen", "--kiosk", "--no-first-run", "--noerrdialogs", "--disable-infobars"]
+        );
+        assert_eq!(options.arguments(&browser("firefox")), ["-kiosk"]);
+        assert!(LaunchOptions::new().kiosk(false).arguments(&browser("chrome")).is_empty());
+
+        let ladybird = Browser { path: "/bin/sh".into(), ..browser("ladybird") };
+        assert!(matches!(spawn(ladybird, &options), Err(LaunchError::Unsupported(_))));
+    }
+
+    #[test]
+    fn test_proxy() {
+        use crate::{LaunchError, ProxyConfig, launch::spawn};
+
+        let options = LaunchOptions::new().profile("Work".to_string()).proxy(ProxyConfig::server("http://proxy:3128"));
+        assert_eq!(
+            options.arguments(&browser("chrome")),
+            ["--profile-directory=Work", "--proxy-server=http://proxy:3128"]
+        );
+        // Firefox gets a generated profile instead
+        assert!(options.arguments(&browser("firefox")).is_empty());
+
+        let safari = Browser { path: "/bin/sh".into(), ..browser("safari") };
+        assert!(matches!(spawn(safari, &options), Err(LaunchError::Unsupported(_))));
+    }
+
+    #[test]
+    fn test_firefox_prefs() {
+        use crate::{FirefoxPref, ProxyConfig};
+        use std::path::PathBuf;
+
+        let options = LaunchOptions::new()
+            .proxy(ProxyConfig::Direct)
+            .firefox_pref("browser.download.dir", "/tmp/\"downloads\"")
+            .firefox_pref("browser.download.folderList", 2)
+            .firefox_pref("network.proxy.type", FirefoxPref::Int(5));
+
+        assert_eq!(
+            options.firefox_profile_files(None).unwrap(),
+            [(
+                PathBuf::from("user.js"),
+                concat!(
+                    "user_pref(\"network.proxy.type\", 0);\n",
+                    "user_pref(\"browser.download.dir\", \"/tmp/\\\"downloads\\\"\");\n",
+                    "user_pref(\"browser.download.folderList\", 2);\n",
+                    "user_pref(\"network.proxy.type\", 5);\n",
+                )
+                .as_bytes()
+                .to_vec()
+            )]
+        );
+        assert!(options.generated_profile("firefox"));
+        assert!(!options.generated_profile("chrome"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_close() {
+        use crate::launch::LaunchedBrowser;
+        use std::io::Read;
+        use std::os::unix::process::ExitStatusExt;
+        use std::process::{Command, Stdio};
+        use std::time::{Duration, Instant};
+
+        let sh = Browser { path: "/bin/sh".into(), ..browser("sh") };
+        let options = LaunchOptions::new();
+        let child = Command::new("/bin/sh").args(["-c", "sleep 30"]).spawn().unwrap();
+        let started = Instant::now();
+        let status = LaunchedBrowser::new(child, &sh, &options, None, false).close(Duration::from_secs(10)).unwrap();
+        assert_eq!(status.signal(), Some(15));
+        assert!(started.elapsed() < Duration::from_secs(10));
+
+        // a process ignoring SIGTERM is killed once the timeout is up, after it says the trap is set
+        let mut child = Command::new("/bin/sh")
+            .args(["-c", "trap '' TERM; echo; sleep 30"])
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child.stdout.take().unwrap().read_exact(&mut [0]).unwrap();
+        let status = LaunchedBrowser::new(child, &sh, &options, None, false).close(Duration::from_millis(200)).unwrap();
+        assert_eq!(status.signal(), Some(9));
+
+        let child = Command::new("/bin/sh").args(["-c", "exit 3"]).spawn().unwrap();
+        let mut launched = LaunchedBrowser::new(child, &sh, &options, None, false);
+        assert_eq!(launched.wait_timeout(Duration::from_secs(10)).unwrap().and_then(|status| status.code()), Some(3));
+        let child = Command::new("sleep").arg("30").spawn().unwrap();
+        let mut launched = LaunchedBrowser::new(child, &sh, &options, None, false);
+        assert!(launched.wait_timeout(Duration::from_millis(100)).unwrap().is_none());
+        launched.kill().unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_kill_on_drop() {
+        use crate::launch::LaunchedBrowser;
+        use std::process::Command;
+
+        let sh = Browser { path: "/bin/sh".into(), ..browser("sh") };
+        let options = LaunchOptions::new().kill_on_drop(true);
+        // `kill -0` only checks that the process exists, which a zombie still does
+        let exists = |pid: u32| Command::new("kill").args(["-0", &pid.to_string()]).status().unwrap().success();
+
+        let launched =
+            LaunchedBrowser::new(Command::new("sleep").arg("30").spawn().unwrap(), &sh, &options, None, false);
+        let pid = launched.id();
+        drop(launched);
+        assert!(!exists(pid));
+
+        let launched =
+            LaunchedBrowser::new(Command::new("sleep").arg("30").spawn().unwrap(), &sh, &options, None, false);
+        let mut child = launched.leak();
+        assert!(exists(child.id()));
+        child.kill().unwrap();
+        child.wait().unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_drop_keeps_profile() {
+        use crate::launch::{LaunchedBrowser, temp_profile_dir};
+        use std::process::{Command, Stdio};
+        use std::thread;
+        use std::time::{Duration, Instant};
+
+        let sh = Browser { path: "/bin/sh".into(), ..browser("sh") };
+        let dir = temp_profile_dir(&[]).unwrap();
+        // exits once its standard input is closed
+        let child = Command::new("/bin/sh").args(["-c", "read line"]).stdin(Stdio::piped()).spawn().unwrap();
+        let mut launched = LaunchedBrowser::new(child, &sh, &LaunchOptions::new(), Some(dir.clone()), false);
+        let stdin = launched.child().stdin.take().unwrap();
+
+        drop(launched);
+        assert!(dir.is_dir());
+        drop(stdin);
+        let start = Instant::now();
+        while dir.exists() && start.elapsed() < Duration::from_secs(10) {
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn test_marionette() {
+        use crate::LaunchError;
+        use crate::launch::spawn;
+        use std::path::PathBuf;
+
+        let options = LaunchOptions::new().marionette(Some(2828)).firefox_pref("marionette.port", 4444);
+        assert_eq!(
+            options.firefox_profile_files(Some(2828)).unwrap(),
+            [(
+                PathBuf::from("user.js"),
+                b"user_pref(\"marionette.port\", 4444);\nuser_pref(\"marionette.port\", 2828);\n".to_vec()
+            )]
+        );
+        assert!(options.generated_profile("firefox"));
+
+        let chrome = Browser { path: "/bin/sh".into(), ..browser("chrome") };
+        assert!(matches!(spawn(chrome, &options), Err(LaunchError::Unsupported(_))));
+        let safari = Browser { path: "/bin/sh".into(), ..browser("safari") };
+        let options = LaunchOptions::new().remote_debugging(None);
+        assert!(matches!(spawn(safari, &options), Err(LaunchError::Unsupported(_))));
+    }
+
+    #[test]
+    fn test_app() {
+        let options =
+            LaunchOptions::new().app("https://example.com".to_string()).url("https://example.org".to_string());
+
+        assert!(options.arguments(&browser("chrome")).contains(&"--app=https://example.com".to_string()));
+        assert_eq!(options.launch_urls("chrome"), ["https://example.org"]);
+        assert!(options.arguments(&browser("firefox")).is_empty());
+        assert_eq!(options.launch_urls("firefox"), ["https://example.com", "https://example.org"]);
+    }
+
+    #[test]
+    fn test_url_arguments() {
+        use crate::launch::url_arguments;
+
+        let urls = ["https://example.com".to_string()];
+        let options = LaunchOptions::new();
+        assert_eq!(url_arguments("firefox", &options, &urls, true), ["-new-tab", "https://example.com"]);
+        assert_eq!(
+            url_arguments("firefox", &options.clone().new_window(), &urls, true),
+            ["-new-window", "https://example.com"]
+        );
+        assert_eq!(url_arguments("firefox", &options.clone().incognito(), &urls, true), ["https://example.com"]);
+        assert_eq!(url_arguments("firefox", &options, &urls, false), ["https://example.com"]);
+        assert_eq!(url_arguments("chrome", &options, &urls, true), ["https://example.com"]);
+    }
+
+    #[test]
+    fn test_window_per_url() {
+        use crate::launch::{split_window_urls, url_arguments};
+
+        let options = LaunchOptions::new().urls(["https://example.com", "https://example.org"]).window_per_url();
+        let (urls, window_urls) = split_window_urls("chrome", &options, &options.urls);
+        assert_eq!((urls, window_urls), (&options.urls[..1], &options.urls[1..]));
+        assert!(options.arguments(&browser("chrome")).contains(&"--new-window".to_string()));
+
+        let (urls, window_urls) = split_window_urls("firefox", &options, &options.urls);
+        assert!(window_urls.is_empty());
+        assert_eq!(
+            url_arguments("firefox", &options, urls, false),
+            ["-new-window", "https://example.com", "-new-window", "https://example.org"]
+        );
+        assert!(split_window_urls("chrome", &options.clone().incognito(), &options.urls).1.is_empty());
+    }
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn test_safari_script() {
+        use crate::launch::safari_script;
+
+        let urls = ["https://example.com/?q=\"a\"".to_string(), "https://example.org".to_string()];
+        assert_eq!(
+            safari_script("Safari", &urls, false, false),
+            [
+                r#"tell application "Safari" to activate"#,
+                r#"tell application "Safari" to make new document with properties {URL:"https://example.com/?q=\"a\""}"#,
+                r#"tell application "Safari" to tell front window to set current tab to (make new tab with properties {URL:"https://example.org"})"#,
+            ]
+        );
+        assert_eq!(
+            safari_script("Safari", &urls[..1], true, false)[2],
+            r#"tell application "Safari" to set URL of front document to "https://example.com/?q=\"a\"""#
+        );
+        assert_eq!(
+            safari_script("Safari", &urls, false, true)[2],
+            r#"tell application "Safari" to make new document with properties {URL:"https://example.org"}"#
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_browser_launch() {
+        let sh = Browser {
+            path: "/bin/sh".into(),
+            args: vec!["-c".to_string(), "exit 3".to_string()],
+            install_source: InstallSource::Custom,
+            ..browser("sh")
+        };
+        // other shells are running, which would count as the browser running already
+        let mut launched = sh.launch(&LaunchOptions::new().reuse_existing(false)).unwrap();
+        assert_eq!(launched.wait().unwrap().code(), Some(3));
+    }
+
+    #[test]
+    fn test_executable_missing() {
+        use crate::LaunchError;
+        use crate::launch::spawn;
+
+        let browser = Browser { path: "/nonexistent/chrome".into(), ..browser("chrome") };
+        let result = spawn(browser, &LaunchOptions::new());
+        assert!(matches!(result, Err(LaunchError::ExecutableMissing(path)) if path.ends_with("chrome")));
+    }
+}
@@ -0,0 +1,27 @@
+//! Lookup of app bundles by bundle ID in the Launch Services database, which answers within the process
+//! instead of spawning `mdfind` and works while Spotlight is disabled or still indexing.
+
+use core_foundation::array::{CFArray, CFArrayRef};
+use core_foundation::base::TCFType;
+use core_foundation::error::CFErrorRef;
+use core_foundation::string::{CFString, CFStringRef};
+use core_foundation::url::CFURL;
+use std::path::PathBuf;
+use std::ptr;
+
+#[link(name = "CoreServices", kind = "framework")]
+unsafe extern "C" {
+    fn LSCopyApplicationURLsForBundleIdentifier(bundle_id: CFStringRef, error: *mut CFErrorRef) -> CFArrayRef;
+}
+
+/// Returns the app bundles registered with `bundle_id`, empty if there are none.
+pub(crate) fn applications(bundle_id: &str) -> Vec<PathBuf> {
+    let bundle_id = CFString::new(bundle_id);
+    // SAFETY: the error is optional, and the array is null if no bundle is registered or else owned by us
+    let urls = unsafe { LSCopyApplicationURLsForBundleIdentifier(bundle_id.as_concrete_TypeRef(), ptr::null_mut()) };
+    if urls.is_null() {
+        return vec![];
+    }
+    let urls: CFArray<CFURL> = unsafe { CFArray::wrap_under_create_rule(urls) };
+    urls.iter().filter_map(|url| url.to_path()).collect()
+}
@@ -1,35 +1,131 @@
-use glob::{MatchOptions, Pattern};
-use std::process::{Child, Command};
+#[cfg(target_os = "macos")]
+pub use backend::SpotlightBackend;
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+pub use backend::TorBackend;
+#[cfg(target_os = "windows")]
+pub use backend::{AppPathsBackend, MsStoreBackend, RegistryBackend};
+#[cfg(target_os = "linux")]
+pub use backend::{DesktopEntryBackend, WslBackend};
+pub use backend::{DetectionBackend, MockBackend, ScanContext};
+pub use capabilities::Capabilities;
+pub use custom::{CustomBrowser, VersionProbe};
+pub use default_browser::{
+    DefaultBrowserChange, SchemeHandler, default_browser, handler_for_scheme, set_default_browser,
+};
+pub use diagnose::{BackendReport, Diagnostics, FilteredOut, SkipReason, SourceOutcome, SourceReport};
+pub use error::{Error, LaunchError, PatternError};
+pub use export::{Browsers, Change};
+pub use info::{Architecture, BrowserInfo, BrowserRecord, Channel, Engine, InstallScope};
+#[cfg(feature = "integrity")]
+pub use integrity::Integrity;
+use inventory::Inventory;
+pub use launch::{FirefoxPref, HeadlessMode, LaunchOptions, LaunchedBrowser, StdioMode};
+pub use matcher::MatchSyntax;
+use matcher::Matcher;
+pub use order::SortBy;
+pub use policy::PolicySource;
+pub use proxy::ProxyConfig;
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
 use std::vec::IntoIter;
+pub use version::{Version, VersionError, VersionReq};
+#[cfg(feature = "watch")]
+pub use watch::{BrowserEvent, BrowserWatcher};
 #[cfg(target_os = "macos")]
-use {plist::Value, std::path::Path};
+use {plist::Value, std::fs};
 
 #[cfg(target_os = "windows")]
 use {
     pelite::FileMap,
     phf::{Map, phf_map},
-    std::path::Path,
     winreg::RegKey,
-    winreg::enums::HKEY_LOCAL_MACHINE,
+    winreg::enums::{HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE},
 };
 
 #[cfg(target_os = "linux")]
 use {
-    freedesktop_desktop_entry::{DesktopEntry, Iter, default_paths},
+    freedesktop_desktop_entry::DesktopEntry,
     lazy_static::lazy_static,
     phf::{Map, phf_map},
     regex::Regex,
-    std::fs,
+    std::process::Command,
 };
 
+mod automation;
+mod backend;
+mod cache;
+mod capabilities;
+#[cfg(feature = "cdp")]
+pub mod cdp;
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+mod cmdline;
+mod custom;
+mod data_dir;
+mod default_browser;
+#[cfg(target_os = "linux")]
+mod desktop_files;
+mod devtools;
+mod diagnose;
+mod error;
+#[cfg(target_os = "linux")]
+mod exec;
+mod export;
+mod extensions;
+#[cfg(target_os = "macos")]
+mod homebrew;
+mod icon;
+mod identify;
+mod info;
+mod install_time;
+#[cfg(feature = "integrity")]
+mod integrity;
+mod inventory;
+mod launch;
+#[cfg(all(target_os = "macos", feature = "launch-services"))]
+mod launch_services;
+mod matcher;
+#[cfg(target_os = "windows")]
+mod msstore;
+pub mod native_messaging;
+mod order;
+#[cfg(target_os = "windows")]
+mod package_managers;
+mod parallel;
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+mod pe;
+mod policy;
+mod process;
+mod proxy;
+mod search_paths;
+#[cfg(feature = "tokio")]
+mod stream;
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+mod tor;
+mod urls;
+mod user_agent;
+mod version;
+#[cfg(feature = "watch")]
+mod watch;
+pub mod webdriver;
+#[cfg(target_os = "windows")]
+pub mod webview2;
+#[cfg(target_os = "linux")]
+mod wsl;
+
 #[cfg(target_os = "macos")]
 const OSX_BROWSER_BUNDLE_LIST: &[(&str, &str, &str)] = &[
     // browser name, bundle ID, version string
+    ("arc", "company.thebrowser.Browser", "CFBundleShortVersionString"),
     ("basilisk", "org.mozilla.basilisk", "CFBundleShortVersionString"),
-    ("brave", "com.brave.Browser", "CFBundleVersion"),
-    ("brave-beta", "com.brave.Browser.beta", "CFBundleVersion"),
-    ("brave-dev", "com.brave.Browser.dev", "CFBundleVersion"),
-    ("brave-nightly", "com.brave.Browser.nightly", "CFBundleVersion"),
+    ("brave", "com.brave.Browser", "CFBundleShortVersionString"),
+    ("brave-beta", "com.brave.Browser.beta", "CFBundleShortVersionString"),
+    ("brave-dev", "com.brave.Browser.dev", "CFBundleShortVersionString"),
+    ("brave-nightly", "com.brave.Browser.nightly", "CFBundleShortVersionString"),
     ("chrome", "com.google.Chrome", "CFBundleShortVersionString"),
     ("chrome-beta", "com.google.Chrome.beta", "CFBundleShortVersionString"),
     ("chrome-canary", "com.google.Chrome.canary", "CFBundleShortVersionString"),
@@ -44,26 +140,32 @@ const OSX_BROWSER_BUNDLE_LIST: &[(&str, &str, &str)] = &[
     ("floorp", "org.mozilla.floorp", "CFBundleShortVersionString"),
     ("librewolf", "org.mozilla.librewolf", "CFBundleShortVersionString"),
     ("midori", "org.mozilla.midori", "CFBundleShortVersionString"),
+    ("min", "com.electron.min", "CFBundleShortVersionString"),
     ("msedge", "com.microsoft.edgemac", "CFBundleShortVersionString"),
     ("msedge-beta", "com.microsoft.edgemac.Beta", "CFBundleShortVersionString"),
     ("msedge-dev", "com.microsoft.edgemac.Dev", "CFBundleShortVersionString"),
     ("msedge-canary", "com.microsoft.edgemac.Canary", "CFBundleShortVersionString"),
-    ("opera", "com.operasoftware.Opera", "CFBundleVersion"),
-    ("opera-beta", "com.operasoftware.OperaNext", "CFBundleVersion"),
-    ("opera-developer", "com.operasoftware.OperaDeveloper", "CFBundleVersion"),
-    ("opera-gx", "com.operasoftware.OperaGX", "CFBundleVersion"),
+    ("opera", "com.operasoftware.Opera", "CFBundleShortVersionString"),
+    ("opera-beta", "com.operasoftware.OperaNext", "CFBundleShortVersionString"),
+    ("opera-developer", "com.operasoftware.OperaDeveloper", "CFBundleShortVersionString"),
+    ("opera-gx", "com.operasoftware.OperaGX", "CFBundleShortVersionString"),
     ("opera-neon", "com.opera.Neon", "CFBundleShortVersionString"),
+    ("orion", "com.kagi.kagimacOS", "CFBundleShortVersionString"),
     ("pale-moon", "org.mozilla.pale moon", "CFBundleShortVersionString"),
     ("safari", "com.apple.Safari", "CFBundleShortVersionString"),
     ("safari-technology-preview", "com.apple.SafariTechnologyPreview", "CFBundleShortVersionString"),
     ("servo", "org.servo.Servo", "CFBundleShortVersionString"),
+    ("sidekick", "com.pushplaylabs.sidekick", "CFBundleShortVersionString"),
+    ("sigmaos", "com.sigmaos.sigmaos.macos", "CFBundleShortVersionString"),
+    ("tor", "org.torproject.torbrowser", "CFBundleShortVersionString"),
     ("vivaldi", "com.vivaldi.Vivaldi", "CFBundleShortVersionString"),
     ("waterfox", "net.waterfox.waterfox", "CFBundleShortVersionString"),
+    ("wavebox", "io.wavebox.wavebox", "CFBundleShortVersionString"),
     ("yandex", "ru.yandex.desktop.yandex-browser", "CFBundleShortVersionString"),
     ("zen", "app.zen-browser.zen", "CFBundleShortVersionString"),
 ];
 
-#[cfg(target_os = "windows")]
+#[cfg(any(target_os = "windows", target_os = "linux"))]
 static WINDOWS_REGISTRY_BROWSER_NAMES: Map<&'static str, &'static str> = phf_map! {
     "Ablaze Floorp" => "floorp",
     "Basilisk" => "basilisk",
@@ -89,52 +191,358 @@ static WINDOWS_REGISTRY_BROWSER_NAMES: Map<&'static str, &'static str> = phf_map
     "Waterfox" => "waterfox",
 };
 
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+const WINDOWS_EXECUTABLE_LIST: &[(&str, &str, &str)] = &[
+    // executable name, part of its directory, browser name (more specific directories first)
+    ("basilisk.exe", "", "basilisk"),
+    ("brave.exe", "Brave-Browser-Beta", "brave-beta"),
+    ("brave.exe", "Brave-Browser-Nightly", "brave-nightly"),
+    ("brave.exe", "", "brave"),
+    ("chrome.exe", "Chrome SxS", "chrome-canary"),
+    ("chrome.exe", "Chrome Beta", "chrome-beta"),
+    ("chrome.exe", "Chrome Dev", "chrome-dev"),
+    ("chrome.exe", "Chromium", "chromium"),
+    ("chrome.exe", "", "chrome"),
+    ("firefox.exe", "Firefox Developer Edition", "firefox-developer"),
+    ("firefox.exe", "Firefox Nightly", "firefox-nightly"),
+    ("firefox.exe", "", "firefox"),
+    ("floorp.exe", "", "floorp"),
+    ("iexplore.exe", "", "msie"),
+    ("launcher.exe", "Opera beta", "opera-beta"),
+    ("launcher.exe", "Opera developer", "opera-developer"),
+    ("launcher.exe", "Opera", "opera"),
+    ("librewolf.exe", "", "librewolf"),
+    ("msedge.exe", "Edge Beta", "msedge-beta"),
+    ("msedge.exe", "Edge Dev", "msedge-dev"),
+    ("msedge.exe", "Edge SxS", "msedge-canary"),
+    ("msedge.exe", "", "msedge"),
+    ("palemoon.exe", "", "pale-moon"),
+    ("vivaldi.exe", "", "vivaldi"),
+    ("waterfox.exe", "", "waterfox"),
+];
+
+#[cfg(target_os = "windows")]
+const WINDOWS_INSTALL_PATH_LIST: &[(&str, &str)] = &[
+    // executable relative to Program Files or %LOCALAPPDATA%, display name
+    (r"Ablaze Floorp\floorp.exe", "Floorp"),
+    (r"BraveSoftware\Brave-Browser\Application\brave.exe", "Brave"),
+    (r"BraveSoftware\Brave-Browser-Beta\Application\brave.exe", "Brave Beta"),
+    (r"BraveSoftware\Brave-Browser-Nightly\Application\brave.exe", "Brave Nightly"),
+    (r"Chromium\Application\chrome.exe", "Chromium"),
+    (r"Firefox Developer Edition\firefox.exe", "Firefox Developer Edition"),
+    (r"Firefox Nightly\firefox.exe", "Firefox Nightly"),
+    (r"Google\Chrome\Application\chrome.exe", "Google Chrome"),
+    (r"Google\Chrome Beta\Application\chrome.exe", "Google Chrome Beta"),
+    (r"Google\Chrome Dev\Application\chrome.exe", "Google Chrome Dev"),
+    (r"Google\Chrome SxS\Application\chrome.exe", "Google Chrome Canary"),
+    (r"LibreWolf\librewolf.exe", "LibreWolf"),
+    (r"Microsoft\Edge\Application\msedge.exe", "Microsoft Edge"),
+    (r"Microsoft\Edge Beta\Application\msedge.exe", "Microsoft Edge Beta"),
+    (r"Microsoft\Edge Dev\Application\msedge.exe", "Microsoft Edge Dev"),
+    (r"Microsoft\Edge SxS\Application\msedge.exe", "Microsoft Edge Canary"),
+    (r"Mozilla Firefox\firefox.exe", "Mozilla Firefox"),
+    (r"Pale Moon\palemoon.exe", "Pale Moon"),
+    (r"Programs\Opera\launcher.exe", "Opera"),
+    (r"Vivaldi\Application\vivaldi.exe", "Vivaldi"),
+    (r"Waterfox\waterfox.exe", "Waterfox"),
+];
+
 #[cfg(target_os = "linux")]
 static LINUX_DESKTOP_ENTRY_NAME_LIST: Map<&'static str, &'static str> = phf_map! {
-    // desktop entry name can be "brave-browser.desktop" or "brave_brave.desktop"
+    // desktop entry name can be "brave-browser.desktop" or "brave_brave.desktop", or a Flatpak app ID
+    "app.zen_browser.zen" => "zen",
+    "badwolf" => "badwolf",
     "brave-browser" => "brave",
     "brave_brave" => "brave",
     "brave-browser-beta" => "brave-beta",
     "brave-browser-nightly" => "brave-nightly",
     "chromium" => "chromium",
     "chromium_chromium" => "chromium",
+    "epiphany" => "epiphany",
     "falkon_falkon" => "falkon",
     "firefox" => "firefox",
     "firefox_firefox" => "firefox",
+    "floorp" => "floorp",
     "google-chrome" => "chrome",
+    "io.github.ungoogled_software.ungoogled_chromium" => "ungoogled-chromium",
+    "io.gitlab.librewolf-community" => "librewolf",
     "konqueror_konqueror" => "konqueror",
+    "librewolf" => "librewolf",
+    "luakit" => "luakit",
     "microsoft-edge" => "msedge",
+    "midori" => "midori",
+    "net.waterfox.waterfox" => "waterfox",
+    "one.ablaze.floorp" => "floorp",
     "opera_opera" => "opera",
     "opera-beta_opera-beta" => "opera-beta",
     "opera-developer_opera-developer" => "opera-developer",
+    "org.gnome.Epiphany" => "epiphany",
+    "org.midori_browser.Midori" => "midori",
+    "org.qutebrowser.qutebrowser" => "qutebrowser",
+    "surf" => "surf",
+    "ungoogled-chromium" => "ungoogled-chromium",
     "vivaldi_vivaldi-stable" => "vivaldi",
+    "waterfox" => "waterfox",
+    "yandex-browser" => "yandex",
+    "yandex-browser-beta" => "yandex-beta",
+    "zen" => "zen",
 };
 
+#[cfg(target_os = "linux")]
+const LINUX_DESKTOP_ENTRY_IGNORE_LIST: &[&str] = &[
+    // desktop entries of browsers that are detected from their install directory instead
+    "start-tor-browser",
+    "torbrowser",
+];
+
+#[cfg(target_os = "linux")]
+const LINUX_VERSION_PROBE_IGNORE_LIST: &[&str] = &[
+    // browsers without a version flag, which would take `--version` for a URL or print usage to stderr
+    "badwolf", "surf",
+];
+
 #[cfg(target_os = "linux")]
 lazy_static! {
     static ref VERSION_PATTERN: Regex = Regex::new(r"\b(\d+(\.\d+)+)\b").unwrap();
 }
 
-#[derive(serde::Serialize, serde::Deserialize, Clone, Hash, Debug)]
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, Eq, Hash, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct Browser {
     pub browser_type: String,
-    pub path: String,
+    /// The executable, or the `.app` bundle of Safari. Kept as an OS string, so paths that aren't valid
+    /// Unicode survive, but serialized lossily.
+    #[serde(serialize_with = "serialize_path")]
+    pub path: PathBuf,
+    /// The Exec line of the Linux desktop entry the browser was found by, e.g.
+    /// `flatpak run org.mozilla.firefox @@u %u @@`, which launching expands instead of running `path`, the
+    /// program it runs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exec: Option<String>,
+    /// Arguments the platform registered the executable with, e.g. `--single-argument` from a Windows
+    /// `shell\open\command` value, which launching passes first. Placeholders for the URLs are left out.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
     pub display_name: String,
+    /// The version the browser shows users, e.g. `1.61.109` for Brave, whose executable and bundle report
+    /// `120.1.61.109`. Edge reports its own version rather than the one of the Chromium it is built on.
     pub version: String,
+    /// The version as the platform reported it, when it differs from `version`.
+    #[serde(default)]
+    pub raw_version: Option<String>,
+    /// Where the browser was found, e.g. a system install or an automation tool's download cache.
+    #[serde(default)]
+    pub install_source: InstallSource,
+    /// The platform's identifier for the install, for looking it up with other system APIs: the bundle
+    /// identifier on macOS, e.g. `com.google.Chrome`, the `StartMenuInternet` subkey or the package full name
+    /// of a Microsoft Store app on Windows, or the desktop entry ID on Linux, e.g. `firefox.desktop`.
+    #[serde(default)]
+    pub source_id: Option<String>,
+    /// The artifact the browser was found through, e.g. the `.app` bundle, the install directory of a
+    /// Microsoft Store package holding `AppxManifest.xml`, or the `.desktop` file.
+    #[serde(default, serialize_with = "serialize_optional_path")]
+    pub source_path: Option<PathBuf>,
+    /// The package manager's identifier for the install, e.g. the Homebrew cask `firefox` or the winget
+    /// package `Mozilla.Firefox`, see `BrowserFinder::with_package_managers`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub package: Option<String>,
+}
+
+fn serialize_path<S: serde::Serializer>(path: &Path, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&path.to_string_lossy())
+}
+
+fn serialize_optional_path<S: serde::Serializer>(path: &Option<PathBuf>, serializer: S) -> Result<S::Ok, S::Error> {
+    match path {
+        Some(path) => serializer.serialize_some(&path.to_string_lossy()),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// How a browser came to be on the machine.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub enum InstallSource {
+    /// Found by the platform scan.
+    #[default]
+    System,
+    /// Registered with `BrowserFinder::register` or the config file.
+    Custom,
+    /// Downloaded by Playwright, e.g. into `~/.cache/ms-playwright`.
+    Playwright,
+    /// Downloaded by Puppeteer, e.g. into `~/.cache/puppeteer`.
+    Puppeteer,
+    /// Installed from the Microsoft Store as an MSIX package.
+    MsStore,
+    /// Extracted from a Chrome for Testing download, e.g. into `~/.cache/chrome-for-testing/<version>`.
+    ChromeForTesting,
+    /// Installed by a Homebrew cask on macOS, see `BrowserFinder::with_package_managers`.
+    Homebrew,
+    /// Installed with winget on Windows.
+    Winget,
+    /// Installed from a Chocolatey package on Windows.
+    Chocolatey,
+    /// Installed into a Scoop bucket's app directory on Windows.
+    Scoop,
+}
+
+impl fmt::Display for InstallSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            InstallSource::System => "system",
+            InstallSource::Custom => "custom",
+            InstallSource::Playwright => "playwright",
+            InstallSource::Puppeteer => "puppeteer",
+            InstallSource::MsStore => "ms-store",
+            InstallSource::ChromeForTesting => "chrome-for-testing",
+            InstallSource::Homebrew => "homebrew",
+            InstallSource::Winget => "winget",
+            InstallSource::Chocolatey => "chocolatey",
+            InstallSource::Scoop => "scoop",
+        })
+    }
+}
+
+impl Browser {
+    /// Returns the version parsed into numeric components, if it has any.
+    pub fn parsed_version(&self) -> Option<Version> {
+        self.version.parse().ok()
+    }
+
+    /// Replaces a version read from platform metadata with the product version, keeping the former in
+    /// `raw_version`.
+    pub(crate) fn normalize_version(&mut self) {
+        if let Some(version) = version::product_version(&self.browser_type, &self.version) {
+            self.raw_version = Some(std::mem::replace(&mut self.version, version));
+        }
+    }
+}
+
+#[cfg(test)]
+impl Browser {
+    /// A system install of `browser_type` without a version, for tests to set the fields they check on.
+    pub(crate) fn test_fixture(browser_type: &str, path: impl Into<PathBuf>) -> Browser {
+        Browser {
+            browser_type: browser_type.to_string(),
+            path: path.into(),
+            exec: None,
+            args: vec![],
+            display_name: browser_type.to_string(),
+            version: String::new(),
+            raw_version: None,
+            install_source: InstallSource::System,
+            source_id: None,
+            source_path: None,
+            package: None,
+        }
+    }
 }
 
+#[derive(Clone)]
 pub struct BrowserFinder {
-    browser_type: String,
+    browser_types: Vec<String>,
     version: String,
-    exclude: String,
+    version_req: Option<String>,
+    excludes: Vec<String>,
+    match_syntax: MatchSyntax,
+    case_sensitive: bool,
+    cache_ttl: Option<Duration>,
+    disk_cache: bool,
+    inventory: Option<PathBuf>,
+    bypass_cache: bool,
+    concurrency: usize,
+    custom: Vec<CustomBrowser>,
+    backends: Vec<Arc<dyn DetectionBackend>>,
+    builtin_backends: bool,
+    disabled_backends: Vec<String>,
+    config_path: Option<PathBuf>,
+    search_paths: Vec<PathBuf>,
+    automation_caches: bool,
+    #[cfg_attr(target_os = "linux", allow(dead_code))]
+    package_managers: bool,
+    browser_env: bool,
+    engines: Vec<Engine>,
+    version_probe: bool,
+    process_probes: bool,
+    probe_timeout: Duration,
+    deadline: Option<Duration>,
+    architectures: Vec<Architecture>,
+    sort_by: SortBy,
+    include_stale: bool,
+    include_no_display: bool,
+}
+
+/// Deduplicates the copies of an application found by bundle ID by canonical path and orders them so
+/// the install in `/Applications` comes first, then `~/Applications`, then other copies such as
+/// ones on external volumes or in `~/Downloads`.
+#[cfg(target_os = "macos")]
+fn order_copies(applications: Vec<PathBuf>) -> Vec<PathBuf> {
+    let home_applications = env::var_os("HOME").map(|home| Path::new(&home).join("Applications"));
+    let rank = |path: &Path| {
+        if path.starts_with("/Applications") || path.starts_with("/System/Applications") {
+            0
+        } else if home_applications.as_ref().is_some_and(|dir| path.starts_with(dir)) {
+            1
+        } else if path.starts_with("/Volumes") {
+            3
+        } else {
+            2
+        }
+    };
+
+    let mut copies: Vec<PathBuf> = vec![];
+    for application in applications {
+        let application = application.canonicalize().unwrap_or(application);
+        if !copies.contains(&application) {
+            copies.push(application);
+        }
+    }
+    copies.sort_by(|a, b| rank(a).cmp(&rank(b)).then_with(|| a.cmp(b)));
+    copies
+}
+
+/// Lists the app bundles in the Applications folders and their subfolders such as `Utilities`, with their
+/// bundle IDs, for scans that may neither ask Launch Services nor run `mdfind`.
+#[cfg(target_os = "macos")]
+fn application_bundles() -> Vec<(PathBuf, String)> {
+    let home_applications = env::var_os("HOME").map(|home| Path::new(&home).join("Applications"));
+    let is_bundle = |path: &Path| path.extension().is_some_and(|extension| extension == "app");
+    let entries = |dir: &Path| {
+        fs::read_dir(dir).into_iter().flatten().filter_map(Result::ok).map(|entry| entry.path()).collect::<Vec<_>>()
+    };
+
+    let mut applications = vec![];
+    for dir in [Some(PathBuf::from("/Applications")), Some(PathBuf::from("/System/Applications")), home_applications]
+        .into_iter()
+        .flatten()
+    {
+        for path in entries(&dir) {
+            match is_bundle(&path) {
+                true => applications.push(path),
+                false if path.is_dir() => {
+                    applications.extend(entries(&path).into_iter().filter(|path| is_bundle(path)))
+                }
+                false => {}
+            }
+        }
+    }
+
+    applications
+        .into_iter()
+        .filter_map(|application| {
+            let properties = Value::from_file(application.join("Contents/Info.plist")).ok()?;
+            let bundle_id = properties.as_dictionary()?.get("CFBundleIdentifier")?.as_string()?.to_string();
+            Some((application, bundle_id))
+        })
+        .collect()
 }
 
+/// Reads the browser from the `Info.plist` of an app bundle, `None` if the bundle is gone or broken, e.g. a
+/// stale Spotlight entry of a deleted app.
 #[cfg(target_os = "macos")]
-fn extract_info_from_plist(application_path: &str, browser_type: &str, version_string: &str) -> Browser {
-    let base_path = Path::new(application_path);
+fn extract_info_from_plist(base_path: &Path, browser_type: &str, version_string: &str) -> Option<Browser> {
     let path = base_path.join("Contents/Info.plist");
-    let properties = Value::from_file(path).unwrap();
+    let properties = Value::from_file(path).ok()?;
 
     let display_name = properties
         .as_dictionary()
@@ -142,72 +550,238 @@ fn extract_info_from_plist(application_path: &str, browser_type: &str, version_s
         .and_then(|e| e.as_string())
         .unwrap_or(browser_type);
 
-    let executable_name =
-        properties.as_dictionary().and_then(|d| d.get("CFBundleExecutable")).and_then(|e| e.as_string()).unwrap();
-
     let executable = match browser_type {
-        "safari" => base_path.to_str().unwrap().to_owned(),
-        _ => base_path.join("Contents/MacOS").join(executable_name).to_str().unwrap().to_owned(),
+        "safari" => base_path.to_path_buf(),
+        _ => base_path.join("Contents/MacOS").join(properties.as_dictionary()?.get("CFBundleExecutable")?.as_string()?),
     };
 
-    let version = properties.as_dictionary().and_then(|d| d.get(version_string)).and_then(|e| e.as_string()).unwrap();
+    // the marketing version users see, or the build version for bundles that only carry that one
+    let version = [version_string, "CFBundleShortVersionString", "CFBundleVersion"]
+        .iter()
+        .find_map(|key| properties.as_dictionary()?.get(key)?.as_string())
+        .unwrap_or_default();
+    let bundle_id = properties.as_dictionary().and_then(|d| d.get("CFBundleIdentifier")).and_then(|e| e.as_string());
 
-    Browser {
+    Some(Browser {
         browser_type: browser_type.to_owned(),
         display_name: display_name.to_owned(),
         path: executable,
+        exec: None,
+        args: vec![],
         version: version.to_owned(),
-    }
+        raw_version: None,
+        install_source: InstallSource::System,
+        source_id: bundle_id.map(str::to_owned),
+        source_path: Some(base_path.to_path_buf()),
+        package: None,
+    })
 }
 
+/// Reads the file version of a Windows executable, remembering it until the file changes.
 #[cfg(target_os = "windows")]
 fn get_version_info(path: &Path) -> String {
-    // https://github.com/loot/loot-condition-interpreter/blob/2b95f26727f995b1b001b7ca9c9c233af9142c3d/src/function/version.rs#L139
+    cache::file_version(path, |path| {
+        pe::file_version(path)
+            .or_else(|| uninstall_version(path))
+            .or_else(|| map_version_info(path))
+            .unwrap_or_default()
+    })
+}
 
-    let mut version = "".to_string();
+/// Reads `DisplayVersion` from the uninstall entry of the executable.
+#[cfg(target_os = "windows")]
+fn uninstall_version(path: &Path) -> Option<String> {
+    uninstall_entry(path)?.get_value("DisplayVersion").ok()
+}
 
-    if let Ok(file_map) = FileMap::open(path) {
-        use pelite::pe64;
+/// Finds the uninstall entry whose `DisplayIcon` is the executable, which most browser installers write.
+#[cfg(target_os = "windows")]
+pub(crate) fn uninstall_entry(path: &Path) -> Option<RegKey> {
+    let roots = [RegKey::predef(HKEY_LOCAL_MACHINE), RegKey::predef(HKEY_CURRENT_USER)];
+    let keys = [
+        r"Software\Microsoft\Windows\CurrentVersion\Uninstall",
+        r"Software\WOW6432Node\Microsoft\Windows\CurrentVersion\Uninstall",
+    ];
 
-        version = match pe64::PeFile::from_bytes(file_map.as_ref()) {
-            Ok(file) => {
-                use pelite::pe64::Pe;
+    roots.iter().flat_map(|root| keys.iter().filter_map(|key| root.open_subkey(key).ok())).find_map(|uninstall| {
+        uninstall.enum_keys().filter_map(Result::ok).find_map(|name| {
+            let entry = uninstall.open_subkey(name).ok()?;
+            // e.g. "C:\Program Files\Google\Chrome\Application\chrome.exe,0"
+            let icon: String = entry.get_value("DisplayIcon").ok()?;
+            let icon = icon.rsplit_once(',').map_or(icon.as_str(), |(icon, _)| icon).trim_matches('"');
+            icon.eq_ignore_ascii_case(&path.to_string_lossy()).then_some(entry)
+        })
+    })
+}
 
-                let fixed_file_info = file.resources().unwrap().version_info().unwrap().fixed().unwrap();
-                format!("{}", fixed_file_info.dwFileVersion)
-            }
-            Err(pelite::Error::PeMagic) => {
-                use pelite::pe32::{Pe, PeFile};
-
-                let fixed_file_info = PeFile::from_bytes(file_map.as_ref())
-                    .unwrap()
-                    .resources()
-                    .unwrap()
-                    .version_info()
-                    .unwrap()
-                    .fixed()
-                    .unwrap();
-                format!("{}", fixed_file_info.dwFileVersion)
-            }
-            Err(_) => version,
+/// Parses the version resource by mapping the whole executable, for files `pe::file_version` cannot read.
+#[cfg(target_os = "windows")]
+fn map_version_info(path: &Path) -> Option<String> {
+    // https://github.com/loot/loot-condition-interpreter/blob/2b95f26727f995b1b001b7ca9c9c233af9142c3d/src/function/version.rs#L139
+    let file_map = FileMap::open(path).ok()?;
+    let fixed_file_info = match pelite::pe64::PeFile::from_bytes(file_map.as_ref()) {
+        Ok(file) => {
+            use pelite::pe64::Pe;
+            file.resources().ok()?.version_info().ok()?.fixed()?
+        }
+        Err(pelite::Error::PeMagic) => {
+            use pelite::pe32::{Pe, PeFile};
+            PeFile::from_bytes(file_map.as_ref()).ok()?.resources().ok()?.version_info().ok()?.fixed()?
         }
+        Err(_) => return None,
     };
+    Some(format!("{}", fixed_file_info.dwFileVersion))
+}
+
+/// Maps a `StartMenuInternet` entry to a browser type by its key name, e.g. `Google Chrome` or
+/// `Microsoft Edge Beta`, or else by its executable, e.g. `...\Firefox Nightly\firefox.exe` for a
+/// `Firefox-308046B0AF4A39CB` key.
+///
+/// The display name may be localized, so it is only checked for `ESR`, which makes Firefox `firefox-esr`.
+/// Also used on Linux for the Windows side of WSL.
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+fn windows_browser_type(key: &str, executable: &str, display_name: &str) -> Option<String> {
+    let executable = executable.to_lowercase();
+    let (directory, file_name) = executable.rsplit_once(['\\', '/']).unwrap_or(("", &executable));
+    let browser_type = registry_name_type(key).or_else(|| {
+        WINDOWS_EXECUTABLE_LIST
+            .iter()
+            .find(|(name, fragment, _)| file_name == *name && directory.contains(&fragment.to_lowercase()))
+            .map(|(_, _, browser_type)| *browser_type)
+    })?;
+
+    let esr = display_name.split_whitespace().any(|word| word.eq_ignore_ascii_case("ESR"));
+    match browser_type {
+        "firefox" if esr => Some("firefox-esr".to_string()),
+        browser_type => Some(browser_type.to_string()),
+    }
+}
+
+/// Looks up a registry name, ignoring case and any version and locale appended to it, e.g.
+/// `Mozilla Firefox 115.0.1 (x64 en-US)`.
+#[cfg(any(target_os = "windows", target_os = "linux"))]
+fn registry_name_type(name: &str) -> Option<&'static str> {
+    let words: Vec<&str> = name
+        .split_whitespace()
+        .take_while(|word| !word.starts_with(|c: char| c == '(' || c.is_ascii_digit()))
+        .filter(|word| !word.eq_ignore_ascii_case("ESR"))
+        .collect();
+
+    // the longest known name wins, e.g. "Microsoft Edge Beta" over "Microsoft Edge"
+    (1..=words.len()).rev().find_map(|n| {
+        let name = words[..n].join(" ");
+        WINDOWS_REGISTRY_BROWSER_NAMES
+            .entries()
+            .find(|(known, _)| known.eq_ignore_ascii_case(&name))
+            .map(|(_, browser_type)| *browser_type)
+    })
+}
+
+/// Returns whether a desktop entry handles `http(s)` URLs and is categorized as a web browser.
+#[cfg(target_os = "linux")]
+fn is_web_browser_entry(entry: &DesktopEntry) -> bool {
+    let has = |list: Option<&str>, value: &str| list.is_some_and(|list| list.split(';').any(|item| item == value));
+    (has(entry.mime_type(), "x-scheme-handler/http") || has(entry.mime_type(), "x-scheme-handler/https"))
+        && has(entry.categories(), "WebBrowser")
+}
+
+/// Names a browser that isn't in the desktop entry table after its desktop file, e.g. `firefox-esr`
+/// or `epiphany` for `org.gnome.Epiphany`.
+#[cfg(target_os = "linux")]
+fn generic_browser_type(base_name: &str) -> String {
+    base_name.rsplit('.').next().unwrap_or(base_name).to_lowercase()
+}
+
+#[cfg(target_os = "linux")]
+fn get_version_output(exec: &str, timeout: Duration) -> String {
+    let Ok(mut argv) = exec::expand(exec, &[], &[]) else {
+        return "".to_string();
+    };
+    argv.push("--version".to_string());
+
+    match version::probe_output(Command::new(&argv[0]).args(&argv[1..]), timeout) {
+        Some(stdout) => match VERSION_PATTERN.captures(stdout.as_str()) {
+            Some(capture) => capture.get(1).map_or("".to_string(), |m| m.as_str().to_string()),
+            None => "".to_string(),
+        },
+        None => "".to_string(),
+    }
+}
 
-    version
+/// Finds an executable by name on `PATH`.
+pub(crate) fn find_on_path(name: &str) -> Option<PathBuf> {
+    let names = [name.to_string(), format!("{name}{}", env::consts::EXE_SUFFIX)];
+    env::split_paths(&env::var_os("PATH")?)
+        .flat_map(|dir| names.iter().map(move |name| dir.join(name)))
+        .find(|path| path.is_file())
+}
+
+impl Default for BrowserFinder {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl BrowserFinder {
     pub fn new() -> Self {
-        BrowserFinder { browser_type: String::from("*"), version: String::from("*"), exclude: String::from("") }
+        BrowserFinder {
+            browser_types: vec![],
+            version: String::from("*"),
+            version_req: None,
+            excludes: vec![],
+            match_syntax: MatchSyntax::default(),
+            case_sensitive: false,
+            cache_ttl: None,
+            disk_cache: false,
+            inventory: None,
+            bypass_cache: false,
+            concurrency: thread::available_parallelism().map_or(1, |n| n.get()),
+            custom: vec![],
+            backends: vec![],
+            builtin_backends: true,
+            disabled_backends: vec![],
+            config_path: custom::default_config_path(),
+            search_paths: vec![],
+            automation_caches: false,
+            package_managers: false,
+            browser_env: true,
+            engines: vec![],
+            version_probe: true,
+            process_probes: true,
+            probe_timeout: version::DEFAULT_PROBE_TIMEOUT,
+            deadline: None,
+            architectures: vec![],
+            sort_by: SortBy::default(),
+            include_stale: false,
+            include_no_display: false,
+        }
     }
 
+    /// Includes browsers whose type or display name matches the glob. Can be called multiple times.
+    ///
+    /// A browser is listed if it matches any of the included patterns, or all browsers if none were given.
     pub fn with_type(mut self, browser_type: String) -> Self {
-        self.browser_type = browser_type;
+        self.browser_types.push(browser_type);
         self
     }
 
+    /// Includes browsers matching any of the globs, see `with_type`.
+    pub fn with_types<I: IntoIterator<Item = S>, S: Into<String>>(mut self, browser_types: I) -> Self {
+        self.browser_types.extend(browser_types.into_iter().map(Into::into));
+        self
+    }
+
+    /// Excludes browsers whose type matches the glob. Can be called multiple times.
+    ///
+    /// A browser matching any excluded pattern is never listed, even if it matches an included one.
     pub fn exclude_type(mut self, browser_type: String) -> Self {
-        self.exclude = browser_type;
+        self.excludes.push(browser_type);
+        self
+    }
+
+    /// Excludes browsers matching any of the globs, see `exclude_type`.
+    pub fn exclude_types<I: IntoIterator<Item = S>, S: Into<String>>(mut self, browser_types: I) -> Self {
+        self.excludes.extend(browser_types.into_iter().map(Into::into));
         self
     }
 
@@ -216,153 +790,565 @@ impl BrowserFinder {
         self
     }
 
+    /// Only lists browsers built on `engine`, e.g. `Engine::Blink` for any Chromium-based browser. Can be
+    /// called multiple times to accept several engines.
+    pub fn with_engine(mut self, engine: Engine) -> Self {
+        self.engines.push(engine);
+        self
+    }
+
+    /// Only lists browsers whose executable is built for `architecture`, e.g. to match a native messaging
+    /// host or driver. A macOS universal binary matches each of its architectures. Can be called multiple
+    /// times to accept several architectures.
+    pub fn with_architecture(mut self, architecture: Architecture) -> Self {
+        self.architectures.push(architecture);
+        self
+    }
+
+    /// Also lists browsers whose executable is gone, e.g. registry keys and desktop entries left behind
+    /// by an uninstaller, for inventories of what is registered. They are left out by default.
+    pub fn include_stale(mut self, include_stale: bool) -> Self {
+        self.include_stale = include_stale;
+        self
+    }
+
+    /// Also lists browsers from Linux desktop entries marked `NoDisplay=true`, which distributions use for
+    /// wrappers and helpers that are kept out of menus. They are left out by default. Entries marked
+    /// `Hidden=true` count as uninstalled and are never listed.
+    pub fn include_no_display(mut self, include_no_display: bool) -> Self {
+        self.include_no_display = include_no_display;
+        self
+    }
+
+    /// Filters by a version requirement such as `>=120`, `^121.0` or `>=115, <120`.
+    ///
+    /// This is applied in addition to the glob set with `with_version`.
+    pub fn with_version_req(mut self, requirement: &str) -> Self {
+        self.version_req = Some(requirement.to_string());
+        self
+    }
+
+    /// Adds a browser that the platform scan doesn't know about. It is listed if its path exists.
+    pub fn register(mut self, browser: CustomBrowser) -> Self {
+        self.custom.push(browser);
+        self
+    }
+
+    /// Adds a source of browsers that runs after the built-in backends on every scan. Its results are
+    /// never cached.
+    pub fn with_backend<B: DetectionBackend + 'static>(mut self, backend: B) -> Self {
+        self.backends.push(Arc::new(backend));
+        self
+    }
+
+    /// Skips the backend with this name, e.g. `msstore` or `wsl`, see `DetectionBackend::name`. Can be
+    /// called multiple times.
+    ///
+    /// The cache is not used while a built-in backend is disabled.
+    pub fn disable_backend(mut self, name: &str) -> Self {
+        self.disabled_backends.push(name.to_string());
+        self
+    }
+
+    /// Skips all of the platform's built-in backends, so only registered browsers, search paths and the
+    /// backends added with `with_backend` are listed. Built-in backends can be added back individually,
+    /// e.g. `with_backend(TorBackend)`.
+    pub fn without_builtin_backends(mut self) -> Self {
+        self.builtin_backends = false;
+        self
+    }
+
+    /// Reads custom browsers from this TOML file instead of the default
+    /// `rsbrowsers/browsers.toml` under the user's config directory.
+    pub fn with_config(mut self, path: PathBuf) -> Self {
+        self.config_path = Some(path);
+        self
+    }
+
+    /// Also identifies the browsers up to a few levels below this directory, e.g. `D:\PortableApps`.
+    /// Can be called multiple times, and adds to the directories in `RSBROWSERS_EXTRA_PATHS` and the
+    /// config file's `search_paths`.
+    pub fn with_search_path(mut self, path: PathBuf) -> Self {
+        self.search_paths.push(path);
+        self
+    }
+
+    /// Also lists browsers downloaded by Playwright and Puppeteer into their caches, including
+    /// `chrome-headless-shell`, and Chrome for Testing downloads.
+    ///
+    /// The caches default to `ms-playwright` under the user's cache directory and `~/.cache/puppeteer`, and
+    /// honor `PLAYWRIGHT_BROWSERS_PATH` and `PUPPETEER_CACHE_DIR`. Chrome for Testing zips are expected to be
+    /// extracted into `chrome-for-testing/<version>` under the user's cache directory.
+    pub fn with_automation_caches(mut self) -> Self {
+        self.automation_caches = true;
+        self
+    }
+
+    /// Reads what package managers recorded about their installs to set `install_source` and `package` of
+    /// the browsers they installed, and to find the ones the platform scan misses. The records are read from
+    /// disk rather than by running the package managers, which take seconds to start. Does nothing on Linux.
+    ///
+    /// On macOS, Homebrew casks are read from `Caskroom` under `HOMEBREW_PREFIX`, `/opt/homebrew` and
+    /// `/usr/local`, which also finds apps installed with `--appdir` outside the folders Spotlight indexes.
+    ///
+    /// On Windows, Scoop apps are read from `SCOOP` and `SCOOP_GLOBAL`, by default `~\scoop` and
+    /// `C:\ProgramData\scoop`, portable winget packages from its `Packages` directories and Chocolatey
+    /// packages from `ChocolateyInstall`. Browsers winget installed by running their installers are only
+    /// recorded in its database and can't be told apart from other installs.
+    pub fn with_package_managers(mut self) -> Self {
+        self.package_managers = true;
+        self
+    }
+
+    /// Ignores the `BROWSER` environment variable when launching, see `launch`.
+    pub fn ignore_browser_env(mut self) -> Self {
+        self.browser_env = false;
+        self
+    }
+
+    /// Limits how many browser entries are probed at the same time. Use `1` to scan serially.
+    ///
+    /// Defaults to the available parallelism. Results keep the same order regardless of the limit.
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Kills a version probe such as `chromium --version` that runs longer than `timeout`, leaving the
+    /// version empty. Defaults to 5 seconds.
+    ///
+    /// Applies to Linux desktop entries and custom browsers, whose versions are read by running them.
+    pub fn with_probe_timeout(mut self, timeout: Duration) -> Self {
+        self.probe_timeout = timeout;
+        self
+    }
+
+    /// Returns the browsers found within `budget` instead of waiting for slow sources, for interactive apps
+    /// that need a predictable latency.
+    ///
+    /// Version probes such as Linux `--version` wait at most until the budget is spent and are skipped after,
+    /// leaving versions empty as with `skip_version_probe`. Backends that would start later are skipped too.
+    /// Results that miss anything are not stored in the cache.
+    pub fn with_deadline(mut self, budget: Duration) -> Self {
+        self.deadline = Some(budget);
+        self
+    }
+
+    /// Leaves the versions of Linux desktop entries empty instead of running each browser, for fast scans.
+    ///
+    /// Version filters then only match browsers whose version is known without probing. Results are not
+    /// stored in the cache.
+    pub fn skip_version_probe(mut self) -> Self {
+        self.version_probe = false;
+        self
+    }
+
+    /// Never spawns processes during discovery, for embedders that only allow filesystem and registry reads.
+    ///
+    /// Versions that are only known by running a browser are left empty as with `skip_version_probe`. Without
+    /// the `launch-services` feature, macOS looks for app bundles in the Applications folders instead of
+    /// asking Spotlight with `mdfind`. Building without the `process-probes` feature has the same effect.
+    pub fn skip_process_probes(mut self) -> Self {
+        self.process_probes = false;
+        self
+    }
+
+    /// Matches type filters, excludes and preferences case-sensitively, e.g. so that a custom browser named
+    /// `Chrome` is not confused with `chrome`. Version globs always ignore case.
+    pub fn case_sensitive(mut self) -> Self {
+        self.case_sensitive = true;
+        self
+    }
+
+    /// Sets how type filters, excludes and preferences are written, globs unless set.
+    ///
+    /// `MatchSyntax::Literal` avoids escaping names that contain `*`, `?` or `[`, and `MatchSyntax::Regex`
+    /// (with the `regex` feature) allows alternations such as `(chrome|msedge)-(beta|dev)`.
+    pub fn match_syntax(mut self, syntax: MatchSyntax) -> Self {
+        self.match_syntax = syntax;
+        self
+    }
+
+    /// Reuses the results of a previous scan for `ttl`, shared by all finders in the process.
+    pub fn cached(mut self, ttl: Duration) -> Self {
+        self.cache_ttl = Some(ttl);
+        self
+    }
+
+    /// Also persists cached results under the platform cache directory so they survive restarts.
+    ///
+    /// Has no effect unless `cached` is set.
+    pub fn disk_cache(mut self) -> Self {
+        self.disk_cache = true;
+        self
+    }
+
+    /// Keeps the last scan in the file at `path` with fingerprints of its sources, such as the modification
+    /// times of desktop entries and app bundles or the last write times of registry keys, and reuses it as
+    /// long as nothing was installed, removed or updated since, for tools that are run again and again.
+    ///
+    /// Unlike `disk_cache`, which keeps the results of `cached` in the platform cache directory until its TTL
+    /// runs out, the file is at a path of your choosing and doesn't expire. Results still fresh in the `cached`
+    /// cache win, and the file is only checked once they expired or if `cached` isn't set. Browsers installed
+    /// outside the fingerprinted places, e.g. per-user installs registered only in the registry of a WSL host,
+    /// are picked up with `bypass_cache`.
+    ///
+    /// After a change, Linux only probes the versions of desktop entries whose files changed again. Windows
+    /// and macOS read versions from file metadata rather than by running browsers, so they read them all again.
+    /// Like the cache, it is not used while a built-in backend is disabled.
+    pub fn with_disk_cache<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.inventory = Some(path.into());
+        self
+    }
+
+    /// Always rescans, refreshing the cache with the new results.
+    pub fn bypass_cache(mut self) -> Self {
+        self.bypass_cache = true;
+        self
+    }
+
+    /// Clears cached results from memory and disk.
+    pub fn invalidate() {
+        cache::clear();
+    }
+
+    /// Orders the results, by browser type, then newest version first, then path unless set. Launching
+    /// picks the first browser in this order.
+    pub fn sort_by(mut self, sort_by: SortBy) -> Self {
+        self.sort_by = sort_by;
+        self
+    }
+
+    /// Iterates over installed browsers matching the filters, without duplicates and in the order set by
+    /// `sort_by`.
+    ///
+    /// Panics if a filter is invalid or the platform's browser sources cannot be read, see `try_all`.
     pub fn all(&self) -> IntoIter<Browser> {
-        let mut browsers = vec![];
-        let browser_pattern = Pattern::new(self.browser_type.as_str()).unwrap();
-        let version_pattern = Pattern::new(self.version.as_str()).unwrap();
-        let exclude_pattern = Pattern::new(self.exclude.as_str()).unwrap();
+        self.try_all().unwrap()
+    }
 
-        #[cfg(target_os = "macos")]
-        for (browser_type, bundle_id, version_string) in OSX_BROWSER_BUNDLE_LIST.iter() {
-            let result = Command::new("mdfind").arg(format!("kMDItemCFBundleIdentifier=='{bundle_id}'")).output();
-            if let Ok(output) = result {
-                browsers.extend(
-                    String::from_utf8(output.stdout)
-                        .unwrap()
-                        .lines()
-                        .map(String::from)
-                        .map(|application| extract_info_from_plist(application.as_str(), browser_type, version_string))
-                        .filter(|browser| {
-                            Self::matches_patterns(browser, &browser_pattern, &version_pattern, &exclude_pattern)
-                        })
-                        .collect::<Vec<Browser>>(),
-                )
-            }
-        }
+    /// Iterates over installed browsers matching the filters, failing if a filter is invalid or the
+    /// platform's browser sources cannot be read.
+    ///
+    /// Patterns are checked before scanning, so a filter such as `chrome[` fails fast with `Error::Pattern`, and
+    /// a version requirement such as `>=abc` with `Error::VersionReq`.
+    pub fn try_all(&self) -> Result<IntoIter<Browser>, Error> {
+        Ok(self.find(None)?.into_iter())
+    }
 
-        #[cfg(target_os = "windows")]
-        if let Ok(smi) = RegKey::predef(HKEY_LOCAL_MACHINE).open_subkey(r"Software\Clients\StartMenuInternet") {
-            for key in smi.enum_keys().map(|x| x.unwrap()) {
-                if let Ok(browser) = smi.open_subkey(&key) {
-                    let display_name: String = match browser.get_value("") {
-                        Ok(display_name) => display_name,
-                        Err(_) => key.to_string(),
-                    };
-
-                    if let Some(type_str) = WINDOWS_REGISTRY_BROWSER_NAMES.get(display_name.as_str()) {
-                        if let Ok(command) = smi.open_subkey(format!(r"{key}\shell\open\command")) {
-                            let mut path: String = match command.get_value("") {
-                                Ok(command) => command,
-                                Err(_) => continue,
-                            };
-                            path = match path.strip_prefix('"') {
-                                Some(string) => string.to_string(),
-                                None => path,
-                            };
-                            path = match path.strip_suffix('"') {
-                                Some(string) => string.to_string(),
-                                None => path,
-                            };
-                            let version = get_version_info(Path::new(path.as_str()));
-
-                            let browser = Browser { browser_type: type_str.to_string(), display_name, path, version };
-
-                            if Self::matches_patterns(&browser, &browser_pattern, &version_pattern, &exclude_pattern) {
-                                browsers.push(browser);
-                            }
-                        }
+    /// Scans and filters, reporting to `recorder` for `diagnose`.
+    fn find(&self, recorder: Option<&diagnose::Recorder>) -> Result<Vec<Browser>, Error> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("discovery").entered();
+        let browser_patterns = self.matchers(&self.browser_types)?;
+        let version_pattern = Matcher::new(self.version.as_str(), MatchSyntax::Glob, false)?;
+        let exclude_patterns = self.matchers(&self.excludes)?;
+        let version_req = match self.version_req.as_deref() {
+            Some(requirement) => Some(VersionReq::parse(requirement)?),
+            None => None,
+        };
+
+        let config = match self.config_path.as_ref().filter(|path| path.exists()) {
+            Some(path) => custom::load_config(path)?,
+            None => custom::Config::default(),
+        };
+
+        // the caches hold the results of all built-in backends with the default settings
+        let cacheable = self.builtin_backends && self.disabled_backends.is_empty() && !self.include_no_display;
+        let inventory_path = self.inventory.as_deref().filter(|_| cacheable);
+        let sources = inventory_path.map(|_| inventory::sources());
+        let inventory = inventory_path.filter(|_| !self.bypass_cache).and_then(Inventory::load);
+
+        let context = ScanContext {
+            concurrency: self.concurrency,
+            version_probe: self.version_probe,
+            process_probes: self.process_probes(),
+            probe_timeout: self.probe_timeout,
+            deadline: self.deadline.map(|budget| Instant::now() + budget),
+            bundles: &config.bundles,
+            include_no_display: self.include_no_display,
+            inventory: inventory.as_ref(),
+            recorder,
+        };
+        let cache_ttl = self.cache_ttl.filter(|_| cacheable);
+        let cache_key = cache::key(self.config_path.as_deref(), &config.bundles);
+        let cached = cache_ttl
+            .filter(|_| !self.bypass_cache)
+            .and_then(|ttl| cache::shared().load(&cache_key, ttl, self.disk_cache))
+            .or_else(|| inventory.as_ref()?.unchanged(&cache_key, sources.as_deref()?));
+        let mut browsers = match cached {
+            Some(browsers) => {
+                #[cfg(feature = "tracing")]
+                tracing::debug!(found = browsers.len(), "using cached scan");
+                browsers
+            }
+            None => {
+                let browsers = self.scan(&context)?;
+                // results without versions would be served to finders that want them
+                if self.version_probe && self.process_probes() && !context.expired() {
+                    if cache_ttl.is_some() {
+                        cache::shared().store(&cache_key, &browsers, self.disk_cache);
+                    }
+                    if let (Some(path), Some(sources)) = (inventory_path, sources) {
+                        Inventory::new(&cache_key, sources, &browsers).store(path);
                     }
                 }
+                browsers
             }
+        };
+
+        for backend in self.backends.iter().filter(|backend| self.backend_enabled(backend.as_ref())) {
+            backend::run(backend.as_ref(), &context, &mut browsers)?;
+        }
+
+        #[cfg(target_os = "macos")]
+        if self.package_managers {
+            homebrew::apply(&mut browsers, &config.bundles);
+        }
+        #[cfg(target_os = "windows")]
+        if self.package_managers {
+            package_managers::apply(&mut browsers);
+        }
+
+        // custom browsers are not cached since they differ between finders
+        let custom = [self.custom.clone(), config.browsers].concat();
+        browsers.extend(
+            parallel::map(custom, self.concurrency, |browser| {
+                browser.resolve(context.probe_timeout(), self.process_probes() && !context.expired())
+            })
+            .into_iter()
+            .flatten(),
+        );
+
+        let search_paths = [self.search_paths.clone(), search_paths::env_paths(), config.search_paths].concat();
+        browsers.extend(search_paths::detect(&search_paths, &context));
+
+        if self.automation_caches {
+            browsers.extend(automation::detect(&context));
         }
 
-        #[cfg(target_os = "linux")]
-        for path in Iter::new(default_paths()) {
-            if let Ok(bytes) = fs::read_to_string(&path) {
-                if let Ok(entry) = DesktopEntry::decode(&path, &bytes) {
-                    let base_name = path.as_path().file_stem().unwrap().to_str().unwrap();
-                    if LINUX_DESKTOP_ENTRY_NAME_LIST.contains_key(base_name) {
-                        let browser_type = LINUX_DESKTOP_ENTRY_NAME_LIST[base_name].to_string();
-                        let display_name = entry.name(None).unwrap().to_string();
-                        let mut path = entry.exec().unwrap().to_string();
-                        if path.to_lowercase().ends_with("%u") {
-                            path.truncate(path.len() - 3);
-                            path = path.trim().to_string();
-                        }
-                        let version = match Command::new("sh").arg("-c").arg(format!("{path} --version")).output() {
-                            Ok(output) => {
-                                let stdout = String::from_utf8(output.stdout).unwrap_or("".to_string());
-                                match VERSION_PATTERN.captures(stdout.as_str()) {
-                                    Some(capture) => capture.get(1).map_or("".to_string(), |m| m.as_str().to_string()),
-                                    None => "".to_string(),
-                                }
-                            }
-                            Err(_) => "".to_string(),
-                        };
-
-                        let browser = Browser { browser_type, display_name, path, version };
-
-                        if Self::matches_patterns(&browser, &browser_pattern, &version_pattern, &exclude_pattern) {
-                            browsers.push(browser);
-                        }
+        // versions of custom browsers are taken as registered
+        for browser in browsers.iter_mut().filter(|browser| browser.install_source != InstallSource::Custom) {
+            browser.normalize_version();
+        }
+
+        let mut browsers = order::dedup(browsers)
+            .into_iter()
+            .filter(|browser| {
+                let rejection = Self::rejection(
+                    browser,
+                    &browser_patterns,
+                    &version_pattern,
+                    version_req.as_ref(),
+                    &exclude_patterns,
+                )
+                .or_else(|| {
+                    (!self.engines.is_empty() && !self.engines.contains(&browser.engine()))
+                        .then_some("engine does not match")
+                })
+                .or_else(|| (!self.include_stale && browser.is_stale()).then_some("executable is missing"))
+                // reads the executable, so it goes last
+                .or_else(|| {
+                    (!self.architectures.is_empty()
+                        && !Architecture::any_of(&browser.path, &self.architectures))
+                    .then_some("architecture does not match")
+                });
+                if let Some(reason) = rejection {
+                    #[cfg(feature = "tracing")]
+                    tracing::debug!(browser_type = %browser.browser_type, path = %browser.path.display(), reason, "filtered out");
+                    if let Some(recorder) = recorder {
+                        recorder.filtered_out(browser, reason);
                     }
                 }
-            }
+                rejection.is_none()
+            })
+            .collect::<Vec<Browser>>();
+        order::sort(&mut browsers, self.sort_by);
+        Ok(browsers)
+    }
+
+    /// Returns the browsers matching the filters grouped by family, e.g. `chrome`, `chrome-beta` and
+    /// `chrome-canary` under `chrome`, see `Browser::family`. Each group keeps the order set with `sort_by`.
+    ///
+    /// Panics like `all`, see `try_grouped`.
+    pub fn grouped(&self) -> BTreeMap<String, Vec<Browser>> {
+        self.try_grouped().unwrap()
+    }
+
+    /// Returns the browsers matching the filters grouped by family, failing like `try_all`.
+    pub fn try_grouped(&self) -> Result<BTreeMap<String, Vec<Browser>>, Error> {
+        let mut groups: BTreeMap<String, Vec<Browser>> = BTreeMap::new();
+        for browser in self.try_all()? {
+            groups.entry(browser.family().to_string()).or_default().push(browser);
         }
+        Ok(groups)
+    }
 
-        browsers.into_iter()
+    /// Runs the enabled built-in backends without applying any filter.
+    fn scan(&self, context: &ScanContext) -> Result<Vec<Browser>, Error> {
+        let mut browsers = vec![];
+        let builtin = match self.builtin_backends {
+            true => backend::builtin(),
+            false => vec![],
+        };
+        for backend in builtin.iter().filter(|backend| self.backend_enabled(backend.as_ref())) {
+            backend::run(backend.as_ref(), context, &mut browsers)?;
+        }
+        Ok(browsers)
     }
 
-    fn matches_patterns(
+    fn backend_enabled(&self, backend: &dyn DetectionBackend) -> bool {
+        !self.disabled_backends.iter().any(|name| name == backend.name())
+    }
+
+    fn process_probes(&self) -> bool {
+        cfg!(feature = "process-probes") && self.process_probes
+    }
+
+    fn matchers<S: AsRef<str>>(&self, patterns: &[S]) -> Result<Vec<Matcher>, Error> {
+        patterns
+            .iter()
+            .map(|p| Matcher::new(p.as_ref(), self.match_syntax, self.case_sensitive))
+            .collect::<Result<_, _>>()
+            .map_err(Error::Pattern)
+    }
+
+    /// Returns why the patterns leave out `browser`, `None` if it matches them.
+    fn rejection(
         browser: &Browser,
-        browser_pattern: &Pattern,
-        version_pattern: &Pattern,
-        exclude_pattern: &Pattern,
-    ) -> bool {
-        let case_insensitive = MatchOptions { case_sensitive: false, ..MatchOptions::new() };
-
-        !exclude_pattern.matches_with(browser.browser_type.as_str(), case_insensitive)
-            && version_pattern.matches_with(browser.version.as_str(), case_insensitive)
-            && (browser_pattern.matches_with(browser.browser_type.as_str(), case_insensitive)
-                | browser_pattern.matches_with(browser.display_name.as_str(), case_insensitive))
-    }
-
-    pub fn launch(&self, args: &[String]) -> (Child, Browser) {
-        let browser = self.all().next().unwrap();
-
-        match browser.browser_type.as_str() {
-            #[cfg(target_os = "macos")]
-            "safari" => {
-                let mut arguments = vec![
-                    "--wait-apps".to_owned(),
-                    "--new".to_owned(),
-                    "--fresh".to_owned(),
-                    "-a".to_owned(),
-                    browser.path.to_owned(),
-                ];
-                arguments.extend_from_slice(args);
-
-                return (Command::new("open").args(arguments).spawn().unwrap(), browser);
-            }
-            _ => {
-                #[cfg(any(target_os = "macos", target_os = "windows"))]
-                return (Command::new(&browser.path).args(args).spawn().unwrap(), browser);
-                #[cfg(target_os = "linux")]
-                return (
-                    Command::new("sh").arg("-c").arg(format!("{} {}", browser.path, args.join(" "))).spawn().unwrap(),
-                    browser,
-                );
-            }
+        browser_patterns: &[Matcher],
+        version_pattern: &Matcher,
+        version_req: Option<&VersionReq>,
+        exclude_patterns: &[Matcher],
+    ) -> Option<&'static str> {
+        if exclude_patterns.iter().any(|pattern| pattern.matches(browser.browser_type.as_str())) {
+            Some("type is excluded")
+        } else if !version_pattern.matches(browser.version.as_str()) {
+            Some("version does not match")
+        } else if !version_req.is_none_or(|req| browser.parsed_version().is_some_and(|version| req.matches(&version))) {
+            Some("version does not meet the requirement")
+        } else if !browser_patterns.is_empty()
+            && !browser_patterns.iter().any(|pattern| {
+                pattern.matches(browser.browser_type.as_str()) | pattern.matches(browser.display_name.as_str())
+            })
+        {
+            Some("type does not match")
+        } else {
+            None
         }
     }
+
+    /// Launches the first browser matching the filters.
+    ///
+    /// Without a type filter, the browser named by the `BROWSER` environment variable is preferred unless
+    /// `ignore_browser_env` is set.
+    pub fn launch(&self, options: &LaunchOptions) -> Result<(LaunchedBrowser, Browser), LaunchError> {
+        let from_env = match self.browser_env && self.browser_types.is_empty() {
+            true => default_browser::from_env(self),
+            false => None,
+        };
+        let browser = match from_env {
+            Some(browser) => browser,
+            None => self.try_all()?.next().ok_or(LaunchError::NotFound)?,
+        };
+        launch::spawn(browser, options)
+    }
+
+    /// Launches the first browser like `launch` and opens `url` in it, in addition to any URLs in `options`.
+    ///
+    /// The URL is validated and normalized first: local paths become `file://` URIs and bare hosts get
+    /// `https://`. Only `http`, `https` and `about` URLs are opened unless more schemes are allowed with
+    /// `LaunchOptions::allow_scheme`, so that e.g. a `javascript:` or `file:` URL from untrusted input is
+    /// rejected with `LaunchError::InvalidUrl`.
+    pub fn launch_url(&self, url: &str, options: &LaunchOptions) -> Result<(LaunchedBrowser, Browser), LaunchError> {
+        let url = urls::normalize(url, options.allowed_schemes())?;
+        self.launch(&options.clone().url(url))
+    }
+
+    /// Launches the first browser like `launch` and opens the local file at `path` in it, e.g. a PDF or an
+    /// HTML report, in addition to any URLs in `options`.
+    ///
+    /// The path may be relative and is resolved to a `file://` URI with spaces, non-ASCII characters and
+    /// Windows UNC paths encoded, so the browser doesn't take it for a search or a URL. A missing file is
+    /// rejected with `LaunchError::InvalidUrl`. The `file` scheme doesn't need `LaunchOptions::allow_scheme`.
+    pub fn launch_file<P: AsRef<Path>>(
+        &self,
+        path: P,
+        options: &LaunchOptions,
+    ) -> Result<(LaunchedBrowser, Browser), LaunchError> {
+        let url = urls::file_url(path.as_ref())?;
+        self.launch(&options.clone().url(url))
+    }
+
+    /// Returns the installed browser that comes first in `preferences`, a list of browser type globs
+    /// such as `["chrome", "msedge", "firefox*"]`.
+    ///
+    /// Only browsers matching the other filters are considered. Panics like `all`, see `try_find_preferred`.
+    pub fn find_preferred(&self, preferences: &[&str]) -> Option<Browser> {
+        self.try_find_preferred(preferences).unwrap()
+    }
+
+    /// Returns the installed browser that comes first in `preferences`, failing if a filter or
+    /// preference is invalid or the platform's browser sources cannot be read.
+    pub fn try_find_preferred(&self, preferences: &[&str]) -> Result<Option<Browser>, Error> {
+        let preferences = self.matchers(preferences)?;
+        Ok(Self::first_preferred(self.try_all()?.collect(), &preferences))
+    }
+
+    /// Launches the installed browser that comes first in `preferences`, see `find_preferred`.
+    pub fn launch_preferred(
+        &self,
+        preferences: &[&str],
+        options: &LaunchOptions,
+    ) -> Result<(LaunchedBrowser, Browser), LaunchError> {
+        let browser = self.try_find_preferred(preferences)?.ok_or(LaunchError::NotFound)?;
+        launch::spawn(browser, options)
+    }
+
+    fn first_preferred(browsers: Vec<Browser>, preferences: &[Matcher]) -> Option<Browser> {
+        preferences
+            .iter()
+            .find_map(|preference| browsers.iter().find(|browser| preference.matches(browser.browser_type.as_str())))
+            .cloned()
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::BrowserFinder;
+    use crate::matcher::Matcher;
+    use crate::{
+        Browser, BrowserFinder, DetectionBackend, Error, InstallSource, MatchSyntax, MockBackend, ScanContext,
+        VersionReq,
+    };
+    use std::env;
+    use std::path::{Path, PathBuf};
+    use std::time::Duration;
 
     #[test]
     fn test_all() {
+        let backend = MockBackend::from_json(
+            r#"[
+                {"browserType": "chrome", "path": "/opt/chrome/chrome", "displayName": "Chrome", "version": "120.0"},
+                {"browserType": "firefox", "path": "/opt/firefox", "displayName": "Firefox", "version": "121.0"},
+                {"browserType": "chrome", "path": "/opt/chrome/chrome", "displayName": "Chrome", "version": "120.0"}
+            ]"#,
+        )
+        .unwrap();
+        let browsers = BrowserFinder::new()
+            .without_builtin_backends()
+            .with_backend(backend)
+            .include_stale(true)
+            .all()
+            .map(|browser| browser.browser_type)
+            .collect::<Vec<String>>();
+
+        assert_eq!(browsers, ["chrome", "firefox"]);
+        assert!(MockBackend::from_json(r#"[{"browserType": "chrome"}]"#).is_err());
+    }
+
+    #[test]
+    #[ignore = "depends on the browsers installed on the machine"]
+    fn test_installed() {
         let browsers = BrowserFinder::new().all().map(|browser| browser.browser_type).collect::<Vec<String>>();
 
         assert!(browsers.contains(&"chrome".to_string()));
@@ -374,4 +1360,259 @@ mod tests {
         #[cfg(target_os = "windows")]
         assert!(browsers.contains(&"internet-explorer".to_string()));
     }
+
+    #[test]
+    fn test_rejection() {
+        let browser = |browser_type: &str| Browser {
+            version: "120.0".to_string(),
+            ..Browser::test_fixture(browser_type, PathBuf::new())
+        };
+        let patterns = |patterns: &[&str]| {
+            patterns.iter().map(|p| Matcher::new(p, MatchSyntax::Glob, false).unwrap()).collect::<Vec<_>>()
+        };
+        let rejection = |browser_type: &str, include: &[&str], exclude: &[&str]| {
+            BrowserFinder::rejection(
+                &browser(browser_type),
+                &patterns(include),
+                &Matcher::new("*", MatchSyntax::Glob, false).unwrap(),
+                None,
+                &patterns(exclude),
+            )
+        };
+        let matches = |browser_type: &str, include: &[&str], exclude: &[&str]| {
+            rejection(browser_type, include, exclude).is_none()
+        };
+
+        assert!(matches("chrome", &[], &[]));
+        assert!(matches("chrome-beta", &["chrome*", "firefox"], &[]));
+        assert!(matches("firefox", &["chrome*", "firefox"], &[]));
+        assert!(!matches("firefox-nightly", &["chrome*", "firefox"], &[]));
+        assert!(!matches("msedge-dev", &[], &["msedge*", "opera*"]));
+        assert!(!matches("chrome-beta", &["chrome*"], &["*-beta"]));
+        assert!(matches("vivaldi", &[], &["msedge*", "opera*"]));
+        assert_eq!(rejection("chrome-beta", &["chrome*"], &["*-beta"]), Some("type is excluded"));
+        assert_eq!(rejection("firefox", &["chrome*"], &[]), Some("type does not match"));
+        let version_req = VersionReq::parse(">=121").unwrap();
+        assert_eq!(
+            BrowserFinder::rejection(
+                &browser("chrome"),
+                &[],
+                &Matcher::new("12*", MatchSyntax::Glob, false).unwrap(),
+                Some(&version_req),
+                &[]
+            ),
+            Some("version does not meet the requirement")
+        );
+    }
+
+    #[test]
+    fn test_custom_backend() {
+        struct FakeBackend;
+
+        impl DetectionBackend for FakeBackend {
+            fn name(&self) -> &str {
+                "fake"
+            }
+
+            fn detect(&self, _context: &ScanContext, sink: &mut Vec<Browser>) -> Result<(), Error> {
+                for browser_type in ["chrome", "firefox"] {
+                    sink.push(Browser {
+                        version: "120.0".to_string(),
+                        install_source: InstallSource::Custom,
+                        ..Browser::test_fixture(browser_type, format!("/opt/{browser_type}/{browser_type}"))
+                    });
+                }
+                Ok(())
+            }
+        }
+
+        let finder = || BrowserFinder::new().without_builtin_backends().with_backend(FakeBackend).include_stale(true);
+        let browser_types =
+            |finder: BrowserFinder| finder.all().map(|browser| browser.browser_type).collect::<Vec<_>>();
+
+        assert_eq!(browser_types(finder()), ["chrome", "firefox"]);
+        assert_eq!(browser_types(finder().with_type("fire*".to_string())), ["firefox"]);
+        assert!(browser_types(finder().disable_backend("fake")).is_empty());
+    }
+
+    #[test]
+    fn test_include_stale() {
+        let browser = |browser_type: &str, path: &Path| Browser {
+            version: "120.0".to_string(),
+            install_source: InstallSource::Custom,
+            ..Browser::test_fixture(browser_type, path.to_path_buf())
+        };
+        let installed = browser("chrome", &env::current_exe().unwrap());
+        let uninstalled = browser("firefox", &env::temp_dir().join("rsbrowsers-uninstalled/firefox"));
+        let finder = BrowserFinder::new()
+            .without_builtin_backends()
+            .with_backend(MockBackend::new(vec![installed, uninstalled]));
+
+        let browser_types =
+            |finder: BrowserFinder| finder.all().map(|browser| browser.browser_type).collect::<Vec<_>>();
+
+        assert_eq!(browser_types(finder.clone()), ["chrome"]);
+        assert_eq!(browser_types(finder.clone().include_stale(true)), ["chrome", "firefox"]);
+        assert_eq!(finder.diagnose().unwrap().filtered_out[0].reason, "executable is missing");
+    }
+
+    #[test]
+    fn test_with_deadline() {
+        let backend = MockBackend::from_json(&format!(
+            r#"[{{"browserType": "chrome", "path": {:?}, "displayName": "Chrome", "version": "120.0"}}]"#,
+            env::current_exe().unwrap()
+        ))
+        .unwrap();
+        let finder = BrowserFinder::new().without_builtin_backends().with_backend(backend);
+
+        assert_eq!(finder.clone().with_deadline(Duration::from_secs(60)).all().count(), 1);
+        // backends that would start after the deadline are skipped and reported
+        let finder = finder.with_deadline(Duration::ZERO);
+        assert_eq!(finder.all().count(), 0);
+        let diagnostics = finder.diagnose().unwrap();
+        assert_eq!(diagnostics.backends[0].error.as_deref(), Some("skipped after the deadline"));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_search_path_probe_timeout() {
+        use std::fs;
+        use std::os::unix::fs::PermissionsExt;
+        use std::time::Instant;
+
+        let root = env::temp_dir().join(format!("rsbrowsers-hung-{}", std::process::id()));
+        let executable = root.join("firefox");
+        fs::create_dir_all(&root).unwrap();
+        fs::write(&executable, "#!/bin/sh\nsleep 30\n").unwrap();
+        fs::set_permissions(&executable, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let started = Instant::now();
+        let finder = BrowserFinder::new().without_builtin_backends().with_search_path(root.clone());
+        let found = finder.with_probe_timeout(Duration::from_millis(100)).all().count();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(found, 0);
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_non_unicode_path() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let path = Path::new(OsStr::from_bytes(b"/opt/br\xf6wser/chrome"));
+        let browser = Browser {
+            version: "120.0".to_string(),
+            source_path: Some(path.to_path_buf()),
+            ..Browser::test_fixture("chrome", path.to_path_buf())
+        };
+        let finder = BrowserFinder::new().without_builtin_backends().with_backend(MockBackend::new(vec![browser]));
+
+        let found = finder.include_stale(true).all().next().unwrap();
+        assert_eq!(found.path.as_path(), path);
+        let json = serde_json::to_value(&found).unwrap();
+        assert_eq!((&json["path"], &json["sourcePath"]), (&"/opt/br\u{fffd}wser/chrome".into(), &json["path"]));
+    }
+
+    #[test]
+    fn test_invalid_pattern() {
+        let error = |finder: BrowserFinder| match finder.try_all() {
+            Err(Error::Pattern(error)) => error.pattern,
+            _ => panic!("expected a pattern error"),
+        };
+
+        assert_eq!(error(BrowserFinder::new().with_type("chrome[".to_string())), "chrome[");
+        assert_eq!(error(BrowserFinder::new().exclude_type("[!".to_string())), "[!");
+        assert_eq!(error(BrowserFinder::new().with_version("120[".to_string())), "120[");
+        assert!(matches!(BrowserFinder::new().with_version_req(">=abc").try_all(), Err(Error::VersionReq(_))));
+    }
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn test_order_copies() {
+        let home = std::env::var("HOME").unwrap();
+        let copies = crate::order_copies(
+            [
+                "/Volumes/Backup/Applications/Firefox.app".to_string(),
+                format!("{home}/Downloads/Firefox.app"),
+                "/Applications/Firefox.app".to_string(),
+                format!("{home}/Applications/Firefox.app"),
+                "/Applications/Firefox.app".to_string(),
+            ]
+            .iter()
+            .map(std::path::PathBuf::from)
+            .collect(),
+        );
+
+        assert_eq!(
+            copies,
+            [
+                "/Applications/Firefox.app".to_string(),
+                format!("{home}/Applications/Firefox.app"),
+                format!("{home}/Downloads/Firefox.app"),
+                "/Volumes/Backup/Applications/Firefox.app".to_string(),
+            ]
+            .iter()
+            .map(std::path::PathBuf::from)
+            .collect::<Vec<_>>()
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_generic_browser_type() {
+        assert_eq!(crate::generic_browser_type("firefox-esr"), "firefox-esr");
+        assert_eq!(crate::generic_browser_type("org.gnome.Epiphany"), "epiphany");
+    }
+
+    #[cfg(any(target_os = "windows", target_os = "linux"))]
+    #[test]
+    fn test_windows_browser_type() {
+        let browser_type = |key, path, display_name| crate::windows_browser_type(key, path, display_name);
+        let firefox = r"C:\Program Files\Mozilla Firefox\firefox.exe";
+
+        assert_eq!(browser_type("Firefox-308046B0AF4A39CB", firefox, "Mozilla Firefox"), Some("firefox".to_string()));
+        assert_eq!(
+            browser_type("Firefox-308046B0AF4A39CB", firefox, "Mozilla Firefox ESR"),
+            Some("firefox-esr".to_string())
+        );
+        assert_eq!(
+            browser_type("Mozilla Firefox 115.0.1 (x64 en-US)", firefox, "Mozilla Firefox 115.0.1 (x64 en-US)"),
+            Some("firefox".to_string())
+        );
+        assert_eq!(
+            browser_type("Firefox-6F193CCC56814779", r"C:\Program Files\Firefox Nightly\firefox.exe", "Nightly"),
+            Some("firefox-nightly".to_string())
+        );
+        assert_eq!(
+            browser_type("Microsoft Edge Beta", r"C:\Edge Beta\Application\msedge.exe", "Microsoft Edge Beta"),
+            Some("msedge-beta".to_string())
+        );
+        // a localized display name doesn't matter
+        assert_eq!(
+            browser_type("IEXPLORE.EXE", r"C:\Program Files\Internet Explorer\iexplore.exe", "Internet-Explorer"),
+            Some("msie".to_string())
+        );
+        assert_eq!(
+            browser_type("Opera Stable", r"C:\Users\me\AppData\Local\Programs\Opera\launcher.exe", "Opera"),
+            Some("opera".to_string())
+        );
+        assert_eq!(browser_type("Thunderbird", r"C:\Mozilla Thunderbird\thunderbird.exe", "Thunderbird"), None);
+    }
+
+    #[test]
+    fn test_first_preferred() {
+        let browser = |browser_type: &str| Browser::test_fixture(browser_type, PathBuf::new());
+        let browsers = vec![browser("firefox-nightly"), browser("msedge"), browser("chrome-beta")];
+        let preferred = |preferences: &[&str]| {
+            let preferences =
+                preferences.iter().map(|p| Matcher::new(p, MatchSyntax::Glob, false).unwrap()).collect::<Vec<_>>();
+            BrowserFinder::first_preferred(browsers.clone(), &preferences).map(|browser| browser.browser_type)
+        };
+
+        assert_eq!(preferred(&["chrome", "msedge", "firefox*"]), Some("msedge".to_string()));
+        assert_eq!(preferred(&["chrome*", "msedge"]), Some("chrome-beta".to_string()));
+        assert_eq!(preferred(&["safari"]), None);
+    }
 }
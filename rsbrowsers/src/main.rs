@@ -0,0 +1,212 @@
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use rsbrowsers::{Browser, BrowserFinder, Browsers, LaunchOptions, default_browser};
+use std::io;
+use std::process::ExitCode;
+
+/// Find and launch browsers.
+#[derive(Parser)]
+#[command(name = "rsbrowsers", version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Lists installed browsers.
+    List {
+        #[command(flatten)]
+        filter: Filter,
+        #[arg(long, value_enum, default_value_t = Format::Table)]
+        format: Format,
+        /// Also lists browsers whose executable is gone
+        #[arg(long)]
+        include_stale: bool,
+        /// Also lists browsers from desktop entries marked NoDisplay (Linux)
+        #[arg(long)]
+        include_no_display: bool,
+        /// Reads Homebrew, winget, Chocolatey and Scoop records to tell how browsers were installed
+        #[arg(long)]
+        package_managers: bool,
+    },
+    /// Shows the first installed browser of the given type.
+    Get {
+        browser_type: String,
+        /// Version glob (e.g. "120*") or requirement (e.g. ">=120")
+        #[arg(long)]
+        version: Option<String>,
+        #[arg(long, value_enum, default_value_t = Format::Table)]
+        format: Format,
+    },
+    /// Launches a browser, optionally opening URLs.
+    Launch {
+        browser_type: String,
+        urls: Vec<String>,
+        /// Version glob (e.g. "120*") or requirement (e.g. ">=120")
+        #[arg(long)]
+        version: Option<String>,
+        /// Opens a private window
+        #[arg(long)]
+        incognito: bool,
+        /// Profile name to launch with
+        #[arg(long)]
+        profile: Option<String>,
+    },
+    /// Shows the system default browser.
+    Default {
+        #[arg(long, value_enum, default_value_t = Format::Table)]
+        format: Format,
+    },
+    /// Prints what a scan examined and why browsers were skipped, as JSON.
+    Diagnose {
+        #[command(flatten)]
+        filter: Filter,
+    },
+}
+
+#[derive(Args)]
+struct Filter {
+    /// Browser type or display name glob (e.g. "chrome*"), can be repeated
+    #[arg(long = "type")]
+    browser_type: Vec<String>,
+    /// Version glob (e.g. "120*") or requirement (e.g. ">=120")
+    #[arg(long)]
+    version: Option<String>,
+    /// Browser type glob to exclude, can be repeated
+    #[arg(long)]
+    exclude: Vec<String>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Table,
+    Json,
+    Yaml,
+    Csv,
+}
+
+fn finder(browser_types: Vec<String>, version: Option<String>, excludes: Vec<String>) -> BrowserFinder {
+    let mut finder = BrowserFinder::new().with_types(browser_types).exclude_types(excludes);
+    if let Some(version) = version {
+        // requirements start with an operator, anything else is treated as a glob
+        finder = match version.starts_with(['<', '>', '=', '^', '~']) {
+            true => finder.with_version_req(&version),
+            false => finder.with_version(version),
+        };
+    }
+    finder
+}
+
+fn print(browsers: &[Browser], format: Format) -> ExitCode {
+    match output(browsers, format) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("{error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn output(browsers: &[Browser], format: Format) -> io::Result<()> {
+    match format {
+        Format::Table => {
+            let headers = ["TYPE", "NAME", "VERSION", "PATH"];
+            let paths: Vec<_> = browsers.iter().map(|b| b.path.to_string_lossy()).collect();
+            let rows: Vec<[&str; 4]> = browsers
+                .iter()
+                .zip(&paths)
+                .map(|(b, path)| [b.browser_type.as_str(), b.display_name.as_str(), b.version.as_str(), path.as_ref()])
+                .collect();
+            let widths: Vec<usize> =
+                (0..3).map(|i| rows.iter().map(|row| row[i].len()).chain([headers[i].len()]).max().unwrap()).collect();
+
+            for row in [headers].iter().chain(rows.iter()) {
+                println!(
+                    "{:<w0$}  {:<w1$}  {:<w2$}  {}",
+                    row[0],
+                    row[1],
+                    row[2],
+                    row[3],
+                    w0 = widths[0],
+                    w1 = widths[1],
+                    w2 = widths[2]
+                );
+            }
+        }
+        Format::Json => println!("{}", serde_json::to_string_pretty(browsers)?),
+        Format::Yaml => print!("{}", serde_yaml::to_string(browsers).map_err(io::Error::other)?),
+        Format::Csv => print!("{}", Browsers::from(browsers.to_vec()).to_csv()?),
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match Cli::parse().command {
+        Commands::List { filter, format, include_stale, include_no_display, package_managers } => {
+            let mut finder = finder(filter.browser_type, filter.version, filter.exclude)
+                .include_stale(include_stale)
+                .include_no_display(include_no_display);
+            if package_managers {
+                finder = finder.with_package_managers();
+            }
+            match finder.try_all() {
+                Ok(browsers) => return print(&browsers.collect::<Vec<Browser>>(), format),
+                Err(error) => {
+                    eprintln!("{error}");
+                    return ExitCode::FAILURE;
+                }
+            }
+        }
+        Commands::Get { browser_type, version, format } => {
+            match finder(vec![browser_type], version, vec![]).try_all().map(|mut browsers| browsers.next()) {
+                Ok(Some(browser)) => return print(&[browser], format),
+                Ok(None) => {
+                    eprintln!("Browser not found.");
+                    return ExitCode::FAILURE;
+                }
+                Err(error) => {
+                    eprintln!("{error}");
+                    return ExitCode::FAILURE;
+                }
+            }
+        }
+        Commands::Launch { browser_type, urls, version, incognito, profile } => {
+            let mut options = LaunchOptions::new();
+            for url in urls {
+                options = options.url(url);
+            }
+            if incognito {
+                options = options.incognito();
+            }
+            if let Some(profile) = profile {
+                options = options.profile(profile);
+            }
+
+            match finder(vec![browser_type], version, vec![]).launch(&options) {
+                Ok((launched, browser)) => {
+                    println!("Launched {} {} (pid {}).", browser.display_name, browser.version, launched.id())
+                }
+                Err(error) => {
+                    eprintln!("{error}");
+                    return ExitCode::FAILURE;
+                }
+            }
+        }
+        Commands::Default { format } => match default_browser() {
+            Some(browser) => return print(&[browser], format),
+            None => {
+                eprintln!("Default browser not found.");
+                return ExitCode::FAILURE;
+            }
+        },
+        Commands::Diagnose { filter } => match finder(filter.browser_type, filter.version, filter.exclude).diagnose() {
+            Ok(diagnostics) => println!("{}", serde_json::to_string_pretty(&diagnostics).unwrap()),
+            Err(error) => {
+                eprintln!("{error}");
+                return ExitCode::FAILURE;
+            }
+        },
+    }
+
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,81 @@
+use crate::PatternError;
+use glob::{MatchOptions, Pattern};
+
+/// How browser type filters are written, see `BrowserFinder::match_syntax`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum MatchSyntax {
+    /// Glob patterns such as `chrome*`.
+    #[default]
+    Glob,
+    /// Exact names, so `pale-moon` matches nothing else and `*` or `[` need no escaping.
+    Literal,
+    /// Regular expressions, which must match the whole name, e.g. `(chrome|msedge)-(beta|dev)`.
+    #[cfg(feature = "regex")]
+    Regex,
+}
+
+/// A compiled filter.
+pub(crate) enum Matcher {
+    Glob(Pattern, MatchOptions),
+    Literal(String, bool),
+    #[cfg(feature = "regex")]
+    Regex(regex::Regex),
+}
+
+impl Matcher {
+    pub(crate) fn new(pattern: &str, syntax: MatchSyntax, case_sensitive: bool) -> Result<Self, PatternError> {
+        let error = |message: String| PatternError { pattern: pattern.to_owned(), message };
+        match syntax {
+            MatchSyntax::Glob => Ok(Matcher::Glob(
+                Pattern::new(pattern).map_err(|e| error(e.to_string()))?,
+                MatchOptions { case_sensitive, ..MatchOptions::new() },
+            )),
+            MatchSyntax::Literal => Ok(Matcher::Literal(pattern.to_owned(), case_sensitive)),
+            #[cfg(feature = "regex")]
+            MatchSyntax::Regex => Ok(Matcher::Regex(
+                regex::RegexBuilder::new(&format!("^(?:{pattern})$"))
+                    .case_insensitive(!case_sensitive)
+                    .build()
+                    .map_err(|e| error(e.to_string()))?,
+            )),
+        }
+    }
+
+    pub(crate) fn matches(&self, text: &str) -> bool {
+        match self {
+            Matcher::Glob(pattern, options) => pattern.matches_with(text, *options),
+            Matcher::Literal(literal, true) => literal == text,
+            Matcher::Literal(literal, false) => literal.to_lowercase() == text.to_lowercase(),
+            #[cfg(feature = "regex")]
+            Matcher::Regex(regex) => regex.is_match(text),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::MatchSyntax;
+    use crate::matcher::Matcher;
+
+    #[test]
+    fn test_matcher() {
+        let matches = |pattern, syntax, case_sensitive, text| {
+            Matcher::new(pattern, syntax, case_sensitive).unwrap().matches(text)
+        };
+
+        assert!(matches("Chrome*", MatchSyntax::Glob, false, "chrome-beta"));
+        assert!(!matches("Chrome*", MatchSyntax::Glob, true, "chrome-beta"));
+        assert!(matches("pale-moon", MatchSyntax::Literal, false, "Pale-Moon"));
+        assert!(!matches("chrome*", MatchSyntax::Literal, false, "chrome-beta"));
+        assert!(matches("chrome[1]", MatchSyntax::Literal, true, "chrome[1]"));
+        assert_eq!(Matcher::new("chrome[", MatchSyntax::Glob, false).err().unwrap().pattern, "chrome[");
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn test_regex_matcher() {
+        let matcher = Matcher::new("(chrome|msedge)-(beta|dev)", MatchSyntax::Regex, false).unwrap();
+        assert!(matcher.matches("MSEdge-dev"));
+        assert!(!matcher.matches("chrome-beta-x"));
+    }
+}
@@ -0,0 +1,73 @@
+use crate::{Browser, InstallSource};
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use winreg::RegKey;
+use winreg::enums::HKEY_CURRENT_USER;
+
+pub(crate) const PACKAGES: &str =
+    r"Software\Classes\Local Settings\Software\Microsoft\Windows\CurrentVersion\AppModel\Repository\Packages";
+
+const WINDOWS_STORE_PACKAGE_LIST: &[(&str, &str)] = &[
+    // package name, browser name
+    ("Mozilla.Firefox", "firefox"),
+    ("Mozilla.FirefoxBeta", "firefox-beta"),
+    ("Mozilla.FirefoxNightly", "firefox-nightly"),
+    ("TheBrowserCompany.Arc", "arc"),
+];
+
+/// Finds browsers installed as Microsoft Store (MSIX) packages for the current user.
+pub(crate) fn detect() -> Vec<Browser> {
+    let Ok(packages) = RegKey::predef(HKEY_CURRENT_USER).open_subkey(PACKAGES) else {
+        return vec![];
+    };
+
+    let mut browsers = vec![];
+    for full_name in packages.enum_keys().filter_map(Result::ok) {
+        // e.g. "Mozilla.Firefox_125.0.3.0_x64__n80bbvh6b1yt2"
+        let mut parts = full_name.split('_');
+        let (Some(name), Some(version)) = (parts.next(), parts.next()) else { continue };
+        let Some((_, browser_type)) = WINDOWS_STORE_PACKAGE_LIST.iter().find(|(package, _)| *package == name) else {
+            continue;
+        };
+        let Ok(package) = packages.open_subkey(&full_name) else { continue };
+        let Ok(root) = package.get_value::<OsString, _>("PackageRootFolder") else { continue };
+        let Some(executable) = manifest_executable(Path::new(&root)) else { continue };
+
+        let display_name: String = package.get_value("DisplayName").unwrap_or(name.to_string());
+        // manifests often point the display name at a resource string
+        let display_name = if display_name.starts_with("@{") { name.to_string() } else { display_name };
+
+        browsers.push(Browser {
+            browser_type: browser_type.to_string(),
+            path: launch_path(Path::new(&root), &executable),
+            exec: None,
+            args: vec![],
+            display_name,
+            version: version.to_string(),
+            raw_version: None,
+            install_source: InstallSource::MsStore,
+            source_id: Some(full_name.clone()),
+            source_path: Some(PathBuf::from(&root)),
+            package: None,
+        });
+    }
+
+    browsers
+}
+
+/// Reads the first application's executable, relative to the package root, from `AppxManifest.xml`.
+fn manifest_executable(root: &Path) -> Option<String> {
+    let manifest = fs::read_to_string(root.join("AppxManifest.xml")).ok()?;
+    let (_, rest) = manifest.split_once("Executable=\"")?;
+    rest.split_once('"').map(|(executable, _)| executable.to_string())
+}
+
+/// Prefers the app execution alias since the package folder under `WindowsApps` is not accessible to users.
+fn launch_path(root: &Path, executable: &str) -> PathBuf {
+    let file_name = Path::new(executable).file_name().unwrap_or_default();
+    dirs::data_local_dir()
+        .map(|dir| dir.join(r"Microsoft\WindowsApps").join(file_name))
+        .filter(|alias| alias.exists())
+        .unwrap_or(root.join(executable))
+}
@@ -0,0 +1,188 @@
+//! Installs native messaging host manifests, which let browser extensions talk to a local program, see
+//! <https://developer.chrome.com/docs/extensions/develop/concepts/native-messaging> and
+//! <https://developer.mozilla.org/en-US/docs/Mozilla/Add-ons/WebExtensions/Native_messaging>.
+//!
+//! Manifests are installed for the current user. On Windows the manifest is written under the local app
+//! data directory and registered in the browser's registry key.
+
+use crate::{Browser, Engine};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+#[cfg(target_os = "windows")]
+use {winreg::RegKey, winreg::enums::HKEY_CURRENT_USER};
+
+#[cfg(target_os = "windows")]
+const REGISTRY_KEY_LIST: &[(&str, &str)] = &[
+    // browser name prefix, registry key under HKEY_CURRENT_USER (other Chromium browsers read Chrome's)
+    ("brave", r"Software\BraveSoftware\Brave-Browser\NativeMessagingHosts"),
+    ("chromium", r"Software\Chromium\NativeMessagingHosts"),
+    ("msedge", r"Software\Microsoft\Edge\NativeMessagingHosts"),
+];
+
+/// A native messaging host to register with a browser.
+#[derive(Clone, Debug)]
+pub struct HostManifest {
+    /// The host name extensions connect to, e.g. `com.example.host`. Only lowercase letters, digits,
+    /// underscores and dots are allowed.
+    pub name: String,
+    pub description: String,
+    /// The absolute path of the host executable.
+    pub path: PathBuf,
+    /// Chromium extensions allowed to connect, e.g. `chrome-extension://knldjmfmopnpolahpmmgbagdohdnhkik/`.
+    pub allowed_origins: Vec<String>,
+    /// Firefox extension IDs allowed to connect, e.g. `host@example.com`.
+    pub allowed_extensions: Vec<String>,
+}
+
+impl HostManifest {
+    /// Returns the manifest JSON in the format of the browser's engine.
+    pub fn to_json(&self, engine: Engine) -> io::Result<String> {
+        let allowed = match engine {
+            Engine::Blink => ("allowed_origins", &self.allowed_origins),
+            Engine::Gecko => ("allowed_extensions", &self.allowed_extensions),
+            engine => return Err(unsupported(format!("{engine:?} browsers"))),
+        };
+        let manifest = serde_json::json!({
+            "name": self.name,
+            "description": self.description,
+            "path": self.path,
+            "type": "stdio",
+            allowed.0: allowed.1,
+        });
+        Ok(serde_json::to_string_pretty(&manifest)?)
+    }
+}
+
+/// Writes the manifest where `browser` looks for it, returning the manifest file.
+pub fn install(browser: &Browser, manifest: &HostManifest) -> io::Result<PathBuf> {
+    validate_name(&manifest.name)?;
+    let json = manifest.to_json(browser.engine())?;
+    let path = manifest_path(browser, &manifest.name)?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, json)?;
+
+    #[cfg(target_os = "windows")]
+    {
+        let (key, _) = RegKey::predef(HKEY_CURRENT_USER).create_subkey(registry_key(browser, &manifest.name))?;
+        key.set_value("", &path.to_string_lossy().to_string())?;
+    }
+
+    Ok(path)
+}
+
+/// Removes a manifest installed by `install`. Succeeds if it is not installed.
+pub fn uninstall(browser: &Browser, name: &str) -> io::Result<()> {
+    validate_name(name)?;
+
+    #[cfg(target_os = "windows")]
+    match RegKey::predef(HKEY_CURRENT_USER).delete_subkey_all(registry_key(browser, name)) {
+        Err(error) if error.kind() != io::ErrorKind::NotFound => return Err(error),
+        _ => {}
+    }
+
+    match fs::remove_file(manifest_path(browser, name)?) {
+        Err(error) if error.kind() != io::ErrorKind::NotFound => Err(error),
+        _ => Ok(()),
+    }
+}
+
+/// Returns where the manifest file for host `name` goes, e.g.
+/// `~/.config/google-chrome/NativeMessagingHosts/com.example.host.json`.
+pub fn manifest_path(browser: &Browser, name: &str) -> io::Result<PathBuf> {
+    let file_name = format!("{name}.json");
+    let missing = || unsupported(format!("{}, whose data directory is unknown", browser.browser_type));
+
+    #[cfg(target_os = "windows")]
+    return match browser.engine() {
+        Engine::Blink | Engine::Gecko => Ok(dirs::data_local_dir()
+            .ok_or_else(missing)?
+            .join(r"rsbrowsers\NativeMessagingHosts")
+            .join(&browser.browser_type)
+            .join(file_name)),
+        engine => Err(unsupported(format!("{engine:?} browsers"))),
+    };
+
+    #[cfg(not(target_os = "windows"))]
+    match browser.engine() {
+        Engine::Blink => Ok(browser.user_data_dir().ok_or_else(missing)?.join("NativeMessagingHosts").join(file_name)),
+        // Firefox-based browsers share Mozilla's location
+        #[cfg(target_os = "macos")]
+        Engine::Gecko => Ok(dirs::data_dir().ok_or_else(missing)?.join("Mozilla/NativeMessagingHosts").join(file_name)),
+        #[cfg(target_os = "linux")]
+        Engine::Gecko => {
+            Ok(dirs::home_dir().ok_or_else(missing)?.join(".mozilla/native-messaging-hosts").join(file_name))
+        }
+        engine => Err(unsupported(format!("{engine:?} browsers"))),
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn registry_key(browser: &Browser, name: &str) -> String {
+    let key = match browser.engine() {
+        Engine::Gecko => r"Software\Mozilla\NativeMessagingHosts",
+        _ => REGISTRY_KEY_LIST
+            .iter()
+            .find(|(prefix, _)| browser.browser_type.starts_with(prefix))
+            .map_or(r"Software\Google\Chrome\NativeMessagingHosts", |(_, key)| key),
+    };
+    format!(r"{key}\{name}")
+}
+
+/// Browsers reject host names outside `[a-z0-9_.]`, or with leading, trailing or doubled dots.
+fn validate_name(name: &str) -> io::Result<()> {
+    let valid = !name.is_empty()
+        && name.bytes().all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'_' || b == b'.')
+        && !name.starts_with('.')
+        && !name.ends_with('.')
+        && !name.contains("..");
+    match valid {
+        true => Ok(()),
+        false => {
+            Err(io::Error::new(io::ErrorKind::InvalidInput, format!("invalid native messaging host name {name:?}")))
+        }
+    }
+}
+
+fn unsupported(what: String) -> io::Error {
+    io::Error::new(io::ErrorKind::Unsupported, format!("native messaging is not supported for {what}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Engine;
+    use crate::native_messaging::{HostManifest, validate_name};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_to_json() {
+        let manifest = HostManifest {
+            name: "com.example.host".to_string(),
+            description: "Example".to_string(),
+            path: PathBuf::from("/opt/example/host"),
+            allowed_origins: vec!["chrome-extension://knldjmfmopnpolahpmmgbagdohdnhkik/".to_string()],
+            allowed_extensions: vec!["host@example.com".to_string()],
+        };
+
+        let chromium: serde_json::Value = serde_json::from_str(&manifest.to_json(Engine::Blink).unwrap()).unwrap();
+        assert_eq!(chromium["type"], "stdio");
+        assert_eq!(chromium["allowed_origins"][0], "chrome-extension://knldjmfmopnpolahpmmgbagdohdnhkik/");
+        assert!(chromium.get("allowed_extensions").is_none());
+
+        let firefox: serde_json::Value = serde_json::from_str(&manifest.to_json(Engine::Gecko).unwrap()).unwrap();
+        assert_eq!(firefox["allowed_extensions"][0], "host@example.com");
+        assert!(manifest.to_json(Engine::WebKit).is_err());
+    }
+
+    #[test]
+    fn test_validate_name() {
+        assert!(validate_name("com.example.native_host").is_ok());
+        assert!(validate_name("com.Example.host").is_err());
+        assert!(validate_name("com..example").is_err());
+        assert!(validate_name(".com.example").is_err());
+        assert!(validate_name("").is_err());
+    }
+}
@@ -0,0 +1,122 @@
+use crate::Browser;
+#[cfg(target_os = "linux")]
+use crate::exec;
+use std::cmp::Ordering;
+use std::path::PathBuf;
+
+/// The order of the browsers returned by `BrowserFinder::all`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum SortBy {
+    /// By browser type, then newest version first, then by path.
+    #[default]
+    BrowserType,
+    /// Newest version first, then by browser type and path. Browsers without a version come last.
+    Version,
+    /// By display name, ignoring case, then by browser type and path.
+    DisplayName,
+    /// In the order the platform's sources report them, followed by custom and automation browsers.
+    Discovery,
+}
+
+/// Sorts `browsers` in place.
+pub(crate) fn sort(browsers: &mut [Browser], sort_by: SortBy) {
+    let by_type = |a: &Browser, b: &Browser| a.browser_type.cmp(&b.browser_type);
+    let by_path = |a: &Browser, b: &Browser| a.path.cmp(&b.path);
+
+    match sort_by {
+        SortBy::BrowserType => {
+            browsers.sort_by(|a, b| by_type(a, b).then_with(|| newest_first(a, b)).then_with(|| by_path(a, b)))
+        }
+        SortBy::Version => {
+            browsers.sort_by(|a, b| newest_first(a, b).then_with(|| by_type(a, b)).then_with(|| by_path(a, b)))
+        }
+        SortBy::DisplayName => browsers.sort_by(|a, b| {
+            a.display_name
+                .to_lowercase()
+                .cmp(&b.display_name.to_lowercase())
+                .then_with(|| by_type(a, b))
+                .then_with(|| by_path(a, b))
+        }),
+        SortBy::Discovery => {}
+    }
+}
+
+fn newest_first(a: &Browser, b: &Browser) -> Ordering {
+    match (a.parsed_version(), b.parsed_version()) {
+        (Some(a), Some(b)) => b.cmp(&a),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+/// Drops browsers of the same type whose canonical executable was already seen, keeping the first, e.g.
+/// a copy reached through a symlink.
+pub(crate) fn dedup(browsers: Vec<Browser>) -> Vec<Browser> {
+    let mut seen = vec![];
+    browsers
+        .into_iter()
+        .filter(|browser| {
+            let key = (browser.browser_type.to_owned(), identity(browser));
+            let duplicate = seen.contains(&key);
+            seen.push(key);
+            !duplicate
+        })
+        .collect()
+}
+
+/// Returns the canonical executable, plus the arguments of a Linux Exec line or the registered `args` since
+/// e.g. every Flatpak app runs `/usr/bin/flatpak`.
+fn identity(browser: &Browser) -> (PathBuf, Vec<String>) {
+    let executable = browser.path.canonicalize().unwrap_or(browser.path.clone());
+
+    #[cfg(target_os = "linux")]
+    if let Some(exec) = &browser.exec {
+        let arguments = exec::split(exec).map(|argv| argv[1..].to_vec()).unwrap_or_default();
+        return (executable, arguments);
+    }
+
+    (executable, browser.args.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::order::{dedup, sort};
+    use crate::{Browser, InstallSource, SortBy};
+
+    fn browser(browser_type: &str, path: &str, version: &str) -> Browser {
+        Browser {
+            version: version.to_string(),
+            install_source: InstallSource::Custom,
+            ..Browser::test_fixture(browser_type, path)
+        }
+    }
+
+    #[test]
+    fn test_sort() {
+        let mut browsers = vec![
+            browser("firefox", "/b/firefox", "115.0"),
+            browser("chrome", "/a/chrome", ""),
+            browser("firefox", "/a/firefox", "128.0"),
+            browser("chrome", "/b/chrome", "120.0"),
+        ];
+
+        sort(&mut browsers, SortBy::BrowserType);
+        let paths: Vec<&str> = browsers.iter().map(|browser| browser.path.to_str().unwrap()).collect();
+        assert_eq!(paths, ["/b/chrome", "/a/chrome", "/a/firefox", "/b/firefox"]);
+
+        sort(&mut browsers, SortBy::Version);
+        let paths: Vec<&str> = browsers.iter().map(|browser| browser.path.to_str().unwrap()).collect();
+        assert_eq!(paths, ["/a/firefox", "/b/chrome", "/b/firefox", "/a/chrome"]);
+    }
+
+    #[test]
+    fn test_dedup() {
+        let browsers = vec![
+            browser("chrome", "/nonexistent/chrome", "120.0"),
+            browser("chrome", "/nonexistent/chrome", "120.0"),
+            browser("chrome-beta", "/nonexistent/chrome", "121.0"),
+        ];
+        assert_eq!(dedup(browsers).len(), 2);
+    }
+}
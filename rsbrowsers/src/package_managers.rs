@@ -0,0 +1,201 @@
+//! Windows package managers, read from the directories winget, Chocolatey and Scoop install packages into.
+
+use crate::backend::fallback_browser;
+use crate::{Browser, InstallSource, get_version_info};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Chocolatey packages that run a browser's installer, with the browser they install. Packages that
+/// extract a browser into their own directory are recognized by its path instead.
+const CHOCOLATEY_PACKAGE_LIST: &[(&str, &str)] = &[
+    ("brave", "brave"),
+    ("chromium", "chromium"),
+    ("firefox", "firefox"),
+    ("firefox-dev", "firefox-developer"),
+    ("firefox-nightly", "firefox-nightly"),
+    ("googlechrome", "chrome"),
+    ("librewolf", "librewolf"),
+    ("microsoft-edge", "msedge"),
+    ("opera", "opera"),
+    ("vivaldi", "vivaldi"),
+    ("waterfox", "waterfox"),
+];
+
+/// How deep to look for browsers below a package's files, e.g. `tools\firefox\firefox.exe`.
+const MAX_DEPTH: usize = 2;
+
+/// The directory a package manager installed a package into.
+struct PackageDir {
+    source: InstallSource,
+    package: String,
+    /// Browsers below it belong to the package.
+    path: PathBuf,
+    /// Where the files of the installed version are, e.g. a Scoop app's `current` directory.
+    files: PathBuf,
+}
+
+/// Sets `install_source` and `package` of the browsers installed by a package manager and adds the ones
+/// the backends missed, see `BrowserFinder::with_package_managers`.
+pub(crate) fn apply(browsers: &mut Vec<Browser>) {
+    for dir in package_dirs() {
+        let mut found = false;
+        for browser in browsers.iter_mut().filter(|browser| is_below(&browser.path, &dir.path)) {
+            browser.install_source = dir.source;
+            browser.package = Some(dir.package.clone());
+            found = true;
+        }
+        if found {
+            continue;
+        }
+
+        let mut executables = vec![];
+        collect_executables(&dir.files, MAX_DEPTH, &mut executables);
+        browsers.extend(executables.into_iter().filter_map(fallback_browser).map(|browser| Browser {
+            version: get_version_info(&browser.path),
+            install_source: dir.source,
+            package: Some(dir.package.clone()),
+            ..browser
+        }));
+    }
+
+    // installers put the browser where other machine-wide installs go, so only the type tells them apart
+    let program_files: Vec<PathBuf> = ["ProgramW6432", "ProgramFiles", "ProgramFiles(x86)"]
+        .iter()
+        .filter_map(env::var_os)
+        .map(PathBuf::from)
+        .collect();
+    for (package, browser_type) in chocolatey_installer_packages() {
+        let installed = browsers.iter_mut().filter(|browser| {
+            browser.browser_type == browser_type
+                && browser.install_source == InstallSource::System
+                && program_files.iter().any(|dir| is_below(&browser.path, dir))
+        });
+        for browser in installed {
+            browser.install_source = InstallSource::Chocolatey;
+            browser.package = Some(package.to_string());
+        }
+    }
+}
+
+/// Lists the apps of Scoop, the portable packages of winget and the packages of Chocolatey.
+fn package_dirs() -> Vec<PackageDir> {
+    let mut dirs = vec![];
+    let subdirs = |dir: &Path| {
+        let entries = fs::read_dir(dir).into_iter().flatten().filter_map(Result::ok);
+        entries
+            .filter(|entry| entry.file_type().is_ok_and(|file_type| file_type.is_dir()))
+            .filter_map(|entry| Some((entry.file_name().into_string().ok()?, entry.path())))
+            .collect::<Vec<_>>()
+    };
+
+    for root in scoop_roots() {
+        for (app, path) in subdirs(&root.join("apps")) {
+            // `current` links to the installed version
+            let files = path.join("current");
+            dirs.push(PackageDir { source: InstallSource::Scoop, package: app, path, files });
+        }
+    }
+    for root in winget_roots() {
+        for (name, path) in subdirs(&root) {
+            let package = winget_package_id(&name).to_string();
+            dirs.push(PackageDir { source: InstallSource::Winget, package, files: path.clone(), path });
+        }
+    }
+    if let Some(root) = chocolatey_root() {
+        for (package, path) in subdirs(&root.join("lib")) {
+            dirs.push(PackageDir { source: InstallSource::Chocolatey, package, files: path.clone(), path });
+        }
+    }
+    dirs
+}
+
+/// Lists the directories packages are installed into, which change when one is added or removed.
+pub(crate) fn roots() -> Vec<PathBuf> {
+    let scoop = scoop_roots().into_iter().map(|root| root.join("apps"));
+    let chocolatey = chocolatey_root().map(|root| root.join("lib"));
+    scoop.chain(winget_roots()).chain(chocolatey).collect()
+}
+
+/// Returns the user's and the global Scoop directory, `~\scoop` and `C:\ProgramData\scoop` by default.
+fn scoop_roots() -> Vec<PathBuf> {
+    let user = env::var_os("SCOOP").map(PathBuf::from).or_else(|| dirs::home_dir().map(|home| home.join("scoop")));
+    let global = env::var_os("SCOOP_GLOBAL")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("ProgramData").map(|dir| Path::new(&dir).join("scoop")));
+    user.into_iter().chain(global).collect()
+}
+
+/// Returns the directories winget extracts portable packages into, for the user and machine-wide.
+fn winget_roots() -> Vec<PathBuf> {
+    let user = dirs::data_local_dir().map(|dir| dir.join(r"Microsoft\WinGet\Packages"));
+    let machine = ["ProgramFiles", "ProgramFiles(x86)"]
+        .iter()
+        .filter_map(env::var_os)
+        .map(|dir| Path::new(&dir).join(r"WinGet\Packages"));
+    user.into_iter().chain(machine).collect()
+}
+
+fn chocolatey_root() -> Option<PathBuf> {
+    env::var_os("ChocolateyInstall")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("ProgramData").map(|dir| Path::new(&dir).join("chocolatey")))
+}
+
+/// Lists the Chocolatey packages of `CHOCOLATEY_PACKAGE_LIST` that are installed.
+fn chocolatey_installer_packages() -> Vec<(&'static str, &'static str)> {
+    let Some(lib) = chocolatey_root().map(|root| root.join("lib")) else { return vec![] };
+    CHOCOLATEY_PACKAGE_LIST.iter().copied().filter(|(package, _)| lib.join(package).is_dir()).collect()
+}
+
+/// Returns the package identifier of a winget package directory, e.g. `Hibbiki.Chromium` for
+/// `Hibbiki.Chromium_Microsoft.Winget.Source_8wekyb3d8bbwe`.
+fn winget_package_id(name: &str) -> &str {
+    name.split_once('_').map_or(name, |(id, _)| id)
+}
+
+/// Whether `path` is inside `dir`, ignoring case and the kind of slashes like Windows does.
+fn is_below(path: &Path, dir: &Path) -> bool {
+    let normalize = |path: &Path| path.to_string_lossy().replace('/', r"\").to_lowercase();
+    let dir = normalize(dir);
+    let dir = dir.trim_end_matches('\\');
+    normalize(path).strip_prefix(dir).is_some_and(|rest| rest.starts_with('\\'))
+}
+
+/// Collects the `.exe` files below `dir`.
+fn collect_executables(dir: &Path, depth: usize, executables: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else { return };
+
+    for entry in entries.filter_map(Result::ok) {
+        let path = entry.path();
+        let Ok(metadata) = fs::metadata(&path) else { continue };
+
+        if metadata.is_file() && path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("exe")) {
+            executables.push(path);
+        } else if metadata.is_dir() && depth > 0 {
+            collect_executables(&path, depth - 1, executables);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::package_managers::{is_below, winget_package_id};
+    use std::path::Path;
+
+    #[test]
+    fn test_winget_package_id() {
+        assert_eq!(winget_package_id("Hibbiki.Chromium_Microsoft.Winget.Source_8wekyb3d8bbwe"), "Hibbiki.Chromium");
+        assert_eq!(winget_package_id("Mozilla.Firefox"), "Mozilla.Firefox");
+    }
+
+    #[test]
+    fn test_is_below() {
+        let scoop = Path::new(r"C:\Users\me\scoop\apps\firefox");
+        assert!(is_below(Path::new(r"C:\Users\me\scoop\apps\firefox\current\firefox.exe"), scoop));
+        assert!(is_below(Path::new(r"c:/users/me/Scoop/apps/Firefox/128.0/firefox.exe"), scoop));
+        assert!(is_below(Path::new(r"C:\Program Files\Mozilla Firefox\firefox.exe"), Path::new(r"C:\Program Files\")));
+        assert!(!is_below(Path::new(r"C:\Users\me\scoop\apps\firefox-nightly\current\firefox.exe"), scoop));
+        assert!(!is_below(scoop, scoop));
+    }
+}
@@ -0,0 +1,52 @@
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+/// Maps `items` on up to `concurrency` threads, returning the results in the order of `items`.
+pub(crate) fn map<T, R, F>(items: Vec<T>, concurrency: usize, f: F) -> Vec<R>
+where
+    T: Send,
+    R: Send,
+    F: Fn(T) -> R + Sync,
+{
+    let workers = concurrency.clamp(1, items.len().max(1));
+    if workers == 1 {
+        return items.into_iter().map(f).collect();
+    }
+
+    let len = items.len();
+    let items: Vec<Mutex<Option<T>>> = items.into_iter().map(|item| Mutex::new(Some(item))).collect();
+    let results: Vec<Mutex<Option<R>>> = (0..len).map(|_| Mutex::new(None)).collect();
+    let next = AtomicUsize::new(0);
+
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| {
+                loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    if index >= len {
+                        break;
+                    }
+                    let item = items[index].lock().unwrap().take().unwrap();
+                    *results[index].lock().unwrap() = Some(f(item));
+                }
+            });
+        }
+    });
+
+    results.into_iter().map(|result| result.into_inner().unwrap().unwrap()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parallel::map;
+
+    #[test]
+    fn test_map_preserves_order() {
+        let items: Vec<u64> = (0..100).collect();
+
+        assert_eq!(map(items.clone(), 8, |i| i * 2), items.iter().map(|i| i * 2).collect::<Vec<u64>>());
+        assert_eq!(map(items.clone(), 1, |i| i + 1)[99], 100);
+        assert!(map(Vec::<u64>::new(), 4, |i| i).is_empty());
+    }
+}
@@ -0,0 +1,96 @@
+//! Reads the file version of a PE executable from its resource section only, since browser executables
+//! can be hundreds of megabytes.
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// `VS_FIXEDFILEINFO.dwSignature`, which marks the fixed part of the version resource.
+const FIXED_FILE_INFO_SIGNATURE: u32 = 0xfeef_04bd;
+/// Resource sections larger than this are left to the full parser.
+const MAX_RESOURCE_SECTION: u32 = 64 * 1024 * 1024;
+
+/// Returns the file version, e.g. `120.0.6099.109`, or `None` if the file is not a PE executable or has no
+/// version resource.
+pub(crate) fn file_version(path: &Path) -> Option<String> {
+    let mut file = File::open(path).ok()?;
+    let mut headers = vec![0; 4096];
+    let len = file.read(&mut headers).ok()?;
+    headers.truncate(len);
+
+    let (offset, size) = resource_section(&headers)?;
+    let mut section = vec![0; size as usize];
+    file.seek(SeekFrom::Start(offset as u64)).ok()?;
+    file.read_exact(&mut section).ok()?;
+    fixed_file_version(&section)
+}
+
+/// Finds the file offset and size of the `.rsrc` section from the section table.
+fn resource_section(headers: &[u8]) -> Option<(u32, u32)> {
+    let u16_le = |at: usize| headers.get(at..at + 2).map(|b| u16::from_le_bytes([b[0], b[1]]));
+    let u32_le = |at: usize| headers.get(at..at + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]));
+
+    if headers.get(..2)? != b"MZ" {
+        return None;
+    }
+    let pe = u32_le(0x3c)? as usize;
+    if headers.get(pe..pe + 4)? != b"PE\0\0" {
+        return None;
+    }
+    let sections = u16_le(pe + 6)? as usize;
+    let section_table = pe + 24 + u16_le(pe + 20)? as usize;
+
+    (0..sections).map(|index| section_table + index * 40).find_map(|section| {
+        let name = headers.get(section..section + 8)?;
+        if !name.starts_with(b".rsrc\0") {
+            return None;
+        }
+        let (size, offset) = (u32_le(section + 16)?, u32_le(section + 20)?);
+        (size <= MAX_RESOURCE_SECTION).then_some((offset, size))
+    })
+}
+
+/// Finds `VS_FIXEDFILEINFO` by its signature, which is 32-bit aligned, and formats its file version.
+fn fixed_file_version(section: &[u8]) -> Option<String> {
+    let u32_le = |at: usize| section.get(at..at + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]));
+
+    let info = (0..section.len()).step_by(4).find(|&at| u32_le(at) == Some(FIXED_FILE_INFO_SIGNATURE))?;
+    let (major_minor, build_patch) = (u32_le(info + 8)?, u32_le(info + 12)?);
+    Some(format!("{}.{}.{}.{}", major_minor >> 16, major_minor & 0xffff, build_patch >> 16, build_patch & 0xffff))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pe::{fixed_file_version, resource_section};
+
+    #[test]
+    fn test_fixed_file_version() {
+        let mut section = vec![0; 16];
+        section.extend(0xfeef_04bd_u32.to_le_bytes());
+        section.extend(0x0001_0000_u32.to_le_bytes());
+        section.extend(((120 << 16) | 1_u32).to_le_bytes());
+        section.extend(((6099 << 16) | 109_u32).to_le_bytes());
+        assert_eq!(fixed_file_version(&section), Some("120.1.6099.109".to_string()));
+        assert_eq!(fixed_file_version(&[0; 64]), None);
+    }
+
+    #[test]
+    fn test_resource_section() {
+        let mut headers = vec![0; 0x40];
+        headers[..2].copy_from_slice(b"MZ");
+        headers[0x3c] = 0x40;
+        headers.extend(b"PE\0\0");
+        // COFF header with two sections and no optional header
+        headers.extend([0x64, 0x86, 2, 0]);
+        headers.resize(0x40 + 24, 0);
+        for (name, size, offset) in [(b".text\0\0\0", 0x1000_u32, 0x400_u32), (b".rsrc\0\0\0", 0x2000, 0x1400)] {
+            headers.extend(name);
+            headers.extend([0; 8]);
+            headers.extend(size.to_le_bytes());
+            headers.extend(offset.to_le_bytes());
+            headers.extend([0; 16]);
+        }
+        assert_eq!(resource_section(&headers), Some((0x1400, 0x2000)));
+        assert_eq!(resource_section(b"#!/bin/sh"), None);
+    }
+}
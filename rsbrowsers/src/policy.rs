@@ -0,0 +1,188 @@
+use crate::Browser;
+use std::path::PathBuf;
+#[cfg(target_os = "macos")]
+use std::{env, path::Path};
+#[cfg(target_os = "linux")]
+use std::{fs, path::Path};
+#[cfg(target_os = "windows")]
+use {
+    winreg::RegKey,
+    winreg::enums::{HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE},
+};
+
+/// Where enterprise policies for a browser come from, see `Browser::policy_sources`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum PolicySource {
+    /// A registry key holding policies, e.g. `HKLM\Software\Policies\Google\Chrome`.
+    Registry(String),
+    /// A policy file, e.g. `/etc/opt/chrome/policies/managed/proxy.json`.
+    File(PathBuf),
+    /// The managed preferences installed by a configuration profile, e.g.
+    /// `/Library/Managed Preferences/com.google.Chrome.plist`.
+    ManagedPreferences(PathBuf),
+}
+
+#[cfg(target_os = "macos")]
+const POLICY_DOMAIN_LIST: &[(&str, &str)] = &[
+    // browser name, preference domain the policies are read from
+    ("brave", "com.brave.Browser"),
+    ("brave-beta", "com.brave.Browser"),
+    ("brave-dev", "com.brave.Browser"),
+    ("brave-nightly", "com.brave.Browser"),
+    ("chrome", "com.google.Chrome"),
+    ("chrome-beta", "com.google.Chrome"),
+    ("chrome-canary", "com.google.Chrome"),
+    ("chrome-dev", "com.google.Chrome"),
+    ("chromium", "org.chromium.Chromium"),
+    ("firefox", "org.mozilla.firefox"),
+    ("firefox-developer", "org.mozilla.firefox"),
+    ("firefox-nightly", "org.mozilla.firefox"),
+    ("msedge", "com.microsoft.Edge"),
+    ("msedge-beta", "com.microsoft.Edge"),
+    ("msedge-canary", "com.microsoft.Edge"),
+    ("msedge-dev", "com.microsoft.Edge"),
+];
+
+#[cfg(target_os = "windows")]
+const POLICY_KEY_LIST: &[(&str, &str)] = &[
+    // browser name, policy key in HKLM and HKCU
+    ("brave", r"Software\Policies\BraveSoftware\Brave"),
+    ("brave-beta", r"Software\Policies\BraveSoftware\Brave"),
+    ("brave-nightly", r"Software\Policies\BraveSoftware\Brave"),
+    ("chrome", r"Software\Policies\Google\Chrome"),
+    ("chrome-beta", r"Software\Policies\Google\Chrome"),
+    ("chrome-canary", r"Software\Policies\Google\Chrome"),
+    ("chrome-dev", r"Software\Policies\Google\Chrome"),
+    ("chromium", r"Software\Policies\Chromium"),
+    ("firefox", r"Software\Policies\Mozilla\Firefox"),
+    ("firefox-developer", r"Software\Policies\Mozilla\Firefox"),
+    ("firefox-esr", r"Software\Policies\Mozilla\Firefox"),
+    ("firefox-nightly", r"Software\Policies\Mozilla\Firefox"),
+    ("msedge", r"Software\Policies\Microsoft\Edge"),
+    ("msedge-beta", r"Software\Policies\Microsoft\Edge"),
+    ("msedge-canary", r"Software\Policies\Microsoft\Edge"),
+    ("msedge-dev", r"Software\Policies\Microsoft\Edge"),
+];
+
+#[cfg(target_os = "linux")]
+const POLICY_DIR_LIST: &[(&str, &str)] = &[
+    // browser name, directory of JSON policy files
+    ("brave", "/etc/brave/policies/managed"),
+    ("brave-beta", "/etc/brave/policies/managed"),
+    ("brave-nightly", "/etc/brave/policies/managed"),
+    ("chrome", "/etc/opt/chrome/policies/managed"),
+    ("chrome-beta", "/etc/opt/chrome/policies/managed"),
+    ("chrome-dev", "/etc/opt/chrome/policies/managed"),
+    ("chromium", "/etc/chromium/policies/managed"),
+    ("firefox", "/etc/firefox/policies"),
+    ("firefox-esr", "/etc/firefox/policies"),
+    ("msedge", "/etc/opt/edge/policies/managed"),
+    ("msedge-beta", "/etc/opt/edge/policies/managed"),
+    ("msedge-dev", "/etc/opt/edge/policies/managed"),
+];
+
+impl Browser {
+    /// Returns whether the browser is managed by enterprise policies, which take precedence over command
+    /// line flags such as `--proxy-server`. See `policy_sources`.
+    pub fn is_managed(&self) -> bool {
+        !self.policy_sources().is_empty()
+    }
+
+    /// Lists the places enterprise policies for the browser are set: the keys under `Software\Policies`
+    /// holding values on Windows, the managed preferences of configuration profiles on macOS and the
+    /// JSON files in the policy directories on Linux, e.g. `/etc/opt/chrome/policies/managed`.
+    ///
+    /// Channels share the policies of their browser, e.g. `chrome-beta` those of `chrome`. Empty for
+    /// unmanaged browsers and ones without a known location.
+    pub fn policy_sources(&self) -> Vec<PolicySource> {
+        policy_sources(&self.browser_type)
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn policy_sources(browser_type: &str) -> Vec<PolicySource> {
+    let Some((_, domain)) = POLICY_DOMAIN_LIST.iter().find(|(name, _)| *name == browser_type) else {
+        return vec![];
+    };
+    let managed = Path::new("/Library/Managed Preferences");
+    let file_name = format!("{domain}.plist");
+    // computer-wide profiles come first, then the ones installed for the user
+    let user = env::var_os("USER").map(|user| managed.join(user).join(&file_name));
+
+    [Some(managed.join(&file_name)), user]
+        .into_iter()
+        .flatten()
+        .filter(|path| path.is_file())
+        .map(PolicySource::ManagedPreferences)
+        .collect()
+}
+
+#[cfg(target_os = "windows")]
+fn policy_sources(browser_type: &str) -> Vec<PolicySource> {
+    let Some((_, key)) = POLICY_KEY_LIST.iter().find(|(name, _)| *name == browser_type) else {
+        return vec![];
+    };
+
+    [(HKEY_LOCAL_MACHINE, "HKLM"), (HKEY_CURRENT_USER, "HKCU")]
+        .into_iter()
+        .filter(|(root, _)| {
+            // an empty key is left behind by uninstalled policy templates
+            RegKey::predef(*root)
+                .open_subkey(key)
+                .is_ok_and(|policies| policies.enum_values().next().is_some() || policies.enum_keys().next().is_some())
+        })
+        .map(|(_, root)| PolicySource::Registry(format!(r"{root}\{key}")))
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+fn policy_sources(browser_type: &str) -> Vec<PolicySource> {
+    match POLICY_DIR_LIST.iter().find(|(name, _)| *name == browser_type) {
+        Some((_, dir)) => policy_files(Path::new(dir)).into_iter().map(PolicySource::File).collect(),
+        None => vec![],
+    }
+}
+
+/// Lists the JSON files in a policy directory in the order Chromium applies them.
+#[cfg(target_os = "linux")]
+fn policy_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "json") && path.is_file())
+        .collect();
+    files.sort();
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Browser;
+
+    #[test]
+    fn test_policy_sources() {
+        let browser = Browser::test_fixture("ladybird", "ladybird");
+
+        assert!(browser.policy_sources().is_empty());
+        assert!(!browser.is_managed());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_policy_files() {
+        use crate::policy::policy_files;
+        use std::{env, fs};
+
+        let dir = env::temp_dir().join(format!("rsbrowsers-policies-{}", std::process::id()));
+        fs::create_dir_all(dir.join("nested.json")).unwrap();
+        for name in ["proxy.json", "README", "extensions.json"] {
+            fs::write(dir.join(name), "{}").unwrap();
+        }
+
+        assert_eq!(policy_files(&dir), [dir.join("extensions.json"), dir.join("proxy.json")]);
+        assert!(policy_files(&dir.join("missing")).is_empty());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
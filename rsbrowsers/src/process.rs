@@ -0,0 +1,73 @@
+#[cfg(target_os = "linux")]
+use std::fs;
+use std::path::{Path, PathBuf};
+#[cfg(not(target_os = "linux"))]
+use std::process::Command;
+
+/// Returns whether a process of the browser whose launcher is `executable` is running.
+///
+/// A launcher is often a wrapper script next to the real binary, e.g. `/opt/google/chrome/google-chrome`
+/// starts `/opt/google/chrome/chrome`, so any process from the launcher's own directory counts unless
+/// that is a shared `bin` directory. On Windows only the image name is compared.
+pub(crate) fn is_running(executable: &Path) -> bool {
+    let executable = executable.canonicalize().unwrap_or(executable.to_path_buf());
+    running_executables().iter().any(|running| is_same_browser(running, &executable))
+}
+
+#[cfg(not(target_os = "windows"))]
+fn is_same_browser(running: &Path, executable: &Path) -> bool {
+    // a macOS app bundle matches the binary inside it
+    if running == executable || running.starts_with(executable) {
+        return true;
+    }
+    let install_dir = executable.parent().filter(|dir| dir.file_name().is_some_and(|name| name != "bin"));
+    install_dir.is_some_and(|dir| running.parent() == Some(dir))
+}
+
+#[cfg(target_os = "windows")]
+fn is_same_browser(running: &Path, executable: &Path) -> bool {
+    match (running.file_name(), executable.file_name()) {
+        (Some(running), Some(executable)) => running.eq_ignore_ascii_case(executable),
+        _ => false,
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn running_executables() -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir("/proc") else { return vec![] };
+    entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_name().to_string_lossy().bytes().all(|b| b.is_ascii_digit()))
+        // processes of other users cannot be read, which is fine for launching
+        .filter_map(|entry| fs::read_link(entry.path().join("exe")).ok())
+        .collect()
+}
+
+#[cfg(target_os = "macos")]
+fn running_executables() -> Vec<PathBuf> {
+    let Ok(output) = Command::new("ps").args(["-axo", "comm="]).output() else { return vec![] };
+    String::from_utf8_lossy(&output.stdout).lines().map(|line| PathBuf::from(line.trim())).collect()
+}
+
+#[cfg(target_os = "windows")]
+fn running_executables() -> Vec<PathBuf> {
+    // e.g. "chrome.exe","1234","Console","1","250,000 K"
+    let Ok(output) = Command::new("tasklist").args(["/FO", "CSV", "/NH"]).output() else { return vec![] };
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.strip_prefix('"')?.split_once('"').map(|(image, _)| PathBuf::from(image)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::process::is_running;
+    use std::env;
+    use std::path::Path;
+
+    #[test]
+    fn test_is_running() {
+        assert!(is_running(&env::current_exe().unwrap()));
+        assert!(!is_running(Path::new("/nonexistent/rsbrowsers-browser")));
+    }
+}
@@ -0,0 +1,103 @@
+use crate::LaunchError;
+use url::Url;
+
+/// A proxy for a single launch, see `LaunchOptions::proxy`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ProxyConfig {
+    /// Sends all requests through this proxy, e.g. `http://proxy.example.com:8080` or `socks5://localhost:1080`,
+    /// except those to the `bypass` hosts such as `localhost` or `*.example.com`.
+    Server { url: String, bypass: Vec<String> },
+    /// Picks the proxy with the proxy auto-config script at this URL.
+    Pac(String),
+    /// Connects directly, ignoring the system proxy settings.
+    Direct,
+}
+
+impl ProxyConfig {
+    /// A proxy server without bypass hosts.
+    pub fn server(url: &str) -> Self {
+        ProxyConfig::Server { url: url.to_owned(), bypass: vec![] }
+    }
+
+    /// Returns the Chromium command-line flags.
+    pub(crate) fn chromium_args(&self) -> Vec<String> {
+        match self {
+            ProxyConfig::Server { url, bypass } if bypass.is_empty() => vec![format!("--proxy-server={url}")],
+            ProxyConfig::Server { url, bypass } => {
+                vec![format!("--proxy-server={url}"), format!("--proxy-bypass-list={}", bypass.join(";"))]
+            }
+            ProxyConfig::Pac(url) => vec![format!("--proxy-pac-url={url}")],
+            ProxyConfig::Direct => vec!["--no-proxy-server".to_owned()],
+        }
+    }
+
+    /// Returns `user.js` lines for a Firefox profile, which has no command-line flag for proxies.
+    pub(crate) fn firefox_prefs(&self) -> Result<String, LaunchError> {
+        let mut prefs: Vec<(&str, String)> = vec![];
+        match self {
+            ProxyConfig::Server { url, bypass } => {
+                let invalid = |reason: &str| LaunchError::InvalidUrl(format!("{url:?}: {reason}"));
+                let parsed = Url::parse(url).map_err(|e| invalid(&e.to_string()))?;
+                let host = parsed.host_str().ok_or_else(|| invalid("missing host"))?;
+                let port = parsed.port_or_known_default().ok_or_else(|| invalid("missing port"))?;
+
+                prefs.push(("network.proxy.type", "1".to_owned()));
+                match parsed.scheme() {
+                    "http" | "https" => {
+                        prefs.push(("network.proxy.http", quote(host)));
+                        prefs.push(("network.proxy.http_port", port.to_string()));
+                        prefs.push(("network.proxy.ssl", quote(host)));
+                        prefs.push(("network.proxy.ssl_port", port.to_string()));
+                    }
+                    scheme @ ("socks" | "socks4" | "socks5") => {
+                        prefs.push(("network.proxy.socks", quote(host)));
+                        prefs.push(("network.proxy.socks_port", port.to_string()));
+                        prefs.push((
+                            "network.proxy.socks_version",
+                            if scheme == "socks4" { "4" } else { "5" }.to_owned(),
+                        ));
+                        prefs.push(("network.proxy.socks_remote_dns", "true".to_owned()));
+                    }
+                    scheme => return Err(invalid(&format!("unsupported proxy scheme {scheme}"))),
+                }
+                prefs.push(("network.proxy.no_proxies_on", quote(&bypass.join(", "))));
+            }
+            ProxyConfig::Pac(url) => {
+                prefs.push(("network.proxy.type", "2".to_owned()));
+                prefs.push(("network.proxy.autoconfig_url", quote(url)));
+            }
+            ProxyConfig::Direct => prefs.push(("network.proxy.type", "0".to_owned())),
+        }
+        Ok(prefs.iter().map(|(name, value)| format!("user_pref(\"{name}\", {value});\n")).collect())
+    }
+}
+
+/// Quotes a string as a JavaScript literal.
+fn quote(value: &str) -> String {
+    serde_json::Value::from(value).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ProxyConfig;
+
+    #[test]
+    fn test_chromium_args() {
+        let proxy = ProxyConfig::Server { url: "http://proxy:3128".to_string(), bypass: vec!["localhost".to_string()] };
+        assert_eq!(proxy.chromium_args(), ["--proxy-server=http://proxy:3128", "--proxy-bypass-list=localhost"]);
+        assert_eq!(ProxyConfig::Direct.chromium_args(), ["--no-proxy-server"]);
+    }
+
+    #[test]
+    fn test_firefox_prefs() {
+        let prefs = ProxyConfig::server("socks5://localhost:1080").firefox_prefs().unwrap();
+        assert!(prefs.contains("user_pref(\"network.proxy.type\", 1);\n"));
+        assert!(prefs.contains("user_pref(\"network.proxy.socks\", \"localhost\");\n"));
+        assert!(prefs.contains("user_pref(\"network.proxy.socks_port\", 1080);\n"));
+
+        let prefs = ProxyConfig::server("http://proxy").firefox_prefs().unwrap();
+        assert!(prefs.contains("user_pref(\"network.proxy.ssl_port\", 80);\n"));
+
+        assert!(ProxyConfig::server("proxy:3128").firefox_prefs().is_err());
+    }
+}
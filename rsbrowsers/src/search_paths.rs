@@ -0,0 +1,118 @@
+//! Scanning of extra directories, e.g. `D:\PortableApps` or a network share, for browsers that are not
+//! registered with the system.
+
+use crate::{Browser, ScanContext, parallel};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// How deep to look below each directory, e.g. `FirefoxPortable\App\Firefox64\firefox.exe`.
+const MAX_DEPTH: usize = 4;
+
+#[cfg(target_os = "linux")]
+const EXECUTABLE_NAME_LIST: &[&str] = &[
+    // only these are run to identify them since anything else in the directories could be as well
+    "brave",
+    "brave-browser",
+    "chrome",
+    "chromium",
+    "chromium-browser",
+    "firefox",
+    "google-chrome",
+    "librewolf",
+    "microsoft-edge",
+    "msedge",
+    "opera",
+    "vivaldi",
+    "waterfox",
+];
+
+/// Returns the directories listed in `RSBROWSERS_EXTRA_PATHS`, separated like `PATH`.
+pub(crate) fn env_paths() -> Vec<PathBuf> {
+    env::var_os("RSBROWSERS_EXTRA_PATHS").map_or(vec![], |paths| env::split_paths(&paths).collect())
+}
+
+/// Identifies the browsers below `directories`, see `Browser::identify`. Linux identifies executables by
+/// running them, so nothing is found there if process probes are off, and each probe is bounded by the
+/// probe timeout. Candidates left when the deadline passes are not identified.
+pub(crate) fn detect(directories: &[PathBuf], context: &ScanContext) -> Vec<Browser> {
+    if cfg!(target_os = "linux") && !context.process_probes() {
+        return vec![];
+    }
+
+    let mut candidates = vec![];
+    for directory in directories {
+        collect_candidates(directory, MAX_DEPTH, &mut candidates);
+    }
+    parallel::map(candidates, context.concurrency(), |path| match context.expired() {
+        true => None,
+        false => Browser::identify_within(&path, context.probe_timeout()),
+    })
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+/// Collects app bundles on macOS, `.exe` files on Windows and known executable names on Linux.
+fn collect_candidates(directory: &Path, depth: usize, candidates: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(directory) else { return };
+
+    for entry in entries.filter_map(Result::ok) {
+        let path = entry.path();
+        // follows symlinks, which portable installs often use
+        let Ok(metadata) = fs::metadata(&path) else { continue };
+
+        if is_candidate(&path, &metadata) {
+            candidates.push(path);
+        } else if metadata.is_dir() && depth > 0 {
+            collect_candidates(&path, depth - 1, candidates);
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn is_candidate(path: &Path, metadata: &fs::Metadata) -> bool {
+    metadata.is_dir() && path.extension().is_some_and(|extension| extension == "app")
+}
+
+#[cfg(target_os = "windows")]
+fn is_candidate(path: &Path, metadata: &fs::Metadata) -> bool {
+    metadata.is_file() && path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("exe"))
+}
+
+#[cfg(target_os = "linux")]
+fn is_candidate(path: &Path, metadata: &fs::Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    metadata.is_file()
+        && metadata.permissions().mode() & 0o111 != 0
+        && path.file_name().and_then(|name| name.to_str()).is_some_and(|name| EXECUTABLE_NAME_LIST.contains(&name))
+}
+
+#[cfg(test)]
+#[cfg(target_os = "linux")]
+mod tests {
+    use crate::search_paths::collect_candidates;
+    use std::env;
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+
+    #[test]
+    fn test_collect_candidates() {
+        let root = env::temp_dir().join(format!("rsbrowsers-search-{}", std::process::id()));
+        let shallow = root.join("firefox-portable/firefox");
+        let deep = root.join("a/b/c/d/e/chrome");
+        let other = root.join("firefox-portable/updater");
+        for path in [&shallow, &deep, &other] {
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+            fs::set_permissions(path, fs::Permissions::from_mode(0o755)).unwrap();
+        }
+
+        let mut candidates = vec![];
+        collect_candidates(&root, 4, &mut candidates);
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(candidates, [shallow]);
+    }
+}
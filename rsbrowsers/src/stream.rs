@@ -0,0 +1,47 @@
+use crate::{Browser, BrowserFinder};
+use tokio::sync::mpsc;
+use tokio::task;
+use tokio_stream::Stream;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+
+impl BrowserFinder {
+    /// Streams installed browsers matching the filters, running the scan and its external probes
+    /// (`mdfind`, `--version`, ...) on tokio's blocking pool so the caller's runtime is not blocked.
+    ///
+    /// Must be called from within a tokio runtime. The stream ends early if a filter is invalid or the
+    /// platform's browser sources cannot be read, use `try_all` to get the error.
+    pub fn all_async(&self) -> impl Stream<Item = Browser> + Send + 'static {
+        let finder = self.clone();
+        let (sender, receiver) = mpsc::unbounded_channel();
+        task::spawn_blocking(move || {
+            for browser in finder.try_all().into_iter().flatten() {
+                if sender.send(browser).is_err() {
+                    // the stream was dropped
+                    break;
+                }
+            }
+        });
+        UnboundedReceiverStream::new(receiver)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::BrowserFinder;
+    use tokio::runtime::Builder;
+    use tokio_stream::StreamExt;
+
+    #[test]
+    fn test_stream() {
+        let runtime = Builder::new_current_thread().build().unwrap();
+        let finder = BrowserFinder::new().with_type("no-such-browser".to_string());
+
+        let browsers: Vec<_> = runtime.block_on(async { finder.all_async().collect().await });
+        assert!(browsers.is_empty());
+
+        // an invalid filter ends the stream instead of panicking
+        let finder = BrowserFinder::new().with_type("[".to_string());
+        let browsers: Vec<_> = runtime.block_on(async { finder.all_async().collect().await });
+        assert!(browsers.is_empty());
+    }
+}
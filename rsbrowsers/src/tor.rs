@@ -0,0 +1,110 @@
+//! Detection of Tor Browser, which is extracted into a user directory rather than installed.
+//!
+//! On macOS it is a regular app bundle and is found through the bundle list.
+
+use crate::{Browser, InstallSource};
+use glob::{Pattern, glob};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Launcher relative to the install directory. On Linux `start-tor-browser` sets up the environment for the
+/// bundled Firefox, on Windows the shortcut runs `firefox.exe` directly.
+#[cfg(target_os = "linux")]
+const LAUNCHER: &str = "Browser/start-tor-browser";
+#[cfg(target_os = "windows")]
+const LAUNCHER: &str = r"Browser\firefox.exe";
+
+/// Finds Tor Browser in its usual install directories, e.g. `~/tor-browser` or `Desktop\Tor Browser`.
+pub(crate) fn detect() -> Vec<Browser> {
+    let mut browsers: Vec<Browser> = vec![];
+
+    for dir in install_dirs() {
+        let launcher = dir.join(LAUNCHER);
+        if !launcher.is_file() {
+            continue;
+        }
+        let path = launcher.canonicalize().unwrap_or(launcher);
+        if browsers.iter().any(|browser| browser.path == path) {
+            continue;
+        }
+
+        browsers.push(Browser {
+            browser_type: "tor".to_string(),
+            path,
+            exec: None,
+            args: vec![],
+            display_name: "Tor Browser".to_string(),
+            version: read_version(&dir).unwrap_or_default(),
+            raw_version: None,
+            install_source: InstallSource::System,
+            source_id: None,
+            source_path: None,
+            package: None,
+        });
+    }
+
+    browsers
+}
+
+/// Lists the launcher and the version file of each install, which change when Tor Browser is installed,
+/// updated or removed.
+pub(crate) fn files() -> Vec<PathBuf> {
+    install_dirs()
+        .into_iter()
+        .flat_map(|dir| [dir.join(LAUNCHER), dir.join("Browser").join("tbb_version.json")])
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+fn install_dirs() -> Vec<PathBuf> {
+    let mut patterns = vec!["/opt/tor-browser*".to_string()];
+    if let Some(home) = dirs::home_dir() {
+        // the tarball extracts to e.g. "tor-browser" or "tor-browser_en-US"
+        patterns.push(format!("{}/tor-browser*", Pattern::escape(&home.to_string_lossy())));
+    }
+    if let Some(data) = dirs::data_local_dir() {
+        // torbrowser-launcher keeps its download per architecture
+        patterns.push(format!("{}/torbrowser/tbb/*/tor-browser*", Pattern::escape(&data.to_string_lossy())));
+    }
+    find(&patterns)
+}
+
+#[cfg(target_os = "windows")]
+fn install_dirs() -> Vec<PathBuf> {
+    let mut patterns = vec![];
+    let dirs = [dirs::desktop_dir(), dirs::data_local_dir(), std::env::var_os("ProgramFiles").map(PathBuf::from)];
+    for dir in dirs.into_iter().flatten() {
+        patterns.push(format!(r"{}\Tor Browser", Pattern::escape(&dir.to_string_lossy())));
+    }
+    find(&patterns)
+}
+
+fn find(patterns: &[String]) -> Vec<PathBuf> {
+    patterns.iter().filter_map(|pattern| glob(pattern).ok()).flat_map(|paths| paths.filter_map(Result::ok)).collect()
+}
+
+/// Reads the Tor Browser version from `Browser/tbb_version.json`, since the executable reports the version
+/// of the Firefox ESR it is based on.
+fn read_version(dir: &Path) -> Option<String> {
+    let json = fs::read_to_string(dir.join("Browser").join("tbb_version.json")).ok()?;
+    let value: serde_json::Value = serde_json::from_str(&json).ok()?;
+    value.get("version")?.as_str().map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tor::read_version;
+    use std::{env, fs};
+
+    #[test]
+    fn test_read_version() {
+        let dir = env::temp_dir().join(format!("rsbrowsers-tor-{}", std::process::id()));
+        fs::create_dir_all(dir.join("Browser")).unwrap();
+        fs::write(dir.join("Browser").join("tbb_version.json"), r#"{"version": "13.5.1", "channel": "release"}"#)
+            .unwrap();
+
+        assert_eq!(read_version(&dir), Some("13.5.1".to_string()));
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(read_version(&dir), None);
+    }
+}
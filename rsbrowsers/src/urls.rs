@@ -0,0 +1,116 @@
+use crate::LaunchError;
+use std::path::Path;
+use std::{env, fs};
+use url::{ParseError, Url};
+
+/// Schemes that may be opened without opting in with `LaunchOptions::allow_scheme`.
+pub(crate) const DEFAULT_SCHEMES: &[&str] = &["http", "https", "about"];
+
+/// Turns user input into a URL a browser can open, e.g. `example.com` into `https://example.com/`,
+/// `localhost:3000` into `http://localhost:3000/` or `./index.html` into a `file://` URI, rejecting schemes
+/// that are not in `allowed_schemes`.
+pub(crate) fn normalize(input: &str, allowed_schemes: &[String]) -> Result<String, LaunchError> {
+    let input = input.trim();
+    let invalid = |reason: String| LaunchError::InvalidUrl(format!("{input:?}: {reason}"));
+
+    let url = if is_local_path(input) {
+        let path = env::current_dir().map(|dir| dir.join(input)).map_err(|e| invalid(e.to_string()))?;
+        Url::from_file_path(&path).map_err(|_| invalid("not an absolute path".to_string()))?
+    } else {
+        match Url::parse(input) {
+            // "localhost:3000" parses with "localhost" as its scheme
+            Ok(_) if has_port(input) => with_scheme(input).map_err(|e| invalid(e.to_string()))?,
+            Ok(url) => url,
+            // a bare host such as "example.com/docs"
+            Err(ParseError::RelativeUrlWithoutBase) => with_scheme(input).map_err(|e| invalid(e.to_string()))?,
+            Err(e) => return Err(invalid(e.to_string())),
+        }
+    };
+
+    let scheme = url.scheme();
+    if !DEFAULT_SCHEMES.contains(&scheme) && !allowed_schemes.iter().any(|allowed| allowed.eq_ignore_ascii_case(scheme))
+    {
+        return Err(invalid(format!("the {scheme} scheme is not allowed")));
+    }
+    Ok(url.to_string())
+}
+
+/// Whether `input` starts with a host and a port such as `example.com:8080/x`, rather than a scheme.
+fn has_port(input: &str) -> bool {
+    let Some((_, rest)) = input.split_once(':') else { return false };
+    let port = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    !port.is_empty() && port.bytes().all(|byte| byte.is_ascii_digit())
+}
+
+/// Parses a URL given without a scheme, as `http` for the local machine, which rarely serves HTTPS, and
+/// `https` otherwise.
+fn with_scheme(input: &str) -> Result<Url, ParseError> {
+    let host = input.split(['/', '?', '#']).next().unwrap_or_default();
+    let host = host.rsplit_once(':').filter(|_| has_port(input)).map_or(host, |(host, _)| host);
+    let scheme = match host {
+        "localhost" | "127.0.0.1" | "[::1]" => "http",
+        _ => "https",
+    };
+    Url::parse(&format!("{scheme}://{input}"))
+}
+
+/// Turns a local file into a `file://` URI for `BrowserFinder::launch_file`, resolving it to an absolute
+/// path without symlinks first. The path must exist.
+///
+/// Spaces and non-ASCII characters are percent-encoded, and Windows drive and UNC paths, including the
+/// `\\?\` forms returned by `fs::canonicalize`, are mapped, e.g. `\\?\UNC\server\share` to
+/// `file://server/share`.
+pub(crate) fn file_url(path: &Path) -> Result<String, LaunchError> {
+    let invalid = |reason: &str| LaunchError::InvalidUrl(format!("{}: {reason}", path.display()));
+    let canonical = fs::canonicalize(path).map_err(|e| invalid(&e.to_string()))?;
+    Url::from_file_path(&canonical).map(String::from).map_err(|_| invalid("cannot be represented as a file URI"))
+}
+
+/// Paths are recognized before URLs since a Windows path such as `C:\index.html` also parses as a URL.
+fn is_local_path(input: &str) -> bool {
+    let path = Path::new(input);
+    path.is_absolute() || input.starts_with("./") || input.starts_with("../") || (!input.contains(':') && path.exists())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::urls::{file_url, normalize};
+
+    #[test]
+    fn test_normalize() {
+        let file = ["file".to_string()];
+
+        assert_eq!(normalize(" https://example.com/a b?q=1 ", &[]).unwrap(), "https://example.com/a%20b?q=1");
+        assert_eq!(normalize("example.com/docs", &[]).unwrap(), "https://example.com/docs");
+        assert_eq!(normalize("localhost:3000", &[]).unwrap(), "http://localhost:3000/");
+        assert_eq!(normalize("example.com:8080/x", &[]).unwrap(), "https://example.com:8080/x");
+        assert_eq!(normalize("about:blank", &[]).unwrap(), "about:blank");
+        assert!(normalize("javascript:alert(1)", &[]).is_err());
+        assert!(normalize("file:///etc/passwd", &[]).is_err());
+        assert_eq!(normalize("file:///etc/passwd", &file).unwrap(), "file:///etc/passwd");
+
+        #[cfg(not(target_os = "windows"))]
+        {
+            assert!(normalize("/tmp/my page.html", &[]).is_err());
+            assert_eq!(normalize("/tmp/my page.html", &file).unwrap(), "file:///tmp/my%20page.html");
+        }
+        #[cfg(target_os = "windows")]
+        assert_eq!(normalize(r"C:\my page.html", &file).unwrap(), "file:///C:/my%20page.html");
+    }
+
+    #[test]
+    fn test_file_url() {
+        use std::{env, fs};
+
+        let dir = env::temp_dir().join(format!("rsbrowsers-file {}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("résumé #1.pdf");
+        fs::write(&path, "%PDF-1.7").unwrap();
+
+        let url = file_url(&dir.join(".").join("résumé #1.pdf")).unwrap();
+        assert!(url.starts_with("file:///"), "{url}");
+        assert!(url.ends_with(&format!("/rsbrowsers-file%20{}/r%C3%A9sum%C3%A9%20%231.pdf", std::process::id())));
+        assert!(file_url(&dir.join("missing.pdf")).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
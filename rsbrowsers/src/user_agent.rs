@@ -0,0 +1,77 @@
+use crate::{Browser, Version};
+
+/// The platform part of Chromium's reduced user agent, see <https://www.chromium.org/updates/ua-reduction/>.
+#[cfg(target_os = "macos")]
+const CHROMIUM_PLATFORM: &str = "Macintosh; Intel Mac OS X 10_15_7";
+#[cfg(target_os = "windows")]
+const CHROMIUM_PLATFORM: &str = "Windows NT 10.0; Win64; x64";
+#[cfg(all(target_os = "linux", target_arch = "aarch64"))]
+const CHROMIUM_PLATFORM: &str = "X11; Linux aarch64";
+#[cfg(all(target_os = "linux", not(target_arch = "aarch64")))]
+const CHROMIUM_PLATFORM: &str = "X11; Linux x86_64";
+
+/// Firefox freezes the macOS version with dots instead of underscores.
+#[cfg(target_os = "macos")]
+const FIREFOX_PLATFORM: &str = "Macintosh; Intel Mac OS X 10.15";
+#[cfg(not(target_os = "macos"))]
+const FIREFOX_PLATFORM: &str = CHROMIUM_PLATFORM;
+
+impl Browser {
+    /// Builds the user agent the browser sends by default on this platform, e.g.
+    /// `Mozilla/5.0 (X11; Linux x86_64; rv:121.0) Gecko/20100101 Firefox/121.0`.
+    ///
+    /// Returns `None` for browsers without a Chrome, Edge, Firefox or Safari template, such as Vivaldi whose
+    /// version isn't the Chromium version it reports, and for browsers without a known version.
+    pub fn user_agent(&self) -> Option<String> {
+        user_agent(self.family(), &self.parsed_version()?, CHROMIUM_PLATFORM, FIREFOX_PLATFORM)
+    }
+}
+
+fn user_agent(family: &str, version: &Version, chromium_platform: &str, firefox_platform: &str) -> Option<String> {
+    // Chromium reports only the major version, see the UA reduction link above
+    let chrome = |product: &str| {
+        format!(
+            "Mozilla/5.0 ({chromium_platform}) AppleWebKit/537.36 (KHTML, like Gecko) {product}/{}.0.0.0 Safari/537.36",
+            version.major()
+        )
+    };
+
+    match family {
+        "chrome" | "chromium" | "chrome-test" | "brave" | "webview2" => Some(chrome("Chrome")),
+        "chrome-headless-shell" => Some(chrome("HeadlessChrome")),
+        "msedge" => Some(format!("{} Edg/{}.0.0.0", chrome("Chrome"), version.major())),
+        "firefox" | "librewolf" => Some(format!(
+            "Mozilla/5.0 ({firefox_platform}; rv:{major}.0) Gecko/20100101 Firefox/{major}.0",
+            major = version.major()
+        )),
+        "safari" => Some(format!(
+            "Mozilla/5.0 ({chromium_platform}) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/{version} \
+             Safari/605.1.15"
+        )),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::user_agent::user_agent;
+
+    #[test]
+    fn test_user_agent() {
+        let windows = "Windows NT 10.0; Win64; x64";
+        let user_agent = |family, version: &str| user_agent(family, &version.parse().unwrap(), windows, windows);
+
+        assert_eq!(
+            user_agent("chrome", "120.0.6099.109").unwrap(),
+            "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 \
+             Safari/537.36"
+        );
+        assert!(user_agent("msedge", "120.0.2210.91").unwrap().ends_with("Safari/537.36 Edg/120.0.0.0"));
+        assert_eq!(
+            user_agent("firefox", "121.0.1").unwrap(),
+            "Mozilla/5.0 (Windows NT 10.0; Win64; x64; rv:121.0) Gecko/20100101 Firefox/121.0"
+        );
+        assert!(user_agent("safari", "17.2.1").unwrap().contains("Version/17.2.1 Safari/605.1.15"));
+        assert_eq!(user_agent("vivaldi", "6.5.3206.48"), None);
+    }
+}
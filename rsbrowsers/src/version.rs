@@ -0,0 +1,363 @@
+use crate::{Channel, Engine};
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::io::Read;
+use std::process::{Command, Stdio};
+use std::str::FromStr;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+/// How long a version probe may run unless set with `BrowserFinder::with_probe_timeout`.
+pub(crate) const DEFAULT_PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A browser version parsed into its numeric components, e.g. `120.0.6099.109`.
+///
+/// Missing trailing components compare as zero, so `120` and `120.0` are equal.
+#[derive(Clone, Debug)]
+pub struct Version {
+    components: Vec<u64>,
+}
+
+/// A version requirement such as `>=120`, `^121.0` or `>=115, <120`.
+///
+/// Comparators are separated by commas and must all match. Partial versions compare only the
+/// components they specify, so `<120` excludes every `120.x` release and `=120` matches all of them.
+/// A comparator without an operator behaves like `^`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VersionReq {
+    comparators: Vec<Comparator>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Comparator {
+    op: Op,
+    components: Vec<u64>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Op {
+    Exact,
+    Greater,
+    GreaterEq,
+    Less,
+    LessEq,
+    Tilde,
+    Caret,
+    Wildcard,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VersionError {
+    input: String,
+}
+
+impl fmt::Display for VersionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid version: {:?}", self.input)
+    }
+}
+
+impl std::error::Error for VersionError {}
+
+impl Version {
+    pub fn new(components: Vec<u64>) -> Self {
+        Version { components }
+    }
+
+    pub fn components(&self) -> &[u64] {
+        &self.components
+    }
+
+    pub fn major(&self) -> u64 {
+        self.component(0)
+    }
+
+    fn component(&self, index: usize) -> u64 {
+        self.components.get(index).copied().unwrap_or(0)
+    }
+
+    /// Compares only the first `len` components of both versions.
+    fn cmp_prefix(&self, other: &[u64], len: usize) -> Ordering {
+        (0..len)
+            .map(|i| self.component(i).cmp(&other.get(i).copied().unwrap_or(0)))
+            .find(|o| o.is_ne())
+            .unwrap_or(Ordering::Equal)
+    }
+
+    fn trimmed(&self) -> &[u64] {
+        let len = self.components.iter().rposition(|c| *c != 0).map_or(0, |i| i + 1);
+        &self.components[..len]
+    }
+}
+
+/// Finds the first word of `text` that parses as a version, e.g. in `--version` output such as
+/// "ChromeDriver 120.0.6099.109 (...)".
+pub(crate) fn find_version(text: &str) -> Option<Version> {
+    text.split_whitespace().find_map(|word| word.parse().ok())
+}
+
+/// Converts the version a platform reports for a browser into the one the browser shows users, `None` if
+/// they are the same. Brave prefixes its own version with the Chromium major, e.g. `120.1.61.109` for
+/// `1.61.109`, and the file versions of Gecko browsers on Windows append a build number, e.g.
+/// `121.0.0.8716` for `121.0`.
+pub(crate) fn product_version(browser_type: &str, raw: &str) -> Option<String> {
+    let components: Vec<&str> = raw.split('.').collect();
+    let numeric = |component: &&str| !component.is_empty() && component.bytes().all(|byte| byte.is_ascii_digit());
+    if components.len() != 4 || !components.iter().all(numeric) {
+        return None;
+    }
+
+    if Channel::split(browser_type).0 == "brave" {
+        return Some(components[1..].join("."));
+    }
+    if Engine::of(browser_type) == Engine::Gecko {
+        // major releases have two components, e.g. "121.0", dot and ESR releases three, e.g. "115.6.0"
+        return Some(match components[1..3] {
+            ["0", "0"] => format!("{}.0", components[0]),
+            _ => components[..3].join("."),
+        });
+    }
+    None
+}
+
+/// Runs a version probe such as `chrome --version` and returns its standard output, or `None` if it
+/// cannot be started or does not finish writing within `timeout`, in which case it is killed.
+pub(crate) fn probe_output(command: &mut Command, timeout: Duration) -> Option<String> {
+    #[cfg(feature = "tracing")]
+    let started = std::time::Instant::now();
+    let mut child = command.stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::null()).spawn().ok()?;
+    let mut stdout = child.stdout.take()?;
+
+    // read on another thread since a hung wrapper may never close its output
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let mut output = vec![];
+        let _ = stdout.read_to_end(&mut output);
+        let _ = sender.send(output);
+    });
+    let output = receiver.recv_timeout(timeout).ok();
+
+    if output.is_none() {
+        let _ = child.kill();
+    }
+    let _ = child.wait();
+    #[cfg(feature = "tracing")]
+    tracing::debug!(
+        program = ?command.get_program(),
+        args = ?command.get_args().collect::<Vec<_>>(),
+        elapsed = ?started.elapsed(),
+        timed_out = output.is_none(),
+        "version probe"
+    );
+    output.map(|output| String::from_utf8_lossy(&output).to_string())
+}
+
+impl FromStr for Version {
+    type Err = VersionError;
+
+    /// Parses the leading dotted number of a version string, ignoring a `v` prefix and any suffix
+    /// such as ` (Official Build)` or `a1`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || VersionError { input: s.to_string() };
+        let trimmed = s.trim();
+        let trimmed = trimmed.strip_prefix(['v', 'V']).unwrap_or(trimmed);
+
+        let mut components = vec![];
+        for part in trimmed.split('.') {
+            let digits: String = part.chars().take_while(|c| c.is_ascii_digit()).collect();
+            if digits.is_empty() {
+                break;
+            }
+            components.push(digits.parse().map_err(|_| error())?);
+            if digits.len() != part.len() {
+                break;
+            }
+        }
+
+        if components.is_empty() { Err(error()) } else { Ok(Version { components }) }
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts: Vec<String> = self.components.iter().map(|c| c.to_string()).collect();
+        write!(f, "{}", parts.join("."))
+    }
+}
+
+impl serde::Serialize for Version {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Version {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let version = String::deserialize(deserializer)?;
+        version.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl PartialEq for Version {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Version {}
+
+impl Hash for Version {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.trimmed().hash(state);
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.cmp_prefix(&other.components, self.components.len().max(other.components.len()))
+    }
+}
+
+impl VersionReq {
+    pub fn parse(requirement: &str) -> Result<Self, VersionError> {
+        let error = || VersionError { input: requirement.to_string() };
+
+        let comparators = requirement
+            .split(',')
+            .map(|comparator| Comparator::parse(comparator.trim()).ok_or_else(error))
+            .collect::<Result<Vec<Comparator>, VersionError>>()?;
+
+        Ok(VersionReq { comparators })
+    }
+
+    pub fn matches(&self, version: &Version) -> bool {
+        self.comparators.iter().all(|comparator| comparator.matches(version))
+    }
+}
+
+impl FromStr for VersionReq {
+    type Err = VersionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        VersionReq::parse(s)
+    }
+}
+
+impl Comparator {
+    fn parse(comparator: &str) -> Option<Self> {
+        if comparator == "*" {
+            return Some(Comparator { op: Op::Wildcard, components: vec![] });
+        }
+
+        let (op, rest) = [
+            (">=", Op::GreaterEq),
+            ("<=", Op::LessEq),
+            (">", Op::Greater),
+            ("<", Op::Less),
+            ("=", Op::Exact),
+            ("~", Op::Tilde),
+            ("^", Op::Caret),
+        ]
+        .iter()
+        .find_map(|(prefix, op)| comparator.strip_prefix(prefix).map(|rest| (*op, rest)))
+        .unwrap_or((Op::Caret, comparator));
+
+        let components = rest
+            .trim()
+            .trim_end_matches(".*")
+            .split('.')
+            .map(|part| part.parse().ok())
+            .collect::<Option<Vec<u64>>>()?;
+
+        Some(Comparator { op, components })
+    }
+
+    fn matches(&self, version: &Version) -> bool {
+        let len = self.components.len();
+        let prefix = version.cmp_prefix(&self.components, len);
+
+        match self.op {
+            Op::Wildcard => true,
+            Op::Exact => prefix.is_eq(),
+            Op::Greater => prefix.is_gt(),
+            Op::GreaterEq => prefix.is_ge(),
+            Op::Less => prefix.is_lt(),
+            Op::LessEq => prefix.is_le(),
+            Op::Tilde => prefix.is_ge() && version.cmp_prefix(&self.components, len.min(2)).is_eq(),
+            Op::Caret => {
+                let significant = self.components.iter().position(|c| *c != 0).unwrap_or(len.saturating_sub(1));
+                prefix.is_ge() && version.cmp_prefix(&self.components, significant + 1).is_eq()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::version::{Version, VersionReq, probe_output, product_version};
+
+    fn matches(requirement: &str, version: &str) -> bool {
+        VersionReq::parse(requirement).unwrap().matches(&version.parse::<Version>().unwrap())
+    }
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!("120.0.6099.109".parse::<Version>().unwrap().components(), &[120, 0, 6099, 109]);
+        assert_eq!("v17.4.1 (Official Build)".parse::<Version>().unwrap().components(), &[17, 4, 1]);
+        assert_eq!("128.0a1".parse::<Version>().unwrap().components(), &[128, 0]);
+        assert_eq!("120".parse::<Version>().unwrap(), "120.0.0".parse::<Version>().unwrap());
+        assert!("".parse::<Version>().is_err());
+    }
+
+    #[test]
+    fn test_version_req() {
+        assert!(matches(">=120", "120.0.6099.109"));
+        assert!(!matches(">=120", "119.0.6045.199"));
+        assert!(matches("^121.0", "121.5.1"));
+        assert!(!matches("^121.0", "122.0.1"));
+        assert!(matches(">=115, <120", "119.9"));
+        assert!(!matches(">=115, <120", "120.0.1"));
+        assert!(!matches(">120", "120.0.6099.109"));
+        assert!(matches("~17.4", "17.4.1"));
+        assert!(!matches("~17.4", "17.5"));
+        assert!(matches("=120", "120.1"));
+        assert!(matches("*", "1"));
+        assert!(VersionReq::parse(">=abc").is_err());
+    }
+
+    #[test]
+    fn test_product_version() {
+        assert_eq!(product_version("brave", "120.1.61.109"), Some("1.61.109".to_string()));
+        assert_eq!(product_version("brave-beta", "121.1.62.97"), Some("1.62.97".to_string()));
+        assert_eq!(product_version("brave", "1.61.109"), None);
+        assert_eq!(product_version("firefox", "121.0.0.8716"), Some("121.0".to_string()));
+        assert_eq!(product_version("firefox", "121.0.1.8720"), Some("121.0.1".to_string()));
+        assert_eq!(product_version("firefox-esr", "115.6.0.8700"), Some("115.6.0".to_string()));
+        assert_eq!(product_version("firefox", "121.0"), None);
+        assert_eq!(product_version("msedge", "120.0.2210.91"), None);
+        assert_eq!(product_version("chrome", "120.0.6099.109"), None);
+        assert_eq!(product_version("brave", "120.1.61.x"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_probe_output() {
+        use std::process::Command;
+        use std::time::{Duration, Instant};
+
+        let output = probe_output(Command::new("sh").args(["-c", "echo Chromium 120.0.1"]), Duration::from_secs(5));
+        assert_eq!(output.as_deref(), Some("Chromium 120.0.1\n"));
+
+        let started = Instant::now();
+        assert_eq!(probe_output(Command::new("sleep").arg("10"), Duration::from_millis(100)), None);
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}
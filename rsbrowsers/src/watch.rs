@@ -0,0 +1,162 @@
+#[cfg(target_os = "linux")]
+use crate::desktop_files::application_dirs;
+use crate::{Browser, BrowserFinder, Browsers, Change, Error};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::BTreeSet;
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+use std::env;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+/// How long the watched directories must stay quiet before rescanning, since installers and updaters
+/// write many files.
+const WATCH_DEBOUNCE: Duration = Duration::from_secs(2);
+
+/// A change to the installed browsers, see `BrowserFinder::watch`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum BrowserEvent {
+    Installed(Browser),
+    Removed(Browser),
+    /// The browser at the same path now reports a different version.
+    Updated(Browser),
+}
+
+/// Delivers `BrowserEvent`s until dropped.
+pub struct BrowserWatcher {
+    _watcher: RecommendedWatcher,
+    events: Receiver<BrowserEvent>,
+}
+
+impl BrowserWatcher {
+    /// The channel events are sent on. It disconnects if the watcher stops, which only happens when it is
+    /// dropped.
+    pub fn events(&self) -> &Receiver<BrowserEvent> {
+        &self.events
+    }
+}
+
+impl BrowserFinder {
+    /// Watches the directories browsers are installed into and emits an event for each browser matching
+    /// the filters that is installed, removed or updated.
+    ///
+    /// Changes trigger a rescan once they settle, so events arrive a few seconds after an installer
+    /// finishes. The Applications folders are watched on macOS, the program directories on Windows and the
+    /// desktop entry directories on Linux, plus the directories of the browsers found by the first scan and
+    /// the search paths. The cache is never used.
+    pub fn watch(&self) -> Result<BrowserWatcher, Error> {
+        let mut finder = self.clone();
+        finder.cache_ttl = None;
+        let mut browsers: Vec<Browser> = finder.try_all()?.collect();
+
+        let (change_sender, changes) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            if event.is_ok() {
+                let _ = change_sender.send(());
+            }
+        })
+        .map_err(|e| Error::Discovery(format!("cannot watch for installs: {e}")))?;
+
+        let dirs = [watch_dirs(), finder.search_paths.clone(), browsers.iter().filter_map(browser_dir).collect()]
+            .concat()
+            .into_iter()
+            .filter(|dir| dir.is_dir())
+            .collect::<BTreeSet<_>>();
+        for dir in dirs {
+            watcher
+                .watch(&dir, RecursiveMode::NonRecursive)
+                .map_err(|e| Error::Discovery(format!("cannot watch {}: {e}", dir.display())))?;
+        }
+
+        let (sender, events) = mpsc::channel();
+        thread::spawn(move || {
+            // ends when the watcher, and with it the sender of changes, is dropped
+            while changes.recv().is_ok() {
+                loop {
+                    match changes.recv_timeout(WATCH_DEBOUNCE) {
+                        Ok(()) => continue,
+                        Err(RecvTimeoutError::Timeout) => break,
+                        Err(RecvTimeoutError::Disconnected) => return,
+                    }
+                }
+                // keep the last results if the sources can't be read mid-install
+                let Ok(current) = finder.try_all() else { continue };
+                let current: Vec<Browser> = current.collect();
+                for event in browser_events(&browsers, &current) {
+                    if sender.send(event).is_err() {
+                        return;
+                    }
+                }
+                browsers = current;
+            }
+        });
+
+        Ok(BrowserWatcher { _watcher: watcher, events })
+    }
+}
+
+/// The directories browsers are installed into on this platform.
+#[cfg(target_os = "macos")]
+fn watch_dirs() -> Vec<PathBuf> {
+    let home = env::var_os("HOME").map(|home| Path::new(&home).join("Applications"));
+    [Some(PathBuf::from("/Applications")), home].into_iter().flatten().collect()
+}
+
+#[cfg(target_os = "windows")]
+fn watch_dirs() -> Vec<PathBuf> {
+    ["ProgramFiles", "ProgramFiles(x86)", "LOCALAPPDATA"]
+        .iter()
+        .filter_map(env::var_os)
+        .map(PathBuf::from)
+        .chain(dirs::data_local_dir().map(|dir| dir.join("Programs")))
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+fn watch_dirs() -> Vec<PathBuf> {
+    application_dirs()
+}
+
+/// The directory holding a browser's executable, which updaters write to.
+fn browser_dir(browser: &Browser) -> Option<PathBuf> {
+    browser.path.parent().filter(|dir| *dir != Path::new("")).map(Path::to_path_buf)
+}
+
+/// Maps the changes between two scans to events.
+fn browser_events(previous: &[Browser], current: &[Browser]) -> Vec<BrowserEvent> {
+    Browsers::diff(previous, current)
+        .into_iter()
+        .map(|change| match change {
+            Change::Added(browser) => BrowserEvent::Installed(browser),
+            Change::Removed(browser) => BrowserEvent::Removed(browser),
+            Change::VersionChanged { new, .. } => BrowserEvent::Updated(new),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Browser;
+    use crate::watch::{BrowserEvent, browser_events};
+
+    #[test]
+    fn test_browser_events() {
+        let browser = |browser_type: &str, version: &str| Browser {
+            version: version.to_string(),
+            ..Browser::test_fixture(browser_type, format!("/opt/{browser_type}/{browser_type}"))
+        };
+
+        let previous = [browser("chrome", "120.0"), browser("firefox", "121.0")];
+        let current = [browser("chrome", "121.0"), browser("brave", "1.61")];
+
+        assert_eq!(
+            browser_events(&previous, &current),
+            [
+                BrowserEvent::Updated(browser("chrome", "121.0")),
+                BrowserEvent::Installed(browser("brave", "1.61")),
+                BrowserEvent::Removed(browser("firefox", "121.0")),
+            ]
+        );
+    }
+}
@@ -0,0 +1,170 @@
+use crate::version::{DEFAULT_PROBE_TIMEOUT, find_version, probe_output};
+use crate::{Browser, Version};
+use glob::glob;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+#[cfg(target_os = "macos")]
+const DRIVER_DIRS: &[&str] = &["/usr/local/bin", "/opt/homebrew/bin", "/usr/bin"];
+#[cfg(target_os = "linux")]
+const DRIVER_DIRS: &[&str] =
+    &["/usr/local/bin", "/usr/bin", "/usr/lib/chromium", "/usr/lib/chromium-browser", "/snap/bin"];
+#[cfg(target_os = "windows")]
+const DRIVER_DIRS: &[&str] = &[r"C:\ProgramData\chocolatey\bin", r"C:\WebDriver", r"C:\WebDriver\bin"];
+
+/// A WebDriver binary found for a browser.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct WebDriver {
+    /// Driver name, e.g. `chromedriver`.
+    pub name: String,
+    #[serde(serialize_with = "crate::serialize_path")]
+    pub path: PathBuf,
+    pub version: String,
+    /// Whether the driver's major version matches the browser's.
+    ///
+    /// `None` for drivers that are not versioned with the browser (geckodriver, safaridriver) and if the
+    /// browser's or the driver's version is unknown.
+    pub version_matches: Option<bool>,
+}
+
+/// Returns the driver name for a browser type, e.g. `chromedriver` for `chrome-beta`.
+pub fn driver_name(browser_type: &str) -> Option<&'static str> {
+    match browser_type {
+        t if t.starts_with("msedge") => Some("msedgedriver"),
+        t if t.starts_with("chrome") || t.starts_with("chromium") => Some("chromedriver"),
+        t if t.starts_with("safari") => Some("safaridriver"),
+        t if t.starts_with("firefox") || t == "librewolf" || t == "waterfox" => Some("geckodriver"),
+        _ => None,
+    }
+}
+
+/// Finds every driver for `browser` on PATH, in Selenium Manager's cache and in common install
+/// directories, in that order. A driver whose `--version` doesn't answer within 5 seconds gets an empty
+/// version.
+pub fn find(browser: &Browser) -> Vec<WebDriver> {
+    let Some(name) = driver_name(browser.browser_type.as_str()) else {
+        return vec![];
+    };
+
+    let mut paths: Vec<PathBuf> = vec![];
+    for path in candidates(browser, name) {
+        if path.is_file() && !paths.contains(&path) {
+            paths.push(path);
+        }
+    }
+
+    let browser_version = browser.parsed_version();
+    paths
+        .into_iter()
+        .map(|path| {
+            let version = driver_version(&path);
+            let version_matches = version_matches(name, browser_version.as_ref(), version.as_ref());
+            WebDriver {
+                name: name.to_string(),
+                path,
+                version: version.map_or("".to_string(), |v| v.to_string()),
+                version_matches,
+            }
+        })
+        .collect()
+}
+
+/// Compares the major versions of Chromium drivers with the browser's, `None` if either is unknown, e.g. because
+/// version probes are skipped, so such drivers are not ruled out.
+fn version_matches(name: &str, browser_version: Option<&Version>, version: Option<&Version>) -> Option<bool> {
+    match name {
+        "chromedriver" | "msedgedriver" => {
+            browser_version.zip(version).map(|(browser_version, version)| browser_version.major() == version.major())
+        }
+        _ => None,
+    }
+}
+
+/// Finds the first driver for `browser` whose version is known to be compatible.
+pub fn find_compatible(browser: &Browser) -> Option<WebDriver> {
+    find(browser).into_iter().find(|driver| driver.version_matches != Some(false))
+}
+
+fn candidates(browser: &Browser, name: &str) -> Vec<PathBuf> {
+    let executable = format!("{name}{}", env::consts::EXE_SUFFIX);
+    let mut candidates = vec![];
+
+    // Safari Technology Preview bundles its own driver
+    if browser.browser_type == "safari-technology-preview" {
+        candidates.push(browser.path.with_file_name(&executable));
+    }
+
+    if let Some(path) = env::var_os("PATH") {
+        candidates.extend(env::split_paths(&path).map(|dir| dir.join(&executable)));
+    }
+
+    if let Some(home) = dirs::home_dir() {
+        let pattern = home.join(".cache/selenium").join(name).join("*/*").join(&executable);
+        if let Ok(paths) = glob(pattern.to_string_lossy().as_ref()) {
+            let mut paths: Vec<PathBuf> = paths.filter_map(Result::ok).collect();
+            // newest driver first
+            paths.sort_by_key(|path| std::cmp::Reverse(path.parent().and_then(|p| p.file_name()).map(version_key)));
+            candidates.extend(paths);
+        }
+    }
+
+    candidates.extend(DRIVER_DIRS.iter().map(|dir| Path::new(dir).join(&executable)));
+    candidates
+}
+
+fn version_key(name: &std::ffi::OsStr) -> Option<Version> {
+    name.to_str().and_then(|name| name.parse().ok())
+}
+
+fn driver_version(path: &Path) -> Option<Version> {
+    let output = probe_output(Command::new(path).arg("--version"), DEFAULT_PROBE_TIMEOUT)?;
+    // e.g. "ChromeDriver 120.0.6099.109 (...)" or "Included with Safari 17.4.1 (...)"
+    find_version(&output)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Version;
+    use crate::webdriver::{driver_name, version_matches};
+
+    #[test]
+    fn test_driver_name() {
+        assert_eq!(driver_name("chrome-beta"), Some("chromedriver"));
+        assert_eq!(driver_name("chromium"), Some("chromedriver"));
+        assert_eq!(driver_name("msedge-dev"), Some("msedgedriver"));
+        assert_eq!(driver_name("firefox-nightly"), Some("geckodriver"));
+        assert_eq!(driver_name("safari"), Some("safaridriver"));
+        assert_eq!(driver_name("opera"), None);
+    }
+
+    #[test]
+    fn test_version_matches() {
+        let version = |version: &str| version.parse::<Version>().unwrap();
+        let (v120, v121) = (version("120.0.6099.109"), version("121.0.6167.85"));
+
+        assert_eq!(version_matches("chromedriver", Some(&v120), Some(&v120)), Some(true));
+        assert_eq!(version_matches("msedgedriver", Some(&v121), Some(&v120)), Some(false));
+        // an unversioned browser, e.g. found with version probes skipped, doesn't rule drivers out
+        assert_eq!(version_matches("chromedriver", None, Some(&v120)), None);
+        assert_eq!(version_matches("chromedriver", Some(&v120), None), None);
+        assert_eq!(version_matches("geckodriver", Some(&v120), Some(&v120)), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_driver_version() {
+        use crate::webdriver::driver_version;
+        use std::os::unix::fs::PermissionsExt;
+        use std::{env, fs};
+
+        let path = env::temp_dir().join(format!("rsbrowsers-chromedriver-{}", std::process::id()));
+        fs::write(&path, "#!/bin/sh\necho 'ChromeDriver 120.0.6099.109 (3419140ab665)'\n").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        let version = driver_version(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(version.map(|version| version.to_string()).as_deref(), Some("120.0.6099.109"));
+    }
+}
@@ -0,0 +1,140 @@
+//! Detection of the Microsoft Edge WebView2 runtime, see
+//! <https://learn.microsoft.com/en-us/microsoft-edge/webview2/concepts/distribution>.
+
+use crate::{Browser, InstallScope, InstallSource, get_version_info};
+use std::env;
+use std::path::{Path, PathBuf};
+use winreg::RegKey;
+use winreg::enums::{HKEY, HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE};
+
+const EVERGREEN_CLIENT_KEYS: &[(HKEY, &str, InstallScope)] = &[
+    // registry root, EdgeUpdate client key, install scope
+    (
+        HKEY_LOCAL_MACHINE,
+        r"Software\WOW6432Node\Microsoft\EdgeUpdate\Clients\{F3017226-FE2A-4295-8BDF-00C3A9A7E4C5}",
+        InstallScope::System,
+    ),
+    (
+        HKEY_LOCAL_MACHINE,
+        r"Software\Microsoft\EdgeUpdate\Clients\{F3017226-FE2A-4295-8BDF-00C3A9A7E4C5}",
+        InstallScope::System,
+    ),
+    (
+        HKEY_CURRENT_USER,
+        r"Software\Microsoft\EdgeUpdate\Clients\{F3017226-FE2A-4295-8BDF-00C3A9A7E4C5}",
+        InstallScope::User,
+    ),
+];
+
+/// Set by apps that ship a fixed-version runtime to point WebView2 at its folder.
+const FIXED_VERSION_FOLDER_VARIABLE: &str = "WEBVIEW2_BROWSER_EXECUTABLE_FOLDER";
+
+const EXECUTABLE: &str = "msedgewebview2.exe";
+
+/// How a WebView2 runtime is distributed.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub enum RuntimeKind {
+    /// Installed once and kept up to date by Edge Update.
+    Evergreen,
+    /// Shipped with an app, found through `WEBVIEW2_BROWSER_EXECUTABLE_FOLDER`.
+    FixedVersion,
+}
+
+/// A WebView2 runtime found on the machine.
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, Eq, Hash, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Runtime {
+    pub kind: RuntimeKind,
+    pub version: String,
+    /// The `msedgewebview2.exe` executable.
+    pub path: PathBuf,
+    pub install_scope: InstallScope,
+}
+
+impl Runtime {
+    /// Returns the runtime as a `webview2` browser entry, e.g. to list it alongside browsers.
+    pub fn browser(&self) -> Browser {
+        Browser {
+            browser_type: "webview2".to_string(),
+            path: self.path.clone(),
+            exec: None,
+            args: vec![],
+            display_name: "Microsoft Edge WebView2 Runtime".to_string(),
+            version: self.version.to_owned(),
+            raw_version: None,
+            install_source: InstallSource::System,
+            source_id: None,
+            source_path: None,
+            package: None,
+        }
+    }
+}
+
+/// Finds the Evergreen runtimes registered with Edge Update for the machine and the current user, followed
+/// by the fixed-version runtime from `WEBVIEW2_BROWSER_EXECUTABLE_FOLDER` if it is set.
+pub fn detect() -> Vec<Runtime> {
+    let mut runtimes: Vec<Runtime> = vec![];
+
+    for (root, key, install_scope) in EVERGREEN_CLIENT_KEYS {
+        let Ok(client) = RegKey::predef(*root).open_subkey(key) else { continue };
+        let Some(version) = client.get_value::<String, _>("pv").ok().and_then(installed_version) else {
+            continue;
+        };
+        let location = match client.get_value::<String, _>("location") {
+            Ok(location) => PathBuf::from(location),
+            Err(_) => default_location(*install_scope),
+        };
+        let path = location.join(&version).join(EXECUTABLE);
+        // 64-bit Windows mirrors the machine key, so skip the second copy
+        if runtimes.iter().any(|runtime| runtime.path == path) || !path.is_file() {
+            continue;
+        }
+
+        runtimes.push(Runtime { kind: RuntimeKind::Evergreen, version, path, install_scope: *install_scope });
+    }
+
+    if let Some(folder) = env::var_os(FIXED_VERSION_FOLDER_VARIABLE) {
+        let path = Path::new(&folder).join(EXECUTABLE);
+        if path.is_file() {
+            runtimes.push(Runtime {
+                kind: RuntimeKind::FixedVersion,
+                version: get_version_info(&path),
+                install_scope: InstallScope::of(&path),
+                path,
+            });
+        }
+    }
+
+    runtimes
+}
+
+/// Edge Update leaves `pv` empty or at `0.0.0.0` once the runtime is uninstalled.
+fn installed_version(pv: String) -> Option<String> {
+    match pv.trim() {
+        "" | "0.0.0.0" => None,
+        version => Some(version.to_string()),
+    }
+}
+
+fn default_location(install_scope: InstallScope) -> PathBuf {
+    let base = match install_scope {
+        InstallScope::System => {
+            env::var_os("ProgramFiles(x86)").or(env::var_os("ProgramFiles")).map(PathBuf::from).unwrap_or_default()
+        }
+        InstallScope::User => dirs::data_local_dir().unwrap_or_default(),
+    };
+    base.join(r"Microsoft\EdgeWebView\Application")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::webview2::installed_version;
+
+    #[test]
+    fn test_installed_version() {
+        assert_eq!(installed_version("120.0.2210.91".to_string()), Some("120.0.2210.91".to_string()));
+        assert_eq!(installed_version("0.0.0.0".to_string()), None);
+        assert_eq!(installed_version("".to_string()), None);
+    }
+}
@@ -0,0 +1,223 @@
+//! Windows browsers seen from the Windows Subsystem for Linux, which can run Windows executables directly.
+
+use crate::version::probe_output;
+use crate::{Browser, InstallSource, ScanContext, cmdline, pe, windows_browser_type};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+const START_MENU_INTERNET_LIST: &[&str] = &[
+    // per-machine installs, then per-user ones
+    r"HKLM\SOFTWARE\Clients\StartMenuInternet",
+    r"HKCU\SOFTWARE\Clients\StartMenuInternet",
+];
+
+const WINDOWS_PROGRAM_LIST: &[&str] = &[
+    // default install locations on drive C, for when reg.exe cannot be run
+    "Program Files/Google/Chrome/Application/chrome.exe",
+    "Program Files (x86)/Google/Chrome/Application/chrome.exe",
+    "Program Files/Microsoft/Edge/Application/msedge.exe",
+    "Program Files (x86)/Microsoft/Edge/Application/msedge.exe",
+    "Program Files/Mozilla Firefox/firefox.exe",
+    "Program Files/BraveSoftware/Brave-Browser/Application/brave.exe",
+];
+
+/// A `StartMenuInternet` entry read with reg.exe.
+#[derive(Debug, Default, PartialEq)]
+struct Entry {
+    key: String,
+    display_name: String,
+    command: String,
+}
+
+/// Returns whether this is a WSL distribution that can run Windows executables.
+pub(crate) fn is_wsl() -> bool {
+    Path::new("/proc/sys/fs/binfmt_misc/WSLInterop").exists()
+        || fs::read_to_string("/proc/version").is_ok_and(|version| version.to_lowercase().contains("microsoft"))
+}
+
+/// Finds the browsers registered in the Windows registry with reg.exe if the context allows running it, or
+/// else in their default install locations.
+pub(crate) fn detect(context: &ScanContext) -> Vec<Browser> {
+    let entries: Vec<Entry> = match context.process_probes() {
+        true => START_MENU_INTERNET_LIST
+            .iter()
+            .take_while(|_| !context.expired())
+            .flat_map(|key| query(key, context.probe_timeout()))
+            .collect(),
+        false => vec![],
+    };
+
+    let found: Vec<(String, PathBuf, String)> = match entries.is_empty() {
+        false => entries
+            .into_iter()
+            .filter_map(|entry| {
+                let executable = executable(&entry.command)?;
+                let browser_type = windows_browser_type(&entry.key, &executable, &entry.display_name)?;
+                Some((browser_type, linux_path(&executable)?, entry.display_name))
+            })
+            .collect(),
+        true => WINDOWS_PROGRAM_LIST
+            .iter()
+            .map(|program| Path::new("/mnt/c").join(program))
+            .filter(|path| path.is_file())
+            .filter_map(|path| {
+                let browser_type = windows_browser_type("", &path.to_string_lossy(), "")?;
+                Some((browser_type.to_owned(), path, browser_type))
+            })
+            .collect(),
+    };
+
+    found
+        .into_iter()
+        .filter(|(_, path, _)| path.is_file())
+        .map(|(browser_type, path, display_name)| Browser {
+            browser_type,
+            version: pe::file_version(&path).unwrap_or_default(),
+            raw_version: None,
+            path,
+            exec: None,
+            args: vec![],
+            display_name,
+            install_source: InstallSource::System,
+            source_id: None,
+            source_path: None,
+            package: None,
+        })
+        .collect()
+}
+
+/// Lists the Program Files directories of drive C and the default install locations, which change when a
+/// browser is installed there. Browsers are also found through the Windows registry, which can only be read
+/// by running reg.exe, so per-user installs elsewhere are not covered.
+pub(crate) fn files() -> Vec<PathBuf> {
+    let program_files = ["Program Files", "Program Files (x86)"].into_iter();
+    program_files.chain(WINDOWS_PROGRAM_LIST.iter().copied()).map(|path| Path::new("/mnt/c").join(path)).collect()
+}
+
+/// Returns `wslview`, which opens URLs in the Windows default browser, if this is WSL and it is installed.
+pub(crate) fn default_browser() -> Option<Browser> {
+    if !is_wsl() {
+        return None;
+    }
+    let path = crate::find_on_path("wslview")?;
+    Some(Browser {
+        browser_type: "wslview".to_string(),
+        path,
+        exec: None,
+        args: vec![],
+        display_name: "Windows default browser".to_string(),
+        version: String::new(),
+        raw_version: None,
+        install_source: InstallSource::Custom,
+        source_id: None,
+        source_path: None,
+        package: None,
+    })
+}
+
+/// Runs `reg.exe query <key> /s`, giving up after `timeout` since the Windows side may be slow to start.
+fn query(key: &str, timeout: Duration) -> Vec<Entry> {
+    match probe_output(Command::new("reg.exe").args(["query", key, "/s"]), timeout) {
+        Some(output) => parse_query(key, &output),
+        None => vec![],
+    }
+}
+
+/// Parses `reg.exe query <key> /s` output, where each key line is followed by its values, e.g.
+/// `    (Default)    REG_SZ    Google Chrome`. The name of the default value is localized but parenthesized.
+fn parse_query(root: &str, output: &str) -> Vec<Entry> {
+    let mut entries: Vec<Entry> = vec![];
+    let mut current = "";
+    let depth = root.split('\\').count();
+
+    for line in output.lines() {
+        if line.starts_with("HKEY_") {
+            current = line.trim();
+            continue;
+        }
+        let mut fields = line.trim().splitn(3, "    ");
+        let (Some(name), Some("REG_SZ" | "REG_EXPAND_SZ"), Some(data)) = (fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        if !name.starts_with('(') {
+            continue;
+        }
+
+        // e.g. "HKEY_LOCAL_MACHINE\SOFTWARE\Clients\StartMenuInternet\Google Chrome\shell\open\command", where
+        // the hive is spelled out and the other names may differ in case from `root`
+        let mut parts = current.splitn(depth + 2, '\\').skip(depth);
+        let Some(key) = parts.next() else { continue };
+
+        if entries.last().is_none_or(|entry| entry.key != key) {
+            entries.push(Entry { key: key.to_string(), ..Entry::default() });
+        }
+        let entry = entries.last_mut().unwrap();
+        match parts.next() {
+            None => entry.display_name = data.trim().to_string(),
+            Some(sub) if sub.eq_ignore_ascii_case(r"shell\open\command") => entry.command = data.trim().to_string(),
+            Some(_) => {}
+        }
+    }
+
+    entries.retain(|entry| !entry.command.is_empty());
+    for entry in &mut entries {
+        if entry.display_name.is_empty() {
+            entry.display_name = entry.key.to_owned();
+        }
+    }
+    entries
+}
+
+/// Takes the executable from a command, which is usually quoted, e.g. `"C:\...\chrome.exe" -osint`.
+fn executable(command: &str) -> Option<String> {
+    let units: Vec<u16> = command.encode_utf16().collect();
+    cmdline::split(&units).first().map(|program| String::from_utf16_lossy(program))
+}
+
+/// Converts a drive path such as `C:\Program Files\...` to `/mnt/c/Program Files/...`, the default mount.
+fn linux_path(path: &str) -> Option<PathBuf> {
+    let (drive, rest) = path.split_once(":\\")?;
+    let drive = drive.chars().next().filter(|_| drive.len() == 1)?.to_ascii_lowercase();
+    Some(Path::new("/mnt").join(drive.to_string()).join(rest.replace('\\', "/")))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::wsl::{Entry, executable, linux_path, parse_query};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_parse_query() {
+        let output = "\r\nHKEY_LOCAL_MACHINE\\SOFTWARE\\Clients\\StartMenuInternet\\Google Chrome\r\n    \
+                      (Standard)    REG_SZ    Google Chrome\r\n\r\n\
+                      HKEY_LOCAL_MACHINE\\SOFTWARE\\Clients\\StartMenuInternet\\Google Chrome\\Capabilities\r\n    \
+                      ApplicationName    REG_SZ    Google Chrome\r\n\r\n\
+                      HKEY_LOCAL_MACHINE\\SOFTWARE\\Clients\\StartMenuInternet\\Google Chrome\\shell\\open\\command\r\n    \
+                      (Standard)    REG_SZ    \"C:\\Program Files\\Google\\Chrome\\Application\\chrome.exe\"\r\n\r\n\
+                      HKEY_LOCAL_MACHINE\\SOFTWARE\\Clients\\StartMenuInternet\\IEXPLORE.EXE\r\n    \
+                      (Standard)    REG_SZ    Internet Explorer\r\n";
+
+        assert_eq!(
+            parse_query(r"HKLM\SOFTWARE\Clients\StartMenuInternet", output),
+            [Entry {
+                key: "Google Chrome".to_string(),
+                display_name: "Google Chrome".to_string(),
+                command: r#""C:\Program Files\Google\Chrome\Application\chrome.exe""#.to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_linux_path() {
+        let command = r#""C:\Program Files\Mozilla Firefox\firefox.exe" -osint"#;
+        assert_eq!(executable(command).as_deref(), Some(r"C:\Program Files\Mozilla Firefox\firefox.exe"));
+        assert_eq!(
+            linux_path(&executable(command).unwrap()),
+            Some(PathBuf::from("/mnt/c/Program Files/Mozilla Firefox/firefox.exe"))
+        );
+        assert_eq!(linux_path(r"\\server\share\chrome.exe"), None);
+    }
+}
@@ -0,0 +1,72 @@
+//! `browsers.aio`, the functions of the module as coroutines for asyncio. Discovery and launching
+//! run in the default executor of the event loop without holding the GIL, so a scan that takes
+//! seconds doesn't block other tasks.
+
+use crate::{Patterns, PyBrowser};
+use pyo3::IntoPyObjectExt;
+use pyo3::prelude::*;
+use pyo3::types::PyCFunction;
+use std::sync::Mutex;
+
+pub(crate) fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = m.py();
+    let aio = PyModule::new(py, "aio")?;
+    aio.add_function(wrap_pyfunction!(list, &aio)?)?;
+    aio.add_function(wrap_pyfunction!(get, &aio)?)?;
+    aio.add_function(wrap_pyfunction!(launch, &aio)?)?;
+    m.add_submodule(&aio)?;
+    // lets `import browsers.aio` find the submodule
+    py.import("sys")?.getattr("modules")?.set_item("browsers.aio", aio)?;
+    Ok(())
+}
+
+/// Returns a future of the running event loop for the result of `work`, which is run without the
+/// GIL in the loop's default executor. `asyncio.run()` waits for the executor before returning, so
+/// no work is left running while the interpreter shuts down.
+fn spawn_future<T, F>(py: Python<'_>, work: F) -> PyResult<Bound<'_, PyAny>>
+where
+    T: for<'py> IntoPyObject<'py> + Send + 'static,
+    F: FnOnce() -> PyResult<T> + Send + 'static,
+{
+    // the executor calls it once, but a Python function has to be callable any number of times
+    let work = Mutex::new(Some(work));
+    let call = PyCFunction::new_closure(py, None, None, move |args, _| {
+        let py = args.py();
+        let work = work.lock().unwrap().take().expect("the work is only run once");
+        py.allow_threads(work)?.into_py_any(py)
+    })?;
+    let event_loop = py.import("asyncio")?.call_method0("get_running_loop")?;
+    event_loop.call_method1("run_in_executor", (py.None(), call))
+}
+
+/// Lists installed browsers like `browsers()`, but as a list.
+#[pyfunction(signature = (r#type=None, version=None, exclude=None))]
+fn list(
+    py: Python<'_>,
+    r#type: Option<Patterns>,
+    version: Option<String>,
+    exclude: Option<Patterns>,
+) -> PyResult<Bound<'_, PyAny>> {
+    spawn_future(py, move || {
+        let browsers = crate::all(r#type, version, exclude)?;
+        Ok(browsers.browsers.map(PyBrowser).collect::<Vec<_>>())
+    })
+}
+
+/// Returns the information for the provided browser key like `browsers.get()`.
+#[pyfunction(signature = (browser, version="*".to_string()))]
+fn get(py: Python<'_>, browser: String, version: String) -> PyResult<Bound<'_, PyAny>> {
+    spawn_future(py, move || crate::get(browser, &version))
+}
+
+/// Launches a web browser like `browsers.launch()`.
+#[pyfunction(signature = (browser, version=None, url=None, args=None))]
+fn launch(
+    py: Python<'_>,
+    browser: String,
+    version: Option<String>,
+    url: Option<String>,
+    args: Option<Vec<String>>,
+) -> PyResult<Bound<'_, PyAny>> {
+    spawn_future(py, move || crate::launch(browser, version, url, args))
+}
@@ -1,67 +1,386 @@
 use pyo3::Bound;
+use pyo3::IntoPyObjectExt;
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyTimeoutError, PyValueError};
 use pyo3::prelude::*;
 use pyo3::types::PyDict;
-use rsbrowsers::{Browser, BrowserFinder};
-use std::convert::Infallible;
+use rsbrowsers::{Browser, BrowserFinder, DefaultBrowserChange, Error, LaunchOptions, LaunchedBrowser, SchemeHandler};
+use std::ffi::OsStr;
+use std::path::PathBuf;
+use std::process::ExitStatus;
+use std::time::Duration;
 
+mod aio;
+
+create_exception!(browsers, BrowserNotFoundError, PyException, "No installed browser matches the query.");
+create_exception!(browsers, DiscoveryError, PyException, "The installed browsers could not be scanned.");
+create_exception!(browsers, LaunchError, PyException, "The browser process could not be started.");
+
+fn to_py_err(error: Error) -> PyErr {
+    match error {
+        Error::Discovery(_) => DiscoveryError::new_err(error.to_string()),
+        Error::Pattern(_) | Error::VersionReq(_) => PyValueError::new_err(error.to_string()),
+    }
+}
+
+fn launch_to_py_err(error: rsbrowsers::LaunchError) -> PyErr {
+    match error {
+        rsbrowsers::LaunchError::NotFound => BrowserNotFoundError::new_err(error.to_string()),
+        rsbrowsers::LaunchError::Discovery(error) => to_py_err(error),
+        _ => LaunchError::new_err(error.to_string()),
+    }
+}
+
+/// An installed browser.
+#[pyclass(name = "Browser", frozen, eq, hash)]
+#[derive(PartialEq, Eq, Hash)]
 struct PyBrowser(Browser);
 
-impl<'py> IntoPyObject<'py> for PyBrowser {
-    type Target = PyDict;
-    type Output = Bound<'py, PyDict>;
-    type Error = Infallible;
+#[pymethods]
+impl PyBrowser {
+    /// The browser key, e.g. "chrome" or "firefox-nightly".
+    #[getter]
+    fn browser_type(&self) -> &str {
+        &self.0.browser_type
+    }
 
-    fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
-        let dict = PyDict::new(py);
+    /// The executable or app bundle. Undecodable bytes are kept as surrogate escapes, like `os.fsdecode`.
+    #[getter]
+    fn path(&self) -> &OsStr {
+        self.0.path.as_os_str()
+    }
 
-        dict.set_item("display_name", self.0.display_name)
-            .expect("Cannot set display_name.");
-        dict.set_item("path", self.0.path).expect("Cannot set path.");
-        dict.set_item("browser_type", self.0.browser_type)
-            .expect("Cannot set browser_type.");
-        dict.set_item("version", self.0.version).expect("Cannot set version.");
+    /// The Exec line of the Linux desktop entry the browser is launched with, None if there is none.
+    #[getter]
+    fn exec(&self) -> Option<&str> {
+        self.0.exec.as_deref()
+    }
+
+    #[getter]
+    fn display_name(&self) -> &str {
+        &self.0.display_name
+    }
+
+    /// The version as reported by the browser, empty if unknown.
+    #[getter]
+    fn version(&self) -> &str {
+        &self.0.version
+    }
+
+    /// The version as read from the executable or bundle, None if it is the same as `version`.
+    #[getter]
+    fn raw_version(&self) -> Option<&str> {
+        self.0.raw_version.as_deref()
+    }
+
+    /// The version of Chromium the browser is built on, only the major one for Edge and Brave, None for
+    /// other engines and browsers that don't reveal it.
+    #[getter]
+    fn chromium_version(&self) -> Option<String> {
+        self.0.chromium_version().map(|version| version.to_string())
+    }
+
+    /// Where the browser was found, e.g. "system" or "ms-store".
+    #[getter]
+    fn install_source(&self) -> String {
+        self.0.install_source.to_string()
+    }
 
+    /// The bundle identifier, registry subkey or desktop entry ID, None if unknown.
+    #[getter]
+    fn source_id(&self) -> Option<&str> {
+        self.0.source_id.as_deref()
+    }
+
+    /// The app bundle, package directory or desktop entry file, None if unknown.
+    #[getter]
+    fn source_path(&self) -> Option<String> {
+        self.0.source_path.as_ref().map(|path| path.to_string_lossy().to_string())
+    }
+
+    /// The package manager's identifier for the install, e.g. the Homebrew cask "firefox", None if unknown.
+    #[getter]
+    fn package(&self) -> Option<&str> {
+        self.0.package.as_deref()
+    }
+
+    /// Launches this browser without looking for it again and returns a handle to its process.
+    #[pyo3(signature = (url=None, args=None))]
+    fn launch(&self, url: Option<String>, args: Option<Vec<String>>) -> PyResult<PyLaunchedBrowser> {
+        let mut options = LaunchOptions::new().args(args.unwrap_or_default());
+        if let Some(url) = url {
+            options = options.url(url);
+        }
+        let launched = self.0.launch(&options).map_err(launch_to_py_err)?;
+        Ok(PyLaunchedBrowser { launched, browser: self.0.clone() })
+    }
+
+    /// Returns the attributes as a dict, as returned by earlier versions.
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("browser_type", &self.0.browser_type)?;
+        dict.set_item("path", self.path())?;
+        dict.set_item("exec", self.exec())?;
+        dict.set_item("display_name", &self.0.display_name)?;
+        dict.set_item("version", &self.0.version)?;
+        dict.set_item("raw_version", self.raw_version())?;
+        dict.set_item("install_source", self.0.install_source.to_string())?;
+        dict.set_item("source_id", self.source_id())?;
+        dict.set_item("source_path", self.source_path())?;
+        dict.set_item("package", self.package())?;
         Ok(dict)
     }
+
+    fn __repr__(&self) -> String {
+        format!("Browser(browser_type={:?}, version={:?}, path={:?})", self.0.browser_type, self.0.version, self.0.path)
+    }
+}
+
+/// The seconds `close()` waits for, also when leaving a `with` block.
+const CLOSE_TIMEOUT: f64 = 10.0;
+
+/// Returns the exit code like `subprocess`, negative for the signal that ended the process on Unix.
+fn returncode(status: ExitStatus) -> i32 {
+    #[cfg(unix)]
+    if let Some(signal) = std::os::unix::process::ExitStatusExt::signal(&status) {
+        return -signal;
+    }
+    status.code().unwrap_or(-1)
+}
+
+/// Converts a timeout in seconds, raising `ValueError` for negative, infinite and NaN values.
+fn seconds(timeout: f64) -> PyResult<Duration> {
+    Duration::try_from_secs_f64(timeout).map_err(|e| PyValueError::new_err(format!("invalid timeout {timeout}: {e}")))
+}
+
+/// A browser started by `launch()` or `Browser.launch()`.
+///
+/// Used as a context manager, the browser is closed when the block exits.
+#[pyclass(name = "LaunchedBrowser")]
+struct PyLaunchedBrowser {
+    launched: LaunchedBrowser,
+    browser: Browser,
+}
+
+#[pymethods]
+impl PyLaunchedBrowser {
+    /// The process ID of the launched browser.
+    #[getter]
+    fn pid(&self) -> u32 {
+        self.launched.id()
+    }
+
+    /// The information of the launched browser.
+    #[getter]
+    fn browser(&self) -> PyBrowser {
+        PyBrowser(self.browser.clone())
+    }
+
+    /// Waits for the browser to exit and returns its exit code, raising `TimeoutError` if it is still
+    /// running after `timeout` seconds.
+    #[pyo3(signature = (timeout=None))]
+    fn wait(&mut self, py: Python<'_>, timeout: Option<f64>) -> PyResult<i32> {
+        let duration = timeout.map(seconds).transpose()?;
+        let launched = &mut self.launched;
+        let status = py.allow_threads(|| match duration {
+            Some(duration) => launched.wait_timeout(duration),
+            None => launched.wait().map(Some),
+        })?;
+        match status {
+            Some(status) => Ok(returncode(status)),
+            None => Err(PyTimeoutError::new_err(format!("the browser is still running after {timeout:?}s"))),
+        }
+    }
+
+    /// Kills the browser without letting it save its profile, see `close()`.
+    fn kill(&mut self) -> PyResult<()> {
+        Ok(self.launched.kill()?)
+    }
+
+    /// Asks the browser to quit, killing it if it is still running after `timeout` seconds, and returns its
+    /// exit code.
+    #[pyo3(signature = (timeout=CLOSE_TIMEOUT))]
+    fn close(&mut self, py: Python<'_>, timeout: f64) -> PyResult<i32> {
+        let timeout = seconds(timeout)?;
+        let launched = &mut self.launched;
+        let status = py.allow_threads(|| launched.close(timeout))?;
+        Ok(returncode(status))
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __exit__(
+        &mut self,
+        py: Python<'_>,
+        _exc_type: PyObject,
+        _exc_value: PyObject,
+        _traceback: PyObject,
+    ) -> PyResult<bool> {
+        self.close(py, CLOSE_TIMEOUT)?;
+        Ok(false)
+    }
+}
+
+/// An application other than a known browser that handles a URL scheme, see `handler_for()`.
+#[pyclass(name = "Application", frozen, eq, hash)]
+#[derive(PartialEq, Eq, Hash)]
+struct PyApplication {
+    id: String,
+    path: Option<PathBuf>,
+}
+
+#[pymethods]
+impl PyApplication {
+    /// The bundle ID on macOS, the ProgId on Windows or the desktop entry ID on Linux.
+    #[getter]
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// The executable or app bundle, None if it could not be found.
+    #[getter]
+    fn path(&self) -> Option<String> {
+        self.path.as_ref().map(|path| path.to_string_lossy().to_string())
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Application(id={:?}, path={:?})", self.id, self.path())
+    }
 }
 
-/// Iterates over installed browsers.
-#[pyfunction(name = "browsers")]
-fn all<'py>(py: Python<'py>) -> PyResult<Vec<PyBrowser>> {
-    let browsers = BrowserFinder::new().all().map(|browser| PyBrowser(browser)).collect();
-    Ok(browsers)
+/// One glob or a list of globs.
+#[derive(FromPyObject)]
+enum Patterns {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl Patterns {
+    fn into_vec(self) -> Vec<String> {
+        match self {
+            Patterns::One(pattern) => vec![pattern],
+            Patterns::Many(patterns) => patterns,
+        }
+    }
+}
+
+/// Iterates over the browsers found by `browsers()`. The scan completes before `browsers()` returns, so
+/// only the conversion of each browser is deferred.
+#[pyclass(name = "BrowserIterator")]
+struct PyBrowserIterator {
+    browsers: std::vec::IntoIter<Browser>,
+}
+
+#[pymethods]
+impl PyBrowserIterator {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(mut slf: PyRefMut<'_, Self>) -> Option<PyBrowser> {
+        slf.browsers.next().map(PyBrowser)
+    }
+}
+
+/// Iterates over installed browsers, optionally filtered by type and version.
+///
+/// `type` and `exclude` take a glob or a list of globs. `version` is a glob such as "120*" or a
+/// requirement such as ">=120". An invalid glob or requirement raises `ValueError`. All installed
+/// browsers are scanned before the first one is returned.
+#[pyfunction(name = "browsers", signature = (r#type=None, version=None, exclude=None))]
+fn all(r#type: Option<Patterns>, version: Option<String>, exclude: Option<Patterns>) -> PyResult<PyBrowserIterator> {
+    let mut finder = BrowserFinder::new()
+        .with_types(r#type.map(Patterns::into_vec).unwrap_or_default())
+        .exclude_types(exclude.map(Patterns::into_vec).unwrap_or_default());
+    if let Some(version) = version {
+        // requirements start with an operator, anything else is treated as a glob
+        finder = if version.starts_with(['<', '>', '=', '^', '~']) {
+            finder.with_version_req(&version)
+        } else {
+            finder.with_version(version)
+        };
+    }
+    let browsers = finder.try_all().map_err(to_py_err)?;
+    Ok(PyBrowserIterator { browsers })
 }
 
 /// Returns the information for the provided browser key.
 #[pyfunction(signature = (browser, version="*"))]
-fn get<'py>(py: Python<'py>, browser: String, version: &str) -> PyResult<Option<PyBrowser>> {
-    match BrowserFinder::new()
-        .with_type(browser)
-        .with_version(version.to_string())
-        .all()
-        .next()
+fn get(browser: String, version: &str) -> PyResult<Option<PyBrowser>> {
+    match BrowserFinder::new().with_type(browser).with_version(version.to_string()).try_all().map_err(to_py_err)?.next()
     {
         Some(browser) => Ok(Some(PyBrowser(browser))),
         None => Ok(None),
     }
 }
 
-/// Launches a web browser.
+/// Launches a web browser and returns a handle to its process.
 #[pyfunction(signature = (browser, version=None, url=None, args=None))]
-fn launch(browser: String, version: Option<String>, url: Option<String>, args: Option<Vec<String>>) {
+fn launch(
+    browser: String,
+    version: Option<String>,
+    url: Option<String>,
+    args: Option<Vec<String>>,
+) -> PyResult<PyLaunchedBrowser> {
     let mut finder = BrowserFinder::new().with_type(browser);
     if let Some(v) = version {
         finder = finder.with_version(v);
     }
-    let args = args.unwrap_or_else(|| vec![]);
-    finder.launch(args.as_slice());
+    let mut options = LaunchOptions::new().args(args.unwrap_or_default());
+    if let Some(url) = url {
+        options = options.url(url);
+    }
+    let (launched, browser) = finder.launch(&options).map_err(launch_to_py_err)?;
+    Ok(PyLaunchedBrowser { launched, browser })
+}
+
+/// Returns the system default browser, None if there is none or it is not installed.
+#[pyfunction(name = "default")]
+fn default_browser() -> Option<PyBrowser> {
+    rsbrowsers::default_browser().map(PyBrowser)
+}
+
+/// Returns the application that opens URLs of `scheme`, e.g. "mailto": a `Browser` if it is a known
+/// browser, an `Application` otherwise and None if nothing handles the scheme.
+#[pyfunction]
+fn handler_for(py: Python<'_>, scheme: &str) -> PyResult<PyObject> {
+    match rsbrowsers::handler_for_scheme(scheme) {
+        Some(SchemeHandler::Browser(browser)) => PyBrowser(browser).into_py_any(py),
+        Some(SchemeHandler::Application { id, path }) => PyApplication { id, path }.into_py_any(py),
+        None => Ok(py.None()),
+    }
+}
+
+/// Makes `browser` the default browser as far as the platform permits and returns what happened:
+/// "changed", "prompted" when macOS asks the user to confirm or "settings-opened" when Windows
+/// opens its Default Apps settings instead. Raises `OSError` if the default could not be set.
+#[pyfunction]
+fn set_default(browser: &PyBrowser) -> PyResult<&'static str> {
+    Ok(match rsbrowsers::set_default_browser(&browser.0)? {
+        DefaultBrowserChange::Changed => "changed",
+        DefaultBrowserChange::Prompted => "prompted",
+        DefaultBrowserChange::SettingsOpened => "settings-opened",
+    })
 }
 
 /// A Python module implemented in Rust.
 #[pymodule]
 fn browsers(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("BrowserNotFoundError", m.py().get_type::<BrowserNotFoundError>())?;
+    m.add("DiscoveryError", m.py().get_type::<DiscoveryError>())?;
+    m.add("LaunchError", m.py().get_type::<LaunchError>())?;
+    m.add_class::<PyBrowser>()?;
+    m.add_class::<PyBrowserIterator>()?;
+    m.add_class::<PyLaunchedBrowser>()?;
+    m.add_class::<PyApplication>()?;
     m.add_function(wrap_pyfunction!(all, m)?)?;
     m.add_function(wrap_pyfunction!(get, m)?)?;
     m.add_function(wrap_pyfunction!(launch, m)?)?;
+    m.add_function(wrap_pyfunction!(default_browser, m)?)?;
+    m.add_function(wrap_pyfunction!(handler_for, m)?)?;
+    m.add_function(wrap_pyfunction!(set_default, m)?)?;
+    aio::register(m)?;
     Ok(())
 }